@@ -10,12 +10,22 @@ use crate::exit_code::{
     ExitCodeError, bootstrap_error_exit_code, connect_error_exit_code, evm_util_error_exit_code,
 };
 use crate::opt::{ALPHA_NETWORK_ID, LOCAL_NETWORK_ID, MAIN_NETWORK_ID, NetworkId};
+use autonomi::client::ChunkBatchUploadState;
 use autonomi::client::config::ClientOperatingStrategy;
+use autonomi::client::quote::DataTypes;
+use autonomi::client::receipt_store::ReceiptStore;
 use autonomi::{BootstrapConfig, Client, ClientConfig, InitialPeersConfig, get_evm_network};
 use color_eyre::eyre::eyre;
 use indicatif::ProgressBar;
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 
+/// Directory of the client data dir where the proofs of payment are kept.
+const RECEIPTS_DIR: &str = "receipts";
+/// Directory where previous versions cached the payments of each uploaded file.
+const LEGACY_PAYMENTS_DIR: &str = "payments";
+
 /// Network connection context containing peer configuration and network ID
 pub struct NetworkContext {
     /// Configuration for connecting to peers
@@ -37,8 +47,18 @@ pub async fn connect_to_network(network_context: NetworkContext) -> Result<Clien
 
 pub async fn connect_to_network_with_config(
     network_context: NetworkContext,
-    operating_strategy: ClientOperatingStrategy,
+    mut operating_strategy: ClientOperatingStrategy,
 ) -> Result<Client, ExitCodeError> {
+    // keep the proofs of payment, so failed uploads are not paid for again when retried
+    if operating_strategy.receipt_store.is_none() {
+        match open_receipt_store() {
+            Ok(store) => operating_strategy.receipt_store = Some(Arc::new(store)),
+            Err(err) => {
+                warn!("Failed to open the receipt store, payments will not be reused: {err}")
+            }
+        }
+    }
+
     // TODO: got the progress_bar display after correct the ticking advance steps.
     // let progress_bar = ProgressBar::new_spinner();
     let progress_bar = ProgressBar::hidden();
@@ -112,3 +132,45 @@ pub async fn connect_to_network_with_config(
         }
     }
 }
+
+fn open_receipt_store() -> color_eyre::Result<ReceiptStore> {
+    let data_dir = crate::access::data_dir::get_client_data_dir_path()?;
+    let store = ReceiptStore::open(data_dir.join(RECEIPTS_DIR))?;
+
+    let legacy_dir = data_dir.join(LEGACY_PAYMENTS_DIR);
+    if legacy_dir.is_dir() {
+        match migrate_legacy_payments(&store, &legacy_dir) {
+            Ok(migrated) => {
+                info!("Moved {migrated} cached payments to {:?}", store.dir());
+                if let Err(err) = std::fs::remove_dir_all(&legacy_dir) {
+                    warn!("Failed to remove the cached payments of {legacy_dir:?}: {err}");
+                }
+            }
+            Err(err) => warn!("Failed to move the cached payments of {legacy_dir:?}: {err}"),
+        }
+    }
+    Ok(store)
+}
+
+/// Move the chunk payments cached per file by previous versions to the receipt store.
+fn migrate_legacy_payments(store: &ReceiptStore, legacy_dir: &Path) -> color_eyre::Result<usize> {
+    let mut migrated = 0;
+    for entry in std::fs::read_dir(legacy_dir)? {
+        let path = entry?.path();
+        let state: ChunkBatchUploadState = match std::fs::read(&path)
+            .map_err(color_eyre::Report::from)
+            .and_then(|bytes| Ok(serde_json::from_slice(&bytes)?))
+        {
+            Ok(state) => state,
+            Err(err) => {
+                warn!("Skipping unreadable cached payment {path:?}: {err}");
+                continue;
+            }
+        };
+        if let Some(receipt) = state.payment {
+            store.save(DataTypes::Chunk, &receipt)?;
+            migrated += receipt.len();
+        }
+    }
+    Ok(migrated)
+}
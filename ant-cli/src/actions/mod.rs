@@ -9,7 +9,9 @@
 mod connect;
 mod download;
 mod progress_bar;
+mod verify;
 
 pub use connect::{NetworkContext, connect_to_network, connect_to_network_with_config};
 pub use download::download;
 pub use progress_bar::get_progress_bar;
+pub use verify::verify;
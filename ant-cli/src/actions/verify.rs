@@ -0,0 +1,166 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::exit_code::{self, ExitCodeError, INVALID_INPUT_EXIT_CODE, UNDER_REPLICATED_EXIT_CODE};
+use autonomi::{
+    Client,
+    chunk::DataMapChunk,
+    client::{CLOSE_GROUP_SIZE, payment::PaymentOption},
+    data::{DataAddress, RepairOutcome, ReplicationStatus, VerifyReport},
+};
+use color_eyre::{Section, eyre::eyre};
+
+/// Data to verify, public or private.
+enum Target {
+    Public(DataAddress),
+    Private(DataMapChunk),
+}
+
+pub async fn verify(
+    addr: &str,
+    client: &Client,
+    repair: Option<PaymentOption>,
+) -> Result<(), ExitCodeError> {
+    let targets = resolve_targets(addr, client).await?;
+
+    let mut unhealthy_chunks = 0;
+    let mut last_error = None;
+    for (name, target) in targets {
+        println!("Verifying {name}...");
+        let res = match &target {
+            Target::Public(address) => client.data_verify_public(address, repair.clone()).await,
+            Target::Private(datamap) => client.data_verify(datamap, repair.clone()).await,
+        };
+        match res {
+            Ok(report) => unhealthy_chunks += print_report(&report),
+            Err(err) => {
+                eprintln!("Failed to verify {name}: {err}");
+                error!("Failed to verify {name} of {addr}: {err}");
+                last_error = Some(err);
+            }
+        }
+    }
+
+    if let Some(err) = last_error {
+        let exit_code = exit_code::get_error_exit_code(&err);
+        return Err((
+            eyre!(err).wrap_err("Errors while verifying data"),
+            exit_code,
+        ));
+    }
+    if unhealthy_chunks > 0 {
+        let err = eyre!("{unhealthy_chunks} chunks of {addr} are not fully replicated");
+        let err = if repair.is_none() {
+            err.with_suggestion(|| "Run the command again with --repair to store them again")
+        } else {
+            err
+        };
+        return Err((err, UNDER_REPLICATED_EXIT_CODE));
+    }
+
+    info!("All chunks of {addr} are fully replicated");
+    println!("All chunks of {addr} are fully replicated");
+    Ok(())
+}
+
+/// The data at the address, followed by the files it holds if it is an archive.
+async fn resolve_targets(
+    addr: &str,
+    client: &Client,
+) -> Result<Vec<(String, Target)>, ExitCodeError> {
+    if let Ok(address) = DataAddress::from_hex(addr) {
+        println!("Input supplied was a public address");
+        let mut targets = vec![(addr.to_string(), Target::Public(address))];
+        if let Ok(archive) = client.archive_get_public(&address).await {
+            targets.extend(archive.iter().map(|(path, file_addr, _)| {
+                (path.display().to_string(), Target::Public(*file_addr))
+            }));
+        }
+        return Ok(targets);
+    }
+
+    if let Ok(datamap) = crate::user_data::get_local_private_archive_access(addr) {
+        println!("Input supplied was a private address");
+        let archive = client.archive_get(&datamap).await.map_err(|e| {
+            let exit_code = exit_code::get_error_exit_code(&e);
+            (
+                eyre!(e).wrap_err("Failed to fetch Private Archive from address"),
+                exit_code,
+            )
+        })?;
+        let mut targets = vec![(addr.to_string(), Target::Private(datamap))];
+        targets.extend(archive.iter().map(|(path, access, _)| {
+            (path.display().to_string(), Target::Private(access.clone()))
+        }));
+        return Ok(targets);
+    }
+
+    if let Ok(datamap) = crate::user_data::get_local_private_file_access(addr) {
+        println!("Input supplied was a private file datamap");
+        return Ok(vec![(addr.to_string(), Target::Private(datamap))]);
+    }
+
+    if let Ok(datamap) = DataMapChunk::from_hex(addr) {
+        println!("Input supplied was a datamap Chunk");
+        return Ok(vec![(datamap.address(), Target::Private(datamap))]);
+    }
+
+    Err((eyre!("Failed to parse data address {addr}")
+            .with_suggestion(|| "Public addresses look like this: 0037cfa13eae4393841cbc00c3a33cade0f98b8c1f20826e5c51f8269e7b09d7")
+            .with_suggestion(|| "Private addresses look like this: 1358645341480028172")
+            .with_suggestion(|| "You can also use a hex encoded DataMap directly here")
+            .with_suggestion(|| "Try the `file list` command to get addresses you have access to"),
+        INVALID_INPUT_EXIT_CODE
+    ))
+}
+
+/// Print the chunks that are not fully replicated, returning how many remain so.
+fn print_report(report: &VerifyReport) -> usize {
+    let under_replicated = report.under_replicated().count();
+    let lost = report.lost().count();
+    println!(
+        "  {} chunks: {} healthy, {under_replicated} under-replicated, {lost} lost",
+        report.chunks.len(),
+        report.chunks.len() - under_replicated - lost,
+    );
+
+    let mut unhealthy = 0;
+    for chunk in &report.chunks {
+        let address = chunk.address.to_hex();
+        let held = chunk.holders.len();
+        match (chunk.status(), &chunk.repair) {
+            (ReplicationStatus::Healthy, _) => {}
+            (ReplicationStatus::Lost, _) => {
+                println!("  - {address}: lost, no node holds it");
+                unhealthy += 1;
+            }
+            (ReplicationStatus::UnderReplicated, Some(RepairOutcome::Repaired { cost })) => {
+                println!(
+                    "  - {address}: held by {held}/{CLOSE_GROUP_SIZE} nodes, repaired for {cost}"
+                );
+            }
+            (ReplicationStatus::UnderReplicated, Some(RepairOutcome::AlreadyStored)) => {
+                println!(
+                    "  - {address}: held by {held}/{CLOSE_GROUP_SIZE} nodes, not stored again as its close group holds it"
+                );
+                unhealthy += 1;
+            }
+            (ReplicationStatus::UnderReplicated, Some(RepairOutcome::Failed(reason))) => {
+                println!(
+                    "  - {address}: held by {held}/{CLOSE_GROUP_SIZE} nodes, repair failed: {reason}"
+                );
+                unhealthy += 1;
+            }
+            (ReplicationStatus::UnderReplicated, None) => {
+                println!("  - {address}: held by {held}/{CLOSE_GROUP_SIZE} nodes");
+                unhealthy += 1;
+            }
+        }
+    }
+    unhealthy
+}
@@ -17,6 +17,7 @@ mod wallet;
 use crate::actions::NetworkContext;
 use crate::args::max_fee_per_gas::MaxFeePerGasParam;
 use crate::opt::{NetworkId, Opt};
+use autonomi::AttoTokens;
 use autonomi::networking::Quorum;
 use clap::{Args, CommandFactory as _, Subcommand, error::ErrorKind};
 use color_eyre::Result;
@@ -99,11 +100,15 @@ pub enum FileCmd {
         /// Single-node payment (default) pays only one node with 3x that amount, saving gas fees.
         #[arg(long)]
         disable_single_node_payment: bool,
+        /// Show which chunks are already stored on the network or duplicated in the upload,
+        /// and what is left to upload, per file.
+        #[arg(long)]
+        detailed: bool,
     },
 
     /// Upload a file and pay for it. Data on the Network is private by default.
     Upload {
-        /// The file to upload.
+        /// The file to upload. Use `-` to read the data from stdin.
         file: String,
         /// Upload the file as public. Everyone can see public data on the Network.
         #[arg(short, long)]
@@ -125,10 +130,53 @@ pub enum FileCmd {
         /// Data is stored on 5 nodes regardless of payment mode.
         #[arg(long)]
         disable_single_node_payment: bool,
+        /// Exact size in bytes of the data read from stdin when uploading `-`.
+        /// The upload is aborted before paying anything if stdin yields a different number of bytes.
+        #[arg(long)]
+        stdin_size: Option<u64>,
+        /// Maximum amount of tokens to pay in total for the upload, e.g. `0.5`.
+        /// The upload stops before paying anything above it, e.g. during price spikes.
+        #[arg(long)]
+        max_cost: Option<AttoTokens>,
+        /// Maximum amount of tokens to pay for a single chunk.
+        /// Chunks priced above it are quoted again a few times before giving up.
+        #[arg(long)]
+        max_chunk_cost: Option<AttoTokens>,
         #[command(flatten)]
         transaction_opt: TransactionOpt,
     },
 
+    /// Prepare the payment of a file upload, to be signed with a wallet kept on another machine.
+    ///
+    /// Writes a payment request bundle holding the quotes and the transactions to sign and send:
+    /// first the `approve` transaction, then each of the payment `transactions`.
+    /// Once sent, complete the upload with `ant file finalize` on this machine.
+    Prepare {
+        /// The file to upload.
+        file: String,
+        /// Upload the file as public. Everyone can see public data on the Network.
+        #[arg(short, long)]
+        public: bool,
+        /// Where to write the payment request bundle. Defaults to `<file name>.payment.json`.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Use standard payment mode instead of single-node payment (default).
+        /// Standard mode pays 3 nodes individually, which costs more in gas fees.
+        #[arg(long)]
+        disable_single_node_payment: bool,
+    },
+
+    /// Upload a file prepared with `ant file prepare`, once its payment transactions were sent.
+    ///
+    /// The payments made by the transactions are checked on-chain before anything is uploaded.
+    Finalize {
+        /// The payment request bundle written by `ant file prepare`.
+        bundle: PathBuf,
+        /// The hashes of the payment transactions sent for the bundle's `transactions`.
+        #[arg(long = "tx-hash", required = true)]
+        tx_hashes: Vec<String>,
+    },
+
     /// Download a file from the given address.
     Download {
         /// The address of the file to download.
@@ -154,6 +202,20 @@ pub enum FileCmd {
         cache_dir: Option<PathBuf>,
     },
 
+    /// Verify that every chunk of the data at the given address is held by its whole close group.
+    ///
+    /// Archives are verified along with each of their files.
+    /// Exits with an error if any chunk is lost or remains under-replicated.
+    Verify {
+        /// The address of the data to verify.
+        addr: String,
+        /// Store the under-replicated chunks again, paying with the wallet if needed.
+        #[arg(long)]
+        repair: bool,
+        #[command(flatten)]
+        transaction_opt: TransactionOpt,
+    },
+
     /// List previous uploads
     List {
         /// List files in archives. Requires network connection.
@@ -471,13 +533,25 @@ pub async fn handle_subcommand(opt: Opt) -> Result<()> {
             FileCmd::Cost {
                 file,
                 disable_single_node_payment,
-            } => file::cost(&file, network_context, disable_single_node_payment).await,
+                detailed,
+            } => {
+                file::cost(
+                    &file,
+                    network_context,
+                    disable_single_node_payment,
+                    detailed,
+                )
+                .await
+            }
             FileCmd::Upload {
                 file,
                 public,
                 no_archive,
                 retry_failed,
                 disable_single_node_payment,
+                stdin_size,
+                max_cost,
+                max_chunk_cost,
                 transaction_opt,
             } => {
                 if let Err((err, exit_code)) = file::upload(
@@ -488,6 +562,9 @@ pub async fn handle_subcommand(opt: Opt) -> Result<()> {
                     transaction_opt.max_fee_per_gas,
                     retry_failed,
                     disable_single_node_payment,
+                    stdin_size,
+                    max_cost,
+                    max_chunk_cost,
                 )
                 .await
                 {
@@ -497,6 +574,37 @@ pub async fn handle_subcommand(opt: Opt) -> Result<()> {
                     Ok(())
                 }
             }
+            FileCmd::Prepare {
+                file,
+                public,
+                output,
+                disable_single_node_payment,
+            } => {
+                if let Err((err, exit_code)) = file::prepare(
+                    &file,
+                    public,
+                    output,
+                    network_context,
+                    disable_single_node_payment,
+                )
+                .await
+                {
+                    eprintln!("{err:?}");
+                    std::process::exit(exit_code);
+                } else {
+                    Ok(())
+                }
+            }
+            FileCmd::Finalize { bundle, tx_hashes } => {
+                if let Err((err, exit_code)) =
+                    file::finalize(&bundle, &tx_hashes, network_context).await
+                {
+                    eprintln!("{err:?}");
+                    std::process::exit(exit_code);
+                } else {
+                    Ok(())
+                }
+            }
             FileCmd::Download {
                 addr,
                 dest_file,
@@ -528,6 +636,25 @@ pub async fn handle_subcommand(opt: Opt) -> Result<()> {
                     Ok(())
                 }
             }
+            FileCmd::Verify {
+                addr,
+                repair,
+                transaction_opt,
+            } => {
+                if let Err((err, exit_code)) = file::verify(
+                    &addr,
+                    repair,
+                    network_context,
+                    transaction_opt.max_fee_per_gas,
+                )
+                .await
+                {
+                    eprintln!("{err:?}");
+                    std::process::exit(exit_code);
+                } else {
+                    Ok(())
+                }
+            }
             FileCmd::List { verbose } => {
                 if let Err((err, exit_code)) = file::list(network_context, verbose).await {
                     eprintln!("{err:?}");
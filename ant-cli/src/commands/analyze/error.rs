@@ -23,6 +23,7 @@ pub enum AnalysisErrorDisplay {
     Configuration,
     UnrecognizedDataMap,
     TooLargeForMemory,
+    Interrupted,
 }
 
 impl AnalysisErrorDisplay {
@@ -47,6 +48,7 @@ impl AnalysisErrorDisplay {
                 GetError::Configuration(_) => Self::Configuration,
                 GetError::UnrecognizedDataMap(_) => Self::UnrecognizedDataMap,
                 GetError::TooLargeForMemory => Self::TooLargeForMemory,
+                GetError::Interrupted(_) => Self::Interrupted,
             },
         }
     }
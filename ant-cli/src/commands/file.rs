@@ -6,28 +6,63 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::access::cached_payments;
 use crate::actions::NetworkContext;
 use crate::args::max_fee_per_gas::{MaxFeePerGasParam, get_max_fee_per_gas_from_opt_param};
-use crate::exit_code::{ExitCodeError, FEES_ERROR, IO_ERROR, upload_exit_code};
+use crate::exit_code::{
+    ExitCodeError, FEES_ERROR, INVALID_INPUT_EXIT_CODE, IO_ERROR, payment_request_exit_code,
+    put_error_exit_code, upload_exit_code,
+};
 use crate::utils::collect_upload_summary;
 use crate::wallet::load_wallet;
+use autonomi::chunk::DataMapChunk;
 use autonomi::client::PutError;
 use autonomi::client::analyze::Analysis;
+use autonomi::client::cost_budget::CostBudget;
+use autonomi::client::external_signer::{
+    PaymentRequest, PaymentRequestError, TxHash, data_content_addrs, encrypt_data,
+};
 use autonomi::client::payment::PaymentOption;
-use autonomi::files::UploadError;
+use autonomi::client::quote::DataTypes;
+use autonomi::data::DataAddress;
+use autonomi::files::{Metadata, PrivateArchive, PublicArchive, UploadError};
 use autonomi::networking::{Quorum, RetryStrategy};
-use autonomi::{Client, ClientOperatingStrategy, PaymentMode, TransactionConfig};
+use autonomi::{
+    AttoTokens, Bytes, Client, ClientOperatingStrategy, PaymentMode, TransactionConfig, XorName,
+};
 use color_eyre::Section;
 use color_eyre::eyre::{Context, Result, eyre};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const MAX_ADDRESSES_TO_PRINT: usize = 3;
 
+/// Number of times chunks priced above `--max-chunk-cost` are quoted again before giving up.
+const MAX_COST_REQUOTE_ATTEMPTS: usize = 3;
+
+/// File argument standing for the data read from stdin.
+const STDIN_FILE: &str = "-";
+
+/// Extension of the payment request bundles written by `ant file prepare`.
+const BUNDLE_EXTENSION: &str = "payment.json";
+
+/// A file upload prepared with `ant file prepare`, waiting for its payment.
+#[derive(Serialize, Deserialize)]
+struct UploadBundle {
+    /// The file to upload, read again when finalizing
+    file: PathBuf,
+    /// The metadata of the file in the uploaded archive, as when the file was prepared
+    metadata: Metadata,
+    public: bool,
+    payment_request: PaymentRequest,
+}
+
 pub async fn cost(
     file: &str,
     network_context: NetworkContext,
     use_standard_payment: bool,
+    detailed: bool,
 ) -> Result<()> {
     let mut client = crate::actions::connect_to_network(network_context)
         .await
@@ -41,6 +76,10 @@ pub async fn cost(
         println!("🎯 Using single node payment mode (default - saves gas fees)");
     }
 
+    if detailed {
+        return cost_detailed(&client, file).await;
+    }
+
     println!("Getting upload cost...");
     info!("Calculating cost for file: {file}");
     let cost = client
@@ -54,6 +93,44 @@ pub async fn cost(
     Ok(())
 }
 
+async fn cost_detailed(client: &Client, file: &str) -> Result<()> {
+    println!("Planning upload...");
+    info!("Planning upload for file: {file}");
+    let plan = client
+        .upload_plan(Path::new(file))
+        .await
+        .wrap_err("Failed to plan the upload for file")?;
+
+    println!("Upload plan for: {file}");
+    for (path, file_plan) in plan.files.iter() {
+        println!(
+            "  {}: {} chunks, {} already stored or duplicated, {} bytes to upload",
+            path.display(),
+            file_plan.chunks,
+            file_plan.deduplicated,
+            file_plan.bytes_to_upload
+        );
+    }
+    println!("Total chunks: {}", plan.total_chunks);
+    println!("Already stored chunks: {}", plan.already_stored_chunks);
+    println!("Duplicate chunks: {}", plan.duplicate_chunks);
+    println!(
+        "Chunks to upload: {} ({} bytes)",
+        plan.chunks_to_upload(),
+        plan.bytes_to_upload
+    );
+    println!("Total cost: {}", plan.estimated_cost);
+    info!(
+        "Upload plan for file: {file}: {} chunks, {} already stored, {} to upload, cost {}",
+        plan.total_chunks,
+        plan.already_stored_chunks,
+        plan.chunks_to_upload(),
+        plan.estimated_cost
+    );
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn upload(
     file: &str,
     public: bool,
@@ -62,8 +139,23 @@ pub async fn upload(
     max_fee_per_gas_param: Option<MaxFeePerGasParam>,
     retry_failed: u64,
     use_standard_payment: bool,
+    stdin_size: Option<u64>,
+    max_cost: Option<AttoTokens>,
+    max_chunk_cost: Option<AttoTokens>,
 ) -> Result<(), ExitCodeError> {
-    let config = ClientOperatingStrategy::new();
+    let mut config = ClientOperatingStrategy::new();
+    if max_cost.is_some() || max_chunk_cost.is_some() {
+        let mut budget = CostBudget::new().with_requote_attempts(MAX_COST_REQUOTE_ATTEMPTS);
+        if let Some(max_cost) = max_cost {
+            println!("💰 Paying at most {max_cost} for the upload");
+            budget = budget.with_max_total(max_cost);
+        }
+        if let Some(max_chunk_cost) = max_chunk_cost {
+            println!("💰 Paying at most {max_chunk_cost} per chunk");
+            budget = budget.with_max_per_address(max_chunk_cost);
+        }
+        config.cost_budget = Some(budget);
+    }
 
     let mut client =
         crate::actions::connect_to_network_with_config(network_context, config).await?;
@@ -91,12 +183,9 @@ pub async fn upload(
             .map_err(|err| (err, FEES_ERROR))?;
     wallet.set_transaction_config(TransactionConfig { max_fee_per_gas });
 
-    let payment = if let Ok(Some(receipt)) = cached_payments::load_payment_for_file(file) {
-        println!("Using cached payment: no need to re-pay");
-        PaymentOption::Receipt(receipt)
-    } else {
-        PaymentOption::Wallet(wallet)
-    };
+    let from_stdin = file == STDIN_FILE;
+    // the proofs of payments made by previous attempts are reused from the receipt store
+    let payment = PaymentOption::Wallet(wallet);
 
     let event_receiver = client.enable_client_events();
     let (upload_summary_thread, upload_completed_tx) = collect_upload_summary(event_receiver);
@@ -108,35 +197,32 @@ pub async fn upload(
     );
 
     let dir_path = PathBuf::from(file);
-    let name = dir_path
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or(file.to_string());
+    let name = if from_stdin {
+        "stdin".to_string()
+    } else {
+        dir_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or(file.to_string())
+    };
 
-    // upload dir
-    let not_single_file = !dir_path.is_file();
-    let (archive_addr, local_addr) =
-        match upload_dir(&client, dir_path, public, no_archive, payment).await {
-            Ok((a, l)) => (a, l),
-            Err(UploadError::PutError(PutError::Batch(upload_state))) => {
-                let res = cached_payments::save_payment(file, &upload_state);
-                println!("Cached payment to local disk for {file}: {res:?}");
-                let exit_code =
-                    upload_exit_code(&UploadError::PutError(PutError::Batch(Default::default())));
-                return Err((
-                    eyre!(UploadError::PutError(PutError::Batch(upload_state)))
-                        .wrap_err("Failed to upload file".to_string()),
-                    exit_code,
-                ));
-            }
-            Err(err) => {
-                let exit_code = upload_exit_code(&err);
-                return Err((
-                    eyre!(err).wrap_err("Failed to upload file".to_string()),
-                    exit_code,
-                ));
-            }
-        };
+    // upload dir, or the data from stdin as a single file
+    let not_single_file = !from_stdin && !dir_path.is_file();
+    let upload_result = if from_stdin {
+        upload_stdin(&client, public, stdin_size, payment).await
+    } else {
+        upload_dir(&client, dir_path, public, no_archive, payment).await
+    };
+    let (archive_addr, local_addr) = match upload_result {
+        Ok((a, l)) => (a, l),
+        Err(err) => {
+            let exit_code = upload_exit_code(&err);
+            return Err((
+                eyre!(err).wrap_err("Failed to upload file".to_string()),
+                exit_code,
+            ));
+        }
+    };
 
     // wait for upload to complete
     if let Err(e) = upload_completed_tx.send(()) {
@@ -208,6 +294,258 @@ pub async fn upload(
     Ok(())
 }
 
+pub async fn prepare(
+    file: &str,
+    public: bool,
+    output: Option<PathBuf>,
+    network_context: NetworkContext,
+    use_standard_payment: bool,
+) -> Result<(), ExitCodeError> {
+    let path = PathBuf::from(file);
+    if !path.is_file() {
+        return Err((
+            eyre!("{file} is not a file, only single files can be prepared for payment"),
+            INVALID_INPUT_EXIT_CODE,
+        ));
+    }
+    let data = std::fs::read(&path)
+        .wrap_err(format!("Failed to read {file}"))
+        .map_err(|err| (err, IO_ERROR))?;
+    let metadata = file_metadata(&path)
+        .wrap_err(format!("Failed to read the metadata of {file}"))
+        .map_err(|err| (err, IO_ERROR))?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or(file.to_string());
+    let (_, content_addrs) =
+        archived_content(file, &name, Bytes::from(data), public, metadata.clone())?;
+
+    let mut client = crate::actions::connect_to_network(network_context).await?;
+    if use_standard_payment {
+        client = client.with_payment_mode(PaymentMode::Standard);
+        println!("💳 Using standard payment mode (pays 3 nodes individually)");
+    }
+
+    println!("Getting quotes for {} chunks...", content_addrs.len());
+    let payment_request = client
+        .create_payment_request(DataTypes::Chunk, content_addrs.into_iter())
+        .await
+        .map_err(|err| {
+            let exit_code = payment_request_exit_code(&err);
+            (
+                eyre!(err).wrap_err("Failed to prepare the payment"),
+                exit_code,
+            )
+        })?;
+
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("{name}.{BUNDLE_EXTENSION}")));
+    let transactions = payment_request.transactions.len();
+    let total_cost = payment_request.total_cost;
+    let bundle = UploadBundle {
+        file: std::path::absolute(&path).unwrap_or(path),
+        metadata,
+        public,
+        payment_request,
+    };
+    let json = serde_json::to_string_pretty(&bundle)
+        .wrap_err("Failed to serialize the payment request")
+        .map_err(|err| (err, IO_ERROR))?;
+    std::fs::write(&output, json)
+        .wrap_err(format!("Failed to write {}", output.display()))
+        .map_err(|err| (err, IO_ERROR))?;
+
+    println!("Payment request written to: {}", output.display());
+    println!("Total cost: {total_cost} in {transactions} transactions");
+    println!(
+        "Sign and send the `approve` transaction, then each of the `transactions`, with the paying wallet."
+    );
+    println!(
+        "Then run: ant file finalize {} --tx-hash <hash> (once per transaction)",
+        output.display()
+    );
+    Ok(())
+}
+
+pub async fn finalize(
+    bundle_path: &Path,
+    tx_hashes: &[String],
+    network_context: NetworkContext,
+) -> Result<(), ExitCodeError> {
+    let tx_hashes = tx_hashes
+        .iter()
+        .map(|hash| TxHash::from_str(hash).map_err(|err| eyre!("Invalid tx hash {hash}: {err}")))
+        .collect::<Result<Vec<_>>>()
+        .map_err(|err| (err, INVALID_INPUT_EXIT_CODE))?;
+    let json = std::fs::read_to_string(bundle_path)
+        .wrap_err(format!("Failed to read {}", bundle_path.display()))
+        .map_err(|err| (err, IO_ERROR))?;
+    let bundle: UploadBundle = serde_json::from_str(&json)
+        .wrap_err("Failed to parse the payment request bundle")
+        .map_err(|err| (err, INVALID_INPUT_EXIT_CODE))?;
+
+    let request_error = |err: PaymentRequestError| {
+        let exit_code = payment_request_exit_code(&err);
+        (
+            eyre!(err).wrap_err("Failed to finalize the upload"),
+            exit_code,
+        )
+    };
+
+    let file = bundle.file.display().to_string();
+    let data = Bytes::from(
+        std::fs::read(&bundle.file)
+            .wrap_err(format!("Failed to read {file}"))
+            .map_err(|err| (err, IO_ERROR))?,
+    );
+    let name = bundle
+        .file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or(file.clone());
+    let (archive, content_addrs) =
+        archived_content(&file, &name, data.clone(), bundle.public, bundle.metadata)?;
+    if !bundle.payment_request.matches_manifest(&content_addrs) {
+        let (err, exit_code) = request_error(PaymentRequestError::ManifestMismatch);
+        return Err((
+            err.with_suggestion(|| "The file changed since it was prepared, prepare it again"),
+            exit_code,
+        ));
+    }
+
+    let client = crate::actions::connect_to_network(network_context).await?;
+    println!("Checking the payment transactions...");
+    let receipt = client
+        .payment_request_receipt(&bundle.payment_request, &tx_hashes)
+        .await
+        .map_err(request_error)?;
+
+    println!("Uploading data to network...");
+    let put_error = |err: PutError| {
+        let exit_code = put_error_exit_code(&err);
+        (eyre!(err).wrap_err("Failed to upload file"), exit_code)
+    };
+    // the file is uploaded along with an archive holding it, as `ant file upload` does for directories
+    if bundle.public {
+        let (_, addr) = client
+            .data_put_public(data, receipt.clone().into())
+            .await
+            .map_err(put_error)?;
+        println!("  - {name:?}: {:?}", addr.to_hex());
+        let (_, archive_addr) = client
+            .data_put_public(archive, receipt.into())
+            .await
+            .map_err(put_error)?;
+        println!("Successfully uploaded: {file}");
+        println!("At address: {}", archive_addr.to_hex());
+        crate::user_data::write_local_public_file_archive(archive_addr.to_hex(), &name)
+            .wrap_err("Failed to save file to local user data")
+            .map_err(|err| (err, IO_ERROR))?;
+    } else {
+        let (_, data_map) = client
+            .data_put(data, receipt.clone().into())
+            .await
+            .map_err(put_error)?;
+        println!("  - {name:?}: {:?}", data_map.to_hex());
+        let (_, archive_data_map) = client
+            .data_put(archive, receipt.into())
+            .await
+            .map_err(put_error)?;
+        println!("Successfully uploaded: {file}");
+        println!("At address: {}", archive_data_map.address());
+        crate::user_data::write_local_private_file_archive(
+            archive_data_map.to_hex(),
+            archive_data_map.address(),
+            &name,
+        )
+        .wrap_err("Failed to save file to local user data")
+        .map_err(|err| (err, IO_ERROR))?;
+    }
+    Ok(())
+}
+
+/// The metadata of the file in its archive, read from the file system.
+fn file_metadata(path: &Path) -> std::io::Result<Metadata> {
+    let fs_metadata = std::fs::metadata(path)?;
+    let unix_time = |time: std::io::Result<SystemTime>| {
+        time.ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_secs())
+    };
+    Ok(Metadata {
+        created: unix_time(fs_metadata.created()),
+        modified: unix_time(fs_metadata.modified()),
+        size: fs_metadata.len(),
+        extra: None,
+    })
+}
+
+/// The serialized archive holding the file, and the content addresses of both the file and the
+/// archive, to pay for and upload.
+fn archived_content(
+    file: &str,
+    name: &str,
+    data: Bytes,
+    public: bool,
+    metadata: Metadata,
+) -> Result<(Bytes, Vec<(XorName, usize)>), ExitCodeError> {
+    let encryption_error = |err| {
+        let exit_code = put_error_exit_code(&PutError::SelfEncryption(err));
+        (eyre!("Failed to encrypt {file}"), exit_code)
+    };
+    let (data_map_chunk, chunks) = encrypt_data(data).map_err(encryption_error)?;
+    let mut content_addrs: Vec<(XorName, usize)> = chunks
+        .iter()
+        .map(|chunk| (*chunk.name(), chunk.size()))
+        .collect();
+
+    let path = PathBuf::from(name);
+    let archive = if public {
+        content_addrs.push((*data_map_chunk.name(), data_map_chunk.size()));
+        let mut archive = PublicArchive::new();
+        archive.add_file(path, DataAddress::new(*data_map_chunk.name()), metadata);
+        archive.to_bytes()
+    } else {
+        // the data map of private data is kept in the archive
+        let mut archive = PrivateArchive::new();
+        archive.add_file(path, DataMapChunk(data_map_chunk), metadata);
+        archive.to_bytes()
+    }
+    .map_err(|err| {
+        let err = PutError::Serialization(format!("Failed to serialize archive: {err:?}"));
+        let exit_code = put_error_exit_code(&err);
+        (eyre!(err), exit_code)
+    })?;
+
+    content_addrs.extend(data_content_addrs(archive.clone(), public).map_err(encryption_error)?);
+    Ok((archive, content_addrs))
+}
+
+/// Uploads the data read from stdin to the network as a single file.
+/// Returns the same addresses as [`upload_dir`] does for a single file.
+async fn upload_stdin(
+    client: &Client,
+    public: bool,
+    stdin_size: Option<u64>,
+    payment_option: PaymentOption,
+) -> Result<(String, String), UploadError> {
+    let stdin = tokio::io::stdin();
+
+    if public {
+        let (_, addr) = client
+            .data_put_stream_public(stdin, stdin_size, payment_option)
+            .await?;
+        println!("  - stdin: {:?}", addr.to_hex());
+        Ok(("no-archive".to_string(), addr.to_hex()))
+    } else {
+        let (_, private_datamap) = client
+            .data_put_stream(stdin, stdin_size, payment_option)
+            .await?;
+        println!("  - stdin: {:?}", private_datamap.to_hex());
+        Ok((private_datamap.to_hex(), private_datamap.address()))
+    }
+}
+
 /// Uploads a file or directory to the network and prints the content and addresses.
 /// Single files are uploaded without an archive, directories are uploaded with an archive.
 /// The no_archive argument can be used to skip the archive upload.
@@ -221,10 +559,21 @@ async fn upload_dir(
 ) -> Result<(String, String), UploadError> {
     let is_single_file = dir_path.is_file();
 
+    let with_archive = !no_archive && !is_single_file;
+
+    // the files and their archive are uploaded as one operation, sharing the cost budget
     if public {
-        let (_, public_archive) = client
-            .dir_content_upload_public(dir_path, payment_option.clone())
-            .await?;
+        let (public_archive, archive_addr) = if with_archive {
+            let (_, archive, addr) = client
+                .dir_upload_public_with_archive(dir_path, payment_option)
+                .await?;
+            (archive, Some(addr))
+        } else {
+            let (_, archive) = client
+                .dir_content_upload_public(dir_path, payment_option)
+                .await?;
+            (archive, None)
+        };
 
         let mut addrs = vec![];
         for (file_path, addr, _meta) in public_archive.iter() {
@@ -232,22 +581,23 @@ async fn upload_dir(
             addrs.push(addr.to_hex());
         }
 
-        if no_archive || is_single_file {
-            if addrs.len() > MAX_ADDRESSES_TO_PRINT {
-                Ok(("no-archive".to_string(), "multiple addresses".to_string()))
-            } else {
-                Ok(("no-archive".to_string(), addrs.join(", ")))
-            }
-        } else {
-            let (_, addr) = client
-                .archive_put_public(&public_archive, payment_option.clone())
-                .await?;
+        if let Some(addr) = archive_addr {
             Ok((addr.to_hex(), addr.to_hex()))
+        } else if addrs.len() > MAX_ADDRESSES_TO_PRINT {
+            Ok(("no-archive".to_string(), "multiple addresses".to_string()))
+        } else {
+            Ok(("no-archive".to_string(), addrs.join(", ")))
         }
     } else {
-        let (_, private_archive) = client
-            .dir_content_upload(dir_path, payment_option.clone())
-            .await?;
+        let (private_archive, archive_datamap) = if with_archive {
+            let (_, archive, datamap) = client
+                .dir_upload_with_archive(dir_path, payment_option)
+                .await?;
+            (archive, Some(datamap))
+        } else {
+            let (_, archive) = client.dir_content_upload(dir_path, payment_option).await?;
+            (archive, None)
+        };
 
         let mut addrs = vec![];
         for (file_path, private_datamap, _meta) in private_archive.iter() {
@@ -255,25 +605,20 @@ async fn upload_dir(
             addrs.push(private_datamap.to_hex());
         }
 
-        if no_archive || is_single_file {
-            if addrs.len() > MAX_ADDRESSES_TO_PRINT {
-                Ok(("no-archive".to_string(), "multiple addresses".to_string()))
-            } else if is_single_file && addrs.len() == 1 {
-                // For single private files, return both full hex and short address
-                if let Some((_, private_datamap, _)) = private_archive.iter().next() {
-                    Ok((private_datamap.to_hex(), private_datamap.address()))
-                } else {
-                    // This should not happen given the conditions, but handle gracefully
-                    Ok(("no-archive".to_string(), addrs.join(", ")))
-                }
+        if let Some(private_datamap) = archive_datamap {
+            Ok((private_datamap.to_hex(), private_datamap.address()))
+        } else if addrs.len() > MAX_ADDRESSES_TO_PRINT {
+            Ok(("no-archive".to_string(), "multiple addresses".to_string()))
+        } else if is_single_file && addrs.len() == 1 {
+            // For single private files, return both full hex and short address
+            if let Some((_, private_datamap, _)) = private_archive.iter().next() {
+                Ok((private_datamap.to_hex(), private_datamap.address()))
             } else {
+                // This should not happen given the conditions, but handle gracefully
                 Ok(("no-archive".to_string(), addrs.join(", ")))
             }
         } else {
-            let (_, private_datamap) = client
-                .archive_put(&private_archive, payment_option.clone())
-                .await?;
-            Ok((private_datamap.to_hex(), private_datamap.address()))
+            Ok(("no-archive".to_string(), addrs.join(", ")))
         }
     }
 }
@@ -315,6 +660,29 @@ pub async fn download(
     crate::actions::download(addr, dest_path, &client).await
 }
 
+pub async fn verify(
+    addr: &str,
+    repair: bool,
+    network_context: NetworkContext,
+    max_fee_per_gas_param: Option<MaxFeePerGasParam>,
+) -> Result<(), ExitCodeError> {
+    let client = crate::actions::connect_to_network(network_context).await?;
+
+    let payment = if repair {
+        let mut wallet = load_wallet(client.evm_network()).map_err(|err| (err, IO_ERROR))?;
+        let max_fee_per_gas =
+            get_max_fee_per_gas_from_opt_param(max_fee_per_gas_param, client.evm_network())
+                .map_err(|err| (err, FEES_ERROR))?;
+        wallet.set_transaction_config(TransactionConfig { max_fee_per_gas });
+        println!("🔧 Repair mode enabled - under-replicated chunks will be stored again");
+        Some(PaymentOption::Wallet(wallet))
+    } else {
+        None
+    };
+
+    crate::actions::verify(addr, &client, payment).await
+}
+
 pub async fn list(network_context: NetworkContext, verbose: bool) -> Result<(), ExitCodeError> {
     let mut config = ClientOperatingStrategy::new();
     config.chunks.get_quorum = Quorum::One;
@@ -10,7 +10,10 @@
 
 use autonomi::{
     BootstrapError,
-    client::{ConnectError, GetError, PutError, analyze::AnalysisError, payment::PayError},
+    client::{
+        ConnectError, GetError, PutError, analyze::AnalysisError,
+        external_signer::PaymentRequestError, payment::PayError,
+    },
     files::{DownloadError, UploadError},
 };
 use color_eyre::eyre::Report;
@@ -21,6 +24,8 @@ pub const IO_ERROR: i32 = 12;
 const NETWORK_ERROR: i32 = 13;
 const PROTOCOL_ERROR: i32 = 14;
 const SELF_ENCRYPTION_ERROR: i32 = 15;
+/// Some chunks of the verified data are lost or held by only part of their close group.
+pub(crate) const UNDER_REPLICATED_EXIT_CODE: i32 = 39;
 pub const FEES_ERROR: i32 = 62;
 
 pub type ExitCodeError = (Report, i32);
@@ -31,6 +36,7 @@ pub(crate) fn upload_exit_code(err: &UploadError) -> i32 {
         UploadError::IoError(_) => IO_ERROR,
         UploadError::PutError(err) => put_error_exit_code(err),
         UploadError::Encryption(_) => SELF_ENCRYPTION_ERROR,
+        UploadError::Journal(_) => IO_ERROR,
     }
 }
 
@@ -44,9 +50,24 @@ pub(crate) fn download_exit_code(err: &DownloadError) -> i32 {
 pub(crate) fn pay_error_exit_code(err: &PayError) -> i32 {
     match err {
         PayError::EvmWalletNetworkMismatch => 21,
-        PayError::EvmWalletError(_) => 22,
+        PayError::EvmWalletError(_)
+        | PayError::BatchedPaymentFailed(_)
+        | PayError::BatchedPaymentInterrupted => 22,
         PayError::SelfEncryption(_) => SELF_ENCRYPTION_ERROR,
         PayError::Cost(_) => 23,
+        PayError::BudgetExceeded { .. } => 24,
+        PayError::PriceCeilingExceeded { .. } => 25,
+    }
+}
+
+pub(crate) fn payment_request_exit_code(err: &PaymentRequestError) -> i32 {
+    match err {
+        PaymentRequestError::Quotes(put_error) => put_error_exit_code(put_error),
+        PaymentRequestError::Calldata(_) | PaymentRequestError::Transaction(_) => 26,
+        PaymentRequestError::UnsupportedVersion(_) => SERIALIZATION_ERROR,
+        PaymentRequestError::EvmNetworkMismatch { .. } => 21,
+        PaymentRequestError::UnpaidQuotes { .. } | PaymentRequestError::InvalidPayment { .. } => 27,
+        PaymentRequestError::ManifestMismatch => 28,
     }
 }
 
@@ -62,6 +83,7 @@ pub(crate) fn get_error_exit_code(err: &GetError) -> i32 {
         GetError::Configuration(_) => 35,
         GetError::UnrecognizedDataMap(_) => 31,
         GetError::TooLargeForMemory => 31,
+        GetError::Interrupted(_) => 38,
     }
 }
 
@@ -90,6 +112,7 @@ pub(crate) fn put_error_exit_code(err: &PutError) -> i32 {
         PutError::Wallet(_) => 42,
         PutError::Batch(_) => 44,
         PutError::PayeesMissing => 45,
+        PutError::Interrupted { .. } => 46,
     }
 }
 
@@ -6,9 +6,11 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::actions::get_progress_bar;
 use autonomi::client::{Amount, ClientEvent, UploadSummary};
+use indicatif::ProgressBar;
 
-/// Collects upload summary from the event receiver.
+/// Collects upload summary from the event receiver, showing the progress of the stored chunks.
 /// Send a signal to the returned sender to stop collecting and to return the result via the join handle.
 pub fn collect_upload_summary(
     mut event_receiver: tokio::sync::mpsc::Receiver<ClientEvent>,
@@ -21,6 +23,7 @@ pub fn collect_upload_summary(
         let mut tokens_spent: Amount = Amount::from(0);
         let mut record_count = 0;
         let mut records_already_paid = 0;
+        let progress_bar = get_progress_bar(0).ok();
 
         loop {
             tokio::select! {
@@ -31,6 +34,7 @@ pub fn collect_upload_summary(
                             record_count += upload_summary.records_paid;
                             records_already_paid += upload_summary.records_already_paid;
                         }
+                        Some(event) => track_upload_progress(progress_bar.as_ref(), &event),
                         None => break,
                     }
                 }
@@ -46,8 +50,12 @@ pub fn collect_upload_summary(
                     record_count += upload_summary.records_paid;
                     records_already_paid += upload_summary.records_already_paid;
                 }
+                event => track_upload_progress(progress_bar.as_ref(), &event),
             }
         }
+        if let Some(progress_bar) = &progress_bar {
+            progress_bar.finish_and_clear();
+        }
 
         UploadSummary {
             tokens_spent,
@@ -58,3 +66,19 @@ pub fn collect_upload_summary(
 
     (stats_thread, upload_completed_tx)
 }
+
+/// Advances the upload progress bar: its length grows with the chunks to pay for,
+/// and it moves forward as chunks get stored.
+fn track_upload_progress(progress_bar: Option<&ProgressBar>, event: &ClientEvent) {
+    let Some(progress_bar) = progress_bar else {
+        return;
+    };
+    match event {
+        ClientEvent::QuotesReceived { to_pay, .. } => progress_bar.inc_length(*to_pay as u64),
+        ClientEvent::ChunkStored { .. } => progress_bar.inc(1),
+        ClientEvent::ChunkRetried { address, .. } => {
+            progress_bar.println(format!("Retrying chunk {address:?}"))
+        }
+        _ => {}
+    }
+}
@@ -69,6 +69,12 @@ pub enum PutValidationError {
     #[error("Rejected outdated record: with counter {counter}, expected any above {expected}")]
     OutdatedRecordCounter { counter: u64, expected: u64 },
 
+    #[error("Rejected strict update: with counter {counter}, expected exactly {expected}")]
+    UnexpectedRecordCounter { counter: u64, expected: u64 },
+
+    #[error("Strict updates are only supported for scratchpads sent by clients: {0:?}")]
+    UnsupportedStrictUpdate(PrettyPrintRecordKey<'static>),
+
     #[error("Scratchpad signature is invalid")]
     InvalidScratchpadSignature,
 
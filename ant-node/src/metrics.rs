@@ -228,6 +228,8 @@ enum PutRecordErrorType {
     PaymentVerificationFailed,
     OversizedChunk,
     OutdatedRecordCounter,
+    UnexpectedRecordCounter,
+    UnsupportedStrictUpdate,
     InvalidScratchpadSignature,
     ScratchpadTooBig,
     EmptyGraphEntry,
@@ -260,6 +262,10 @@ impl From<&crate::PutValidationError> for PutRecordErrorType {
             }
             crate::PutValidationError::OversizedChunk(_, _) => Self::OversizedChunk,
             crate::PutValidationError::OutdatedRecordCounter { .. } => Self::OutdatedRecordCounter,
+            crate::PutValidationError::UnexpectedRecordCounter { .. } => {
+                Self::UnexpectedRecordCounter
+            }
+            crate::PutValidationError::UnsupportedStrictUpdate(_) => Self::UnsupportedStrictUpdate,
             crate::PutValidationError::InvalidScratchpadSignature => {
                 Self::InvalidScratchpadSignature
             }
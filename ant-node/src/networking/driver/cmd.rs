@@ -250,6 +250,12 @@ impl SwarmDriver {
                             );
                             return Err(NetworkError::InCorrectRecordHeader);
                         }
+                        RecordKind::StrictUpdate(_) => {
+                            error!(
+                                "Record {record_key:?} of a strict update shall not be stored locally."
+                            );
+                            return Err(NetworkError::InCorrectRecordHeader);
+                        }
                     },
                     Err(err) => {
                         error!("For record {record_key:?}, failed to parse record_header {err:?}");
@@ -475,7 +475,7 @@ impl SwarmDriver {
                 })
                 .collect();
 
-            stats.sort_by(|a, b| b.1.cmp(&a.1)); // Sort by count in descending order
+            stats.sort_by_key(|stat| std::cmp::Reverse(stat.1)); // Sort by count in descending order
 
             trace!("SwarmDriver Handling Statistics: {:?}", stats);
             // now we've logged, lets clear the stats from the btreemap
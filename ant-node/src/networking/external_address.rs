@@ -371,16 +371,14 @@ impl ExternalAddressManager {
                         address,
                         num_reports,
                         ip_address,
-                    } => {
-                        if *num_reports >= MAX_REPORTS_BEFORE_SWITCHING_IP {
-                            info!("Switching to new IP, adding confirmed address: {address:?}");
-                            swarm.add_external_address(address.clone());
-                            *state = ExternalAddressState::Confirmed {
-                                address: address.clone(),
-                                num_reports: *num_reports,
-                                ip_address: *ip_address,
-                            };
-                        }
+                    } if *num_reports >= MAX_REPORTS_BEFORE_SWITCHING_IP => {
+                        info!("Switching to new IP, adding confirmed address: {address:?}");
+                        swarm.add_external_address(address.clone());
+                        *state = ExternalAddressState::Confirmed {
+                            address: address.clone(),
+                            num_reports: *num_reports,
+                            ip_address: *ip_address,
+                        };
                     }
 
                     ExternalAddressState::Listener { address, .. } => {
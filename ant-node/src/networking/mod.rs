@@ -73,7 +73,7 @@ pub fn sort_peers_by_key<T>(
     }
 
     // Sort the vector of tuples by the distance.
-    peer_distances.sort_by(|a, b| a.2.cmp(&b.2));
+    peer_distances.sort_by_key(|(_, _, distance)| *distance);
 
     // Collect the sorted peers into a new vector.
     let sorted_peers: Vec<(PeerId, Addresses)> = peer_distances
@@ -906,6 +906,11 @@ impl RecordStore for NodeRecordStore {
                     RecordKind::DataWithPayment(_) => {
                         debug!("Record {record_key:?} with payment shall always be processed.");
                     }
+                    RecordKind::StrictUpdate(_) => {
+                        debug!(
+                            "Record {record_key:?} of a strict update shall always be processed."
+                        );
+                    }
                     // Shall not use wildcard, to avoid mis-match during enum update.
                     RecordKind::DataOnly(_) => {
                         // Chunk with existing key do not to be stored again.
@@ -1533,8 +1538,7 @@ mod tests {
         // now all failed records should be farther than the farthest stored record
         let mut sorted_stored_data = stored_data_at_end.iter().collect_vec();
 
-        sorted_stored_data
-            .sort_by(|(a, _), (b, _)| self_address.distance(a).cmp(&self_address.distance(b)));
+        sorted_stored_data.sort_by_key(|(a, _)| self_address.distance(a));
 
         // next assert that all records stored are closer than the next closest of the failed records
         if let Some((most_distant_data, _)) = sorted_stored_data.last() {
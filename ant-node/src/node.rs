@@ -734,6 +734,13 @@ impl Node {
                         ));
                         Err(ProtocolError::OutdatedRecordCounter { counter, expected })
                     }
+                    Err(PutValidationError::UnexpectedRecordCounter { counter, expected }) => {
+                        node.record_metrics(Marker::RecordRejected(
+                            &key,
+                            &PutValidationError::UnexpectedRecordCounter { counter, expected },
+                        ));
+                        Err(ProtocolError::UnexpectedRecordCounter { counter, expected })
+                    }
                     Err(err) => {
                         node.record_metrics(Marker::RecordRejected(&key, &err));
                         Err(ProtocolError::PutRecordFailed(format!("{err:?}")))
@@ -154,6 +154,7 @@ impl Node {
                         scratchpad,
                         record_key.clone(),
                         true,
+                        false,
                         Some(payment),
                     )
                     .await;
@@ -182,8 +183,10 @@ impl Node {
 
                 store_scratchpad_result
             }
-            RecordKind::DataOnly(DataTypes::Scratchpad) => {
+            RecordKind::DataOnly(DataTypes::Scratchpad)
+            | RecordKind::StrictUpdate(DataTypes::Scratchpad) => {
                 // make sure we already have this scratchpad locally, else reject it as first time upload needs payment
+                let strict = matches!(record_header.kind, RecordKind::StrictUpdate(_));
                 let key = record.key.clone();
                 let scratchpad = try_deserialize_record::<Scratchpad>(&record).map_err(|_| {
                     PutValidationError::InvalidRecord(
@@ -201,9 +204,15 @@ impl Node {
                 }
 
                 // store the scratchpad
-                self.validate_and_store_scratchpad_record(scratchpad, key, true, None)
+                self.validate_and_store_scratchpad_record(scratchpad, key, true, strict, None)
                     .await
             }
+            RecordKind::StrictUpdate(_) => {
+                warn!("Strict updates are only supported for scratchpads");
+                Err(PutValidationError::UnsupportedStrictUpdate(
+                    PrettyPrintRecordKey::from(&record.key).into_owned(),
+                ))
+            }
             RecordKind::DataOnly(DataTypes::GraphEntry) => {
                 // Transactions should always be paid for
                 error!("Transaction should not be validated at this point");
@@ -400,6 +409,13 @@ impl Node {
                     PrettyPrintRecordKey::from(&record.key).into_owned(),
                 ))
             }
+            // Strict updates only come from clients, replicated records being stored as data only
+            RecordKind::StrictUpdate(_) => {
+                warn!("Replicated record came as a strict update, which only clients can send");
+                Err(PutValidationError::UnsupportedStrictUpdate(
+                    PrettyPrintRecordKey::from(&record.key).into_owned(),
+                ))
+            }
             RecordKind::DataOnly(DataTypes::Chunk) => {
                 let chunk = try_deserialize_record::<Chunk>(&record).map_err(|_| {
                     PutValidationError::InvalidRecord(
@@ -428,7 +444,7 @@ impl Node {
                         PrettyPrintRecordKey::from(&record.key).into_owned(),
                     )
                 })?;
-                self.validate_and_store_scratchpad_record(scratchpad, key, false, None)
+                self.validate_and_store_scratchpad_record(scratchpad, key, false, false, None)
                     .await
             }
             RecordKind::DataOnly(DataTypes::GraphEntry) => {
@@ -543,11 +559,15 @@ impl Node {
     /// Check Counter: It MUST ensure that the new counter value is strictly greater than the currently stored value to prevent replay attacks.
     /// Verify Signature: It MUST use the public key to verify the BLS12-381 signature against the content hash and the counter.
     /// Accept or Reject: If all verifications succeed, the node MUST accept the packet and replace any previous version. Otherwise, it MUST reject the update.
+    ///
+    /// In `strict` mode, requested by clients with a [`RecordKind::StrictUpdate`], the new counter
+    /// MUST be exactly one above the current one, so concurrent writers of the same version fail.
     pub(crate) async fn validate_and_store_scratchpad_record(
         &self,
         scratchpad: Scratchpad,
         record_key: RecordKey,
         is_client_put: bool,
+        strict: bool,
         _payment: Option<ProofOfPayment>,
     ) -> Result<(), PutValidationError> {
         // owner PK is defined herein, so as long as record key and this match, we're good
@@ -578,15 +598,7 @@ impl Node {
                 debug!("Scratchpad at {addr:?} already exists locally, skip to success");
                 return Ok(());
             }
-            if local_pad.counter() >= scratchpad.counter() {
-                warn!(
-                    "Rejecting Scratchpad PUT with counter less than or equal to the current counter"
-                );
-                return Err(PutValidationError::OutdatedRecordCounter {
-                    counter: scratchpad.counter(),
-                    expected: local_pad.counter(),
-                });
-            }
+            check_scratchpad_counter(local_pad.counter(), scratchpad.counter(), strict)?;
         }
 
         // ensure data integrity
@@ -696,7 +708,7 @@ impl Node {
         // add local GraphEntries to the validated GraphEntries, turn to Vec
         let local_entries = self.get_local_graphentries(addr).await?;
         let existing_entry = local_entries.len();
-        validated_entries.extend(local_entries.into_iter());
+        validated_entries.extend(local_entries);
         let validated_entries: Vec<GraphEntry> = validated_entries.into_iter().collect();
 
         // No need to write to disk if nothing new.
@@ -1016,3 +1028,69 @@ impl Node {
         Ok(())
     }
 }
+
+/// Check the counter of a new version of a scratchpad against the one of the version stored locally.
+///
+/// The new counter must be above the local one, and in `strict` mode exactly one above it.
+fn check_scratchpad_counter(local: u64, new: u64, strict: bool) -> Result<(), PutValidationError> {
+    if local >= new {
+        warn!("Rejecting Scratchpad PUT with counter less than or equal to the current counter");
+        return Err(PutValidationError::OutdatedRecordCounter {
+            counter: new,
+            expected: local,
+        });
+    }
+    if strict {
+        // no counter can follow the maximum one
+        let expected = local
+            .checked_add(1)
+            .ok_or(PutValidationError::OutdatedRecordCounter {
+                counter: new,
+                expected: local,
+            })?;
+        if new != expected {
+            warn!("Rejecting strict Scratchpad PUT with counter not following the current counter");
+            return Err(PutValidationError::UnexpectedRecordCounter {
+                counter: new,
+                expected,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scratchpad_counter_check() {
+        assert!(check_scratchpad_counter(3, 4, false).is_ok());
+        assert!(check_scratchpad_counter(3, 7, false).is_ok());
+        assert!(matches!(
+            check_scratchpad_counter(3, 3, false),
+            Err(PutValidationError::OutdatedRecordCounter {
+                counter: 3,
+                expected: 3
+            })
+        ));
+
+        // strict updates must follow the local counter exactly
+        assert!(check_scratchpad_counter(3, 4, true).is_ok());
+        assert!(matches!(
+            check_scratchpad_counter(3, 7, true),
+            Err(PutValidationError::UnexpectedRecordCounter {
+                counter: 7,
+                expected: 4
+            })
+        ));
+        assert!(matches!(
+            check_scratchpad_counter(3, 2, true),
+            Err(PutValidationError::OutdatedRecordCounter { .. })
+        ));
+        assert!(matches!(
+            check_scratchpad_counter(u64::MAX, u64::MAX, true),
+            Err(PutValidationError::OutdatedRecordCounter { .. })
+        ));
+    }
+}
@@ -72,6 +72,16 @@ pub enum Error {
     PutRecordFailed(String),
     #[error("Outdated record: with counter {counter}, expected any above {expected}")]
     OutdatedRecordCounter { counter: u64, expected: u64 },
+    #[error("Unexpected record counter in strict update: {counter}, expected exactly {expected}")]
+    UnexpectedRecordCounter { counter: u64, expected: u64 },
+
+    // ---------- Shared scratchpad errors
+    /// The scratchpad data is not an envelope shared with readers
+    #[error("Scratchpad data is not shared with readers")]
+    ScratchpadNotShared,
+    /// The provided SecretKey is not one of the readers of the shared scratchpad
+    #[error("The key is not a reader of the shared scratchpad")]
+    ScratchpadNotAReader,
 
     // Dev Note: add new variants above this one for backward compatibility with older protocol versions
     // ---------- Unknown/fallback variant for retro compatibility
@@ -67,11 +67,17 @@ pub struct RecordHeader {
 pub enum RecordKind {
     DataOnly(DataTypes),
     DataWithPayment(DataTypes),
+    /// An update of mutable data, only to be stored if its counter is exactly one above the
+    /// counter of the stored version. Never stored as such, the stored record being `DataOnly`.
+    StrictUpdate(DataTypes),
 }
 
 /// Allowing 10 data types to be defined, leaving margin for future.
 pub const RECORD_KIND_PAYMENT_STARTING_INDEX: u32 = 10;
 
+/// Index of the first [`RecordKind::StrictUpdate`], after the kinds with payment.
+pub const RECORD_KIND_STRICT_UPDATE_STARTING_INDEX: u32 = 2 * RECORD_KIND_PAYMENT_STARTING_INDEX;
+
 impl Serialize for RecordKind {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -82,6 +88,9 @@ impl Serialize for RecordKind {
             Self::DataWithPayment(data_types) => {
                 RECORD_KIND_PAYMENT_STARTING_INDEX + data_types.get_index()
             }
+            Self::StrictUpdate(data_types) => {
+                RECORD_KIND_STRICT_UPDATE_STARTING_INDEX + data_types.get_index()
+            }
         };
         serializer.serialize_u32(index)
     }
@@ -93,18 +102,23 @@ impl<'de> Deserialize<'de> for RecordKind {
         D: serde::Deserializer<'de>,
     {
         let num = u32::deserialize(deserializer)?;
-        let data_type_index = if num < RECORD_KIND_PAYMENT_STARTING_INDEX {
-            num
-        } else {
-            num - RECORD_KIND_PAYMENT_STARTING_INDEX
-        };
-
-        if let Some(data_type) = DataTypes::from_index(data_type_index) {
+        let (kind, data_type_index): (fn(DataTypes) -> Self, u32) =
             if num < RECORD_KIND_PAYMENT_STARTING_INDEX {
-                Ok(Self::DataOnly(data_type))
+                (Self::DataOnly, num)
+            } else if num < RECORD_KIND_STRICT_UPDATE_STARTING_INDEX {
+                (
+                    Self::DataWithPayment,
+                    num - RECORD_KIND_PAYMENT_STARTING_INDEX,
+                )
             } else {
-                Ok(Self::DataWithPayment(data_type))
-            }
+                (
+                    Self::StrictUpdate,
+                    num - RECORD_KIND_STRICT_UPDATE_STARTING_INDEX,
+                )
+            };
+
+        if let Some(data_type) = DataTypes::from_index(data_type_index) {
+            Ok(kind(data_type))
         } else {
             Err(serde::de::Error::custom(format!(
                 "Unexpected index {num} for RecordKind variant",
@@ -158,9 +172,9 @@ impl RecordHeader {
     pub fn get_data_type(record: &Record) -> Result<DataTypes, Error> {
         let kind = Self::from_record(record)?.kind;
         match kind {
-            RecordKind::DataOnly(data_type) | RecordKind::DataWithPayment(data_type) => {
-                Ok(data_type)
-            }
+            RecordKind::DataOnly(data_type)
+            | RecordKind::DataWithPayment(data_type)
+            | RecordKind::StrictUpdate(data_type) => Ok(data_type),
         }
     }
 }
@@ -247,6 +261,12 @@ mod tests {
         .try_serialize()?;
         assert_eq!(pointer_with_payment.len(), RecordHeader::SIZE);
 
+        let scratchpad_strict_update = RecordHeader {
+            kind: RecordKind::StrictUpdate(DataTypes::Scratchpad),
+        }
+        .try_serialize()?;
+        assert_eq!(scratchpad_strict_update.len(), RecordHeader::SIZE);
+
         Ok(())
     }
 
@@ -261,6 +281,7 @@ mod tests {
             RecordKind::DataWithPayment(DataTypes::Scratchpad),
             RecordKind::DataOnly(DataTypes::Pointer),
             RecordKind::DataWithPayment(DataTypes::Pointer),
+            RecordKind::StrictUpdate(DataTypes::Scratchpad),
         ];
 
         for kind in kinds {
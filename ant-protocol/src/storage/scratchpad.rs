@@ -16,6 +16,37 @@ use std::mem::size_of;
 
 use xor_name::XorName;
 
+/// The content key of a shared scratchpad, encrypted to one of its readers
+#[derive(Clone, Serialize, Deserialize)]
+struct WrappedKey {
+    reader: PublicKey,
+    encrypted_key: Bytes,
+}
+
+/// Data of a shared scratchpad: the data is encrypted with a content key, which is in turn
+/// encrypted to each of the readers.
+#[derive(Clone, Serialize, Deserialize)]
+struct SharedEnvelope {
+    readers: Vec<WrappedKey>,
+    encrypted_data: Bytes,
+}
+
+/// This type wraps the envelope in a version marker, so the format can evolve.
+#[derive(Serialize, Deserialize)]
+#[non_exhaustive]
+enum SharedEnvelopeVersioned {
+    V0(SharedEnvelope),
+}
+
+impl SharedEnvelope {
+    fn from_bytes(bytes: &Bytes) -> Result<Self> {
+        let versioned: SharedEnvelopeVersioned =
+            rmp_serde::from_slice(bytes).map_err(|_| Error::ScratchpadNotShared)?;
+        let SharedEnvelopeVersioned::V0(envelope) = versioned;
+        Ok(envelope)
+    }
+}
+
 /// Scratchpad, a mutable space for encrypted data on the Network
 #[derive(Hash, Eq, PartialEq, PartialOrd, Ord, Clone, Serialize, Deserialize)]
 pub struct Scratchpad {
@@ -77,6 +108,64 @@ impl Scratchpad {
         }
     }
 
+    /// Creates a new instance of `Scratchpad` whose data can be read by the readers, but only
+    /// written by the owner.
+    ///
+    /// The data is encrypted with a fresh content key, which is encrypted to each reader. The owner
+    /// is always a reader. As each version gets its own content key, a reader left out of a new
+    /// version cannot read it, although it may have kept the previous ones.
+    pub fn new_shared(
+        owner: &SecretKey,
+        data_encoding: u64,
+        unencrypted_data: &Bytes,
+        readers: &[PublicKey],
+        counter: u64,
+    ) -> Result<Self> {
+        let content_key = SecretKey::random();
+        let encrypted_data = Bytes::from(
+            content_key
+                .public_key()
+                .encrypt(unencrypted_data)
+                .to_bytes(),
+        );
+
+        let owner_pk = owner.public_key();
+        let mut reader_keys = vec![owner_pk];
+        for reader in readers {
+            if !reader_keys.contains(reader) {
+                reader_keys.push(*reader);
+            }
+        }
+        let readers = reader_keys
+            .into_iter()
+            .map(|reader| WrappedKey {
+                reader,
+                encrypted_key: Bytes::from(reader.encrypt(content_key.to_bytes()).to_bytes()),
+            })
+            .collect();
+
+        let envelope = SharedEnvelopeVersioned::V0(SharedEnvelope {
+            readers,
+            encrypted_data,
+        });
+        let envelope_bytes =
+            Bytes::from(rmp_serde::to_vec(&envelope).map_err(|_| Error::RecordParsingFailed)?);
+        let addr = ScratchpadAddress::new(owner_pk);
+        let signature = owner.sign(Self::bytes_for_signature(
+            addr,
+            data_encoding,
+            &envelope_bytes,
+            counter,
+        ));
+        Ok(Self {
+            address: addr,
+            encrypted_data: envelope_bytes,
+            data_encoding,
+            counter,
+            signature,
+        })
+    }
+
     /// Create a new Scratchpad without provding the secret key
     /// It is the caller's responsibility to ensure the signature is valid (signs [`Scratchpad::bytes_for_signature`]) and the data is encrypted
     /// It is recommended to use the [`Scratchpad::new`] method instead when possible
@@ -167,6 +256,44 @@ impl Scratchpad {
         Ok(Bytes::from(bytes))
     }
 
+    /// Returns the data of a shared scratchpad, decrypted via the SecretKey of one of its readers
+    pub fn decrypt_shared_data(&self, reader: &SecretKey) -> Result<Bytes> {
+        let envelope = SharedEnvelope::from_bytes(&self.encrypted_data)?;
+        let reader_pk = reader.public_key();
+        let wrapped = envelope
+            .readers
+            .iter()
+            .find(|wrapped| wrapped.reader == reader_pk)
+            .ok_or(Error::ScratchpadNotAReader)?;
+
+        let key_cipher = Ciphertext::from_bytes(&wrapped.encrypted_key)
+            .map_err(|_| Error::ScratchpadCipherTextFailed)?;
+        let key_bytes: [u8; 32] = reader
+            .decrypt(&key_cipher)
+            .ok_or(Error::ScratchpadCipherTextInvalid)?
+            .try_into()
+            .map_err(|_| Error::ScratchpadCipherTextInvalid)?;
+        let content_key =
+            SecretKey::from_bytes(key_bytes).map_err(|_| Error::ScratchpadCipherTextInvalid)?;
+
+        let cipher = Ciphertext::from_bytes(&envelope.encrypted_data)
+            .map_err(|_| Error::ScratchpadCipherTextFailed)?;
+        let bytes = content_key
+            .decrypt(&cipher)
+            .ok_or(Error::ScratchpadCipherTextInvalid)?;
+        Ok(Bytes::from(bytes))
+    }
+
+    /// Returns the readers of a shared scratchpad, the owner first
+    pub fn shared_readers(&self) -> Result<Vec<PublicKey>> {
+        let envelope = SharedEnvelope::from_bytes(&self.encrypted_data)?;
+        Ok(envelope
+            .readers
+            .into_iter()
+            .map(|wrapped| wrapped.reader)
+            .collect())
+    }
+
     /// Returns the encrypted_data hash
     pub fn encrypted_data_hash(&self) -> XorName {
         XorName::from_content(&self.encrypted_data)
@@ -255,6 +382,35 @@ mod tests {
         assert_eq!(decrypted_data, raw_data);
     }
 
+    #[test]
+    fn test_shared_scratchpad_encryption() {
+        let owner = SecretKey::random();
+        let reader = SecretKey::random();
+        let stranger = SecretKey::random();
+        let raw_data = Bytes::from_static(b"data to be shared");
+        let scratchpad =
+            Scratchpad::new_shared(&owner, 42, &raw_data, &[reader.public_key()], 0).unwrap();
+        assert!(scratchpad.verify_signature());
+
+        assert_eq!(scratchpad.decrypt_shared_data(&reader).unwrap(), raw_data);
+        assert_eq!(scratchpad.decrypt_shared_data(&owner).unwrap(), raw_data);
+        assert_eq!(
+            scratchpad.decrypt_shared_data(&stranger),
+            Err(Error::ScratchpadNotAReader)
+        );
+        assert_eq!(
+            scratchpad.shared_readers().unwrap(),
+            vec![owner.public_key(), reader.public_key()]
+        );
+
+        // scratchpads encrypted to the owner only are not shared
+        let private = Scratchpad::new(&owner, 42, &raw_data, 0);
+        assert_eq!(
+            private.decrypt_shared_data(&owner),
+            Err(Error::ScratchpadNotShared)
+        );
+    }
+
     #[test]
     fn test_bls_determinism() {
         let secret_key = SecretKey::random();
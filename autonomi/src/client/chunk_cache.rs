@@ -7,12 +7,21 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::chunk::ChunkAddress;
+use crate::client::config::{ChunkCacheBackend, ClientOperatingStrategy};
 use ant_protocol::storage::Chunk;
 use bytes::Bytes;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::SystemTime;
+use xor_name::XorName;
 
 const CHUNK_CACHE_FOLDER: &str = "chunk_cache";
+const CHUNK_FILE_EXTENSION: &str = "chunk";
+
+/// Default maximum size of the cached chunks: 1 GiB.
+pub const DEFAULT_CHUNK_CACHE_MAX_SIZE: u64 = 1024 * 1024 * 1024;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ChunkCacheError {
@@ -22,6 +31,54 @@ pub enum ChunkCacheError {
     DirectoryCreation(String),
 }
 
+/// A cache of downloaded chunks, so that interrupted or repeated downloads don't fetch them again.
+///
+/// Select the implementation used by the client with [`crate::client::config::ChunkCacheBackend`].
+pub trait ChunkCache: Send + Sync + std::fmt::Debug {
+    /// Load a cached chunk, `None` if it is not cached.
+    fn load(&self, chunk_addr: &ChunkAddress) -> Option<Chunk>;
+
+    /// Store a chunk in the cache, evicting the least recently used chunks if the cache is full.
+    fn store(&self, chunk_addr: &ChunkAddress, chunk: &Chunk) -> Result<(), ChunkCacheError>;
+
+    /// Remove chunks from the cache.
+    fn remove(&self, chunk_addrs: &[ChunkAddress]) -> Result<(), ChunkCacheError>;
+
+    /// Total size in bytes of the cached chunks.
+    fn size(&self) -> u64;
+}
+
+/// Build the chunk cache selected by the strategy, `None` if caching is disabled or the cache can't be opened.
+/// A disk cache is taken from `disk_caches`, shared with the other clients caching in the same directory.
+pub(crate) fn chunk_cache_from_strategy(
+    strategy: &ClientOperatingStrategy,
+    disk_caches: &DiskChunkCaches,
+) -> Option<Arc<dyn ChunkCache>> {
+    if !strategy.chunk_cache_enabled {
+        return None;
+    }
+
+    let max_size = strategy.chunk_cache_max_size;
+    match &strategy.chunk_cache_backend {
+        ChunkCacheBackend::Disk => {
+            let cache = match &strategy.chunk_cache_dir {
+                Some(dir) => Ok(dir.clone()),
+                None => default_cache_dir(),
+            }
+            .and_then(|dir| disk_caches.open(dir, max_size));
+            match cache {
+                Ok(cache) => Some(cache),
+                Err(e) => {
+                    warn!("Failed to open the chunk cache, chunk caching is disabled: {e}");
+                    None
+                }
+            }
+        }
+        ChunkCacheBackend::Memory => Some(Arc::new(MemoryChunkCache::new(max_size))),
+        ChunkCacheBackend::Custom(cache) => Some(Arc::clone(cache)),
+    }
+}
+
 /// Get the default chunk cache directory for the Autonomi client
 pub fn default_cache_dir() -> Result<PathBuf, ChunkCacheError> {
     let mut cache_dir = dirs_next::data_dir().ok_or_else(|| {
@@ -35,98 +92,440 @@ pub fn default_cache_dir() -> Result<PathBuf, ChunkCacheError> {
     Ok(cache_dir)
 }
 
-/// Get the file path for a cached chunk
-fn chunk_file_path(cache_dir: PathBuf, chunk_addr: &ChunkAddress) -> PathBuf {
-    let chunk_hash = hex::encode(chunk_addr.xorname().0);
-    cache_dir.join(format!("{chunk_hash}.chunk"))
+/// The disk chunk caches of the clients attached to a [`crate::client::SharedNetwork`], by directory.
+///
+/// Clients caching in the same directory share one cache, so that their indexes of the cached chunks
+/// don't diverge and the evictions of one don't delete the files another still indexes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DiskChunkCaches(Arc<Mutex<HashMap<PathBuf, Arc<DiskChunkCache>>>>);
+
+impl DiskChunkCaches {
+    /// The cache of the directory, opened with `max_size` unless another client opened it already.
+    fn open(&self, dir: PathBuf, max_size: u64) -> Result<Arc<DiskChunkCache>, ChunkCacheError> {
+        let dir = std::path::absolute(&dir).unwrap_or(dir);
+        let mut caches = lock(&self.0);
+        if let Some(cache) = caches.get(&dir) {
+            if cache.max_size != max_size {
+                debug!(
+                    "Chunk cache at {} already opened with a maximum size of {} bytes",
+                    dir.display(),
+                    cache.max_size
+                );
+            }
+            return Ok(Arc::clone(cache));
+        }
+        let cache = Arc::new(DiskChunkCache::new(dir.clone(), max_size)?);
+        caches.insert(dir, Arc::clone(&cache));
+        Ok(cache)
+    }
+}
+
+/// Checks that a chunk loaded from the cache is the one expected at that address.
+fn verify_chunk(chunk_addr: &ChunkAddress, chunk: &Chunk) -> bool {
+    chunk.address() == chunk_addr
 }
 
-/// Check if a chunk is already cached
-pub fn is_chunk_cached(cache_dir: PathBuf, chunk_addr: &ChunkAddress) -> bool {
-    chunk_file_path(cache_dir, chunk_addr).exists()
+/// Least recently used bookkeeping of the cached chunks.
+#[derive(Debug, Default)]
+struct LruIndex {
+    /// Size and last use of each cached chunk
+    entries: HashMap<XorName, (u64, u64)>,
+    /// Cached chunks by last use
+    by_last_use: BTreeMap<u64, XorName>,
+    total_size: u64,
+    clock: u64,
 }
 
-/// Store a chunk in the cache
-pub fn store_chunk(
-    cache_dir: PathBuf,
-    chunk_addr: &ChunkAddress,
-    chunk: &Chunk,
-) -> Result<(), ChunkCacheError> {
-    // Create the cache directory if it doesn't exist
-    if !cache_dir.exists() {
-        fs::create_dir_all(&cache_dir).map_err(|e| {
-            ChunkCacheError::DirectoryCreation(format!(
-                "Failed to create cache directory {}: {}",
-                cache_dir.display(),
-                e
-            ))
-        })?;
+impl LruIndex {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Mark a chunk as the most recently used one.
+    fn touch(&mut self, name: &XorName) {
+        let now = self.tick();
+        if let Some((_, last_use)) = self.entries.get_mut(name) {
+            self.by_last_use.remove(last_use);
+            *last_use = now;
+            self.by_last_use.insert(now, *name);
+        }
     }
 
-    let chunk_file_path = chunk_file_path(cache_dir, chunk_addr);
+    /// Add or replace a chunk as the most recently used one.
+    fn insert(&mut self, name: XorName, size: u64) {
+        self.remove(&name);
+        let now = self.tick();
+        self.entries.insert(name, (size, now));
+        self.by_last_use.insert(now, name);
+        self.total_size += size;
+    }
 
-    // Write chunk data to file
-    fs::write(&chunk_file_path, chunk.value())?;
+    fn remove(&mut self, name: &XorName) {
+        if let Some((size, last_use)) = self.entries.remove(name) {
+            self.by_last_use.remove(&last_use);
+            self.total_size -= size;
+        }
+    }
 
-    debug!(
-        "Cached chunk {} at {}",
-        chunk_addr.to_hex(),
-        chunk_file_path.display()
-    );
-    Ok(())
+    /// Drop the least recently used chunks until the total size fits in `max_size`.
+    /// Returns the evicted chunks.
+    fn evict(&mut self, max_size: u64) -> Vec<XorName> {
+        let mut evicted = vec![];
+        while self.total_size > max_size {
+            let Some((_, name)) = self.by_last_use.pop_first() else {
+                break;
+            };
+            if let Some((size, _)) = self.entries.remove(&name) {
+                self.total_size -= size;
+            }
+            evicted.push(name);
+        }
+        evicted
+    }
 }
 
-/// Load a cached chunk
-pub fn load_chunk(
-    cache_dir: PathBuf,
-    chunk_addr: &ChunkAddress,
-) -> Result<Option<Chunk>, ChunkCacheError> {
-    let chunk_file_path = chunk_file_path(cache_dir, chunk_addr);
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // the guarded state is always left consistent, so a panic elsewhere doesn't invalidate it
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Chunk cache storing each chunk in a `<hash>.chunk` file of a directory.
+///
+/// The total size of the files is kept under a maximum, evicting the least recently used chunks.
+/// Chunks are verified against their address when loaded, corrupted files are deleted.
+#[derive(Debug)]
+pub struct DiskChunkCache {
+    dir: PathBuf,
+    max_size: u64,
+    index: Mutex<LruIndex>,
+}
+
+impl DiskChunkCache {
+    /// Open the cache in `dir`, indexing the chunks already there by modification time.
+    /// If they exceed `max_size`, the oldest ones are evicted right away.
+    pub fn new(dir: PathBuf, max_size: u64) -> Result<Self, ChunkCacheError> {
+        let mut existing = vec![];
+        if dir.exists() {
+            for entry in fs::read_dir(&dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some(CHUNK_FILE_EXTENSION) {
+                    continue;
+                }
+                let Some(name) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| hex::decode(stem).ok())
+                    .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                else {
+                    continue;
+                };
+                let metadata = entry.metadata()?;
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                existing.push((modified, XorName(name), metadata.len()));
+            }
+        }
+        existing.sort();
+
+        let mut index = LruIndex::default();
+        for (_, name, size) in existing {
+            index.insert(name, size);
+        }
+
+        let cache = Self {
+            dir,
+            max_size,
+            index: Mutex::new(index),
+        };
+        let evicted = lock(&cache.index).evict(max_size);
+        cache.delete_files(&evicted);
+        debug!(
+            "Opened chunk cache at {} holding {} bytes, evicted {} chunks",
+            cache.dir.display(),
+            cache.size(),
+            evicted.len()
+        );
+        Ok(cache)
+    }
 
-    if !chunk_file_path.exists() {
-        return Ok(None);
+    /// Open the cache in the default directory, see [`default_cache_dir`].
+    pub fn new_default(max_size: u64) -> Result<Self, ChunkCacheError> {
+        Self::new(default_cache_dir()?, max_size)
     }
 
-    match fs::read(&chunk_file_path) {
-        Ok(data) => {
-            let chunk = Chunk::new(Bytes::from(data));
-            debug!(
-                "Loaded cached chunk {} from {}",
+    /// Get the file path for a cached chunk
+    fn chunk_file_path(&self, name: &XorName) -> PathBuf {
+        let chunk_hash = hex::encode(name.0);
+        self.dir
+            .join(format!("{chunk_hash}.{CHUNK_FILE_EXTENSION}"))
+    }
+
+    fn delete_files(&self, names: &[XorName]) {
+        for name in names {
+            let path = self.chunk_file_path(name);
+            if let Err(e) = fs::remove_file(&path)
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                warn!("Failed to delete cached chunk {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+impl ChunkCache for DiskChunkCache {
+    fn load(&self, chunk_addr: &ChunkAddress) -> Option<Chunk> {
+        let name = chunk_addr.xorname();
+        let chunk_file_path = self.chunk_file_path(name);
+        let data = match fs::read(&chunk_file_path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!("Failed to read cached chunk {}: {}", chunk_addr.to_hex(), e);
+                return None;
+            }
+        };
+
+        let chunk = Chunk::new(Bytes::from(data));
+        if !verify_chunk(chunk_addr, &chunk) {
+            warn!(
+                "Cached chunk {} is corrupted, deleting {}",
                 chunk_addr.to_hex(),
                 chunk_file_path.display()
             );
-            Ok(Some(chunk))
+            lock(&self.index).remove(name);
+            self.delete_files(&[*name]);
+            return None;
         }
-        Err(e) => {
-            warn!("Failed to read cached chunk {}: {}", chunk_addr.to_hex(), e);
-            Ok(None)
+
+        lock(&self.index).touch(name);
+        // keep the recency across restarts, which index chunks by modification time
+        if let Err(e) = fs::File::options()
+            .write(true)
+            .open(&chunk_file_path)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+        {
+            debug!("Failed to update the modification time of a cached chunk: {e}");
         }
+
+        debug!(
+            "Loaded cached chunk {} from {}",
+            chunk_addr.to_hex(),
+            chunk_file_path.display()
+        );
+        Some(chunk)
     }
-}
 
-/// Delete a chunk from the cache
-pub fn delete_chunk(cache_dir: PathBuf, chunk_addr: &ChunkAddress) -> Result<(), ChunkCacheError> {
-    let chunk_file_path = chunk_file_path(cache_dir, chunk_addr);
+    fn store(&self, chunk_addr: &ChunkAddress, chunk: &Chunk) -> Result<(), ChunkCacheError> {
+        let size = chunk.size() as u64;
+        if size > self.max_size {
+            debug!(
+                "Not caching chunk {}, larger than the cache",
+                chunk_addr.to_hex()
+            );
+            return Ok(());
+        }
+
+        // Create the cache directory if it doesn't exist
+        if !self.dir.exists() {
+            fs::create_dir_all(&self.dir).map_err(|e| {
+                ChunkCacheError::DirectoryCreation(format!(
+                    "Failed to create cache directory {}: {}",
+                    self.dir.display(),
+                    e
+                ))
+            })?;
+        }
+
+        let name = chunk_addr.xorname();
+        let chunk_file_path = self.chunk_file_path(name);
+        fs::write(&chunk_file_path, chunk.value())?;
+
+        let evicted = {
+            let mut index = lock(&self.index);
+            index.insert(*name, size);
+            index.evict(self.max_size)
+        };
+        self.delete_files(&evicted);
 
-    if chunk_file_path.exists() {
-        fs::remove_file(&chunk_file_path)?;
         debug!(
-            "Deleted cached chunk {} at {}",
+            "Cached chunk {} at {}, evicted {} chunks",
             chunk_addr.to_hex(),
-            chunk_file_path.display()
+            chunk_file_path.display(),
+            evicted.len()
         );
+        Ok(())
+    }
+
+    fn remove(&self, chunk_addrs: &[ChunkAddress]) -> Result<(), ChunkCacheError> {
+        for chunk_addr in chunk_addrs {
+            let name = chunk_addr.xorname();
+            lock(&self.index).remove(name);
+            let chunk_file_path = self.chunk_file_path(name);
+            match fs::remove_file(&chunk_file_path) {
+                Ok(()) => debug!(
+                    "Deleted cached chunk {} at {}",
+                    chunk_addr.to_hex(),
+                    chunk_file_path.display()
+                ),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> u64 {
+        lock(&self.index).total_size
+    }
+}
+
+/// Chunk cache holding the chunks in memory, for short-lived services without a writable disk.
+///
+/// The total size of the chunks is kept under a maximum, evicting the least recently used chunks.
+#[derive(Debug)]
+pub struct MemoryChunkCache {
+    max_size: u64,
+    state: Mutex<(LruIndex, HashMap<XorName, Chunk>)>,
+}
+
+impl MemoryChunkCache {
+    pub fn new(max_size: u64) -> Self {
+        Self {
+            max_size,
+            state: Mutex::new(Default::default()),
+        }
+    }
+}
+
+impl ChunkCache for MemoryChunkCache {
+    fn load(&self, chunk_addr: &ChunkAddress) -> Option<Chunk> {
+        let mut state = lock(&self.state);
+        let (index, chunks) = &mut *state;
+        let chunk = chunks.get(chunk_addr.xorname()).cloned()?;
+        index.touch(chunk_addr.xorname());
+        Some(chunk)
     }
 
-    Ok(())
+    fn store(&self, chunk_addr: &ChunkAddress, chunk: &Chunk) -> Result<(), ChunkCacheError> {
+        let size = chunk.size() as u64;
+        if size > self.max_size {
+            return Ok(());
+        }
+
+        let mut state = lock(&self.state);
+        let (index, chunks) = &mut *state;
+        let name = *chunk_addr.xorname();
+        chunks.insert(name, chunk.clone());
+        index.insert(name, size);
+        for evicted in index.evict(self.max_size) {
+            chunks.remove(&evicted);
+        }
+        Ok(())
+    }
+
+    fn remove(&self, chunk_addrs: &[ChunkAddress]) -> Result<(), ChunkCacheError> {
+        let mut state = lock(&self.state);
+        let (index, chunks) = &mut *state;
+        for chunk_addr in chunk_addrs {
+            index.remove(chunk_addr.xorname());
+            chunks.remove(chunk_addr.xorname());
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> u64 {
+        lock(&self.state).0.total_size
+    }
 }
 
-/// Delete multiple chunks from the cache
-pub fn delete_chunks(
-    cache_dir: PathBuf,
-    chunk_addrs: &[ChunkAddress],
-) -> Result<(), ChunkCacheError> {
-    for chunk_addr in chunk_addrs {
-        delete_chunk(cache_dir.clone(), chunk_addr)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(content: &'static [u8]) -> (ChunkAddress, Chunk) {
+        let chunk = Chunk::new(Bytes::from_static(content));
+        (*chunk.address(), chunk)
+    }
+
+    #[test]
+    fn test_memory_cache_lru_eviction() {
+        let cache = MemoryChunkCache::new(8);
+        let (a, chunk_a) = chunk(b"aaaa");
+        let (b, chunk_b) = chunk(b"bbbb");
+        let (c, chunk_c) = chunk(b"cccc");
+
+        cache.store(&a, &chunk_a).unwrap();
+        cache.store(&b, &chunk_b).unwrap();
+        // `a` is now more recently used than `b`
+        assert_eq!(cache.load(&a), Some(chunk_a));
+        cache.store(&c, &chunk_c).unwrap();
+
+        assert_eq!(cache.size(), 8);
+        assert!(cache.load(&a).is_some());
+        assert!(cache.load(&b).is_none());
+        assert!(cache.load(&c).is_some());
+
+        cache.remove(&[a]).unwrap();
+        assert_eq!(cache.size(), 4);
+    }
+
+    #[test]
+    fn test_disk_cache_eviction_and_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskChunkCache::new(dir.path().to_path_buf(), 8).unwrap();
+        let (a, chunk_a) = chunk(b"aaaa");
+        let (b, chunk_b) = chunk(b"bbbb");
+        let (c, chunk_c) = chunk(b"cccc");
+
+        cache.store(&a, &chunk_a).unwrap();
+        cache.store(&b, &chunk_b).unwrap();
+        cache.store(&c, &chunk_c).unwrap();
+        assert_eq!(cache.size(), 8);
+        assert!(cache.load(&a).is_none());
+        assert!(!cache.chunk_file_path(a.xorname()).exists());
+        assert_eq!(cache.load(&b), Some(chunk_b));
+
+        // a corrupted file is not returned and gets deleted
+        fs::write(cache.chunk_file_path(c.xorname()), b"corrupted").unwrap();
+        assert!(cache.load(&c).is_none());
+        assert!(!cache.chunk_file_path(c.xorname()).exists());
+        assert_eq!(cache.size(), 4);
+
+        // reopening indexes the chunks left and enforces the new limit
+        let reopened = DiskChunkCache::new(dir.path().to_path_buf(), 8).unwrap();
+        assert_eq!(reopened.size(), 4);
+        let reopened = DiskChunkCache::new(dir.path().to_path_buf(), 2).unwrap();
+        assert_eq!(reopened.size(), 0);
+        assert!(!reopened.chunk_file_path(b.xorname()).exists());
+    }
+
+    #[test]
+    fn test_disk_caches_are_shared_by_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let other_dir = tempfile::tempdir().unwrap();
+        let disk_caches = DiskChunkCaches::default();
+        let strategy = |dir: &tempfile::TempDir| ClientOperatingStrategy {
+            chunk_cache_dir: Some(dir.path().to_path_buf()),
+            chunk_cache_max_size: 8,
+            ..Default::default()
+        };
+
+        let alice = chunk_cache_from_strategy(&strategy(&dir), &disk_caches).unwrap();
+        let bob = chunk_cache_from_strategy(&strategy(&dir), &disk_caches).unwrap();
+        let carol = chunk_cache_from_strategy(&strategy(&other_dir), &disk_caches).unwrap();
+        assert!(Arc::ptr_eq(&alice, &bob));
+        assert!(!Arc::ptr_eq(&alice, &carol));
+
+        // the evictions of one client are seen by the other
+        let (a, chunk_a) = chunk(b"aaaa");
+        let (b, chunk_b) = chunk(b"bbbb");
+        let (c, chunk_c) = chunk(b"cccc");
+        alice.store(&a, &chunk_a).unwrap();
+        bob.store(&b, &chunk_b).unwrap();
+        alice.store(&c, &chunk_c).unwrap();
+        assert_eq!(bob.size(), 8);
+        assert!(bob.load(&a).is_none());
+        assert_eq!(carol.size(), 0);
     }
-    Ok(())
 }
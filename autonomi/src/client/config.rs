@@ -6,13 +6,22 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::networking::{Quorum, RetryStrategy, Strategy};
+use crate::client::chunk_cache::{ChunkCache, DEFAULT_CHUNK_CACHE_MAX_SIZE};
+use crate::client::cost_budget::CostBudget;
+use crate::client::data_types::scratchpad::ForkMerge;
+use crate::client::receipt_store::ReceiptStore;
+use crate::client::watch::WatchInterval;
+use crate::networking::{Network, Quorum, RetryPolicy, RetryStrategy, Strategy};
 pub use ant_bootstrap::{
     Bootstrap, BootstrapConfig, InitialPeersConfig, error::Error as BootstrapError,
 };
 use ant_evm::EvmNetwork;
 use evmlib::contract::payment_vault::MAX_TRANSFERS_PER_TRANSACTION;
-use std::{num::NonZero, sync::LazyLock};
+use std::{
+    num::NonZero,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
 
 /// Number of chunks to upload in parallel.
 ///
@@ -137,14 +146,67 @@ pub struct ClientOperatingStrategy {
     pub scratchpad: Strategy,
     /// Enable chunk caching for faster retrieval
     pub chunk_cache_enabled: bool,
-    /// Custom chunk cache directory (if None, uses default)
+    /// Custom chunk cache directory (if None, uses default). The clients of a
+    /// [`crate::client::SharedNetwork`] share the cache of a directory, clients in other processes
+    /// must use their own.
     pub chunk_cache_dir: Option<std::path::PathBuf>,
+    /// Where the cached chunks are kept
+    pub chunk_cache_backend: ChunkCacheBackend,
+    /// Maximum size in bytes of the cached chunks, the least recently used ones being evicted past it.
+    /// Not applied to [`ChunkCacheBackend::Custom`] caches.
+    pub chunk_cache_max_size: u64,
+    /// Policy deciding when to retry failed requests and which peers to send them to.
+    /// If None, the [`crate::networking::AdaptiveRetryPolicy`] of the network is used.
+    pub retry_policy: Option<Arc<dyn RetryPolicy>>,
+    /// Limits on what is paid for uploads. If None, quotes are paid whatever their price.
+    pub cost_budget: Option<CostBudget>,
+    /// How long quotes are reused for the same address, counted from the time the nodes issued them,
+    /// e.g. when paying for data after estimating its cost. If None, the default, quotes are never
+    /// reused. [`crate::client::quote_cache::DEFAULT_QUOTE_VALIDITY`] is a sensible value to enable it.
+    pub quote_validity: Option<Duration>,
+    /// Time during which the payments of concurrent uploads with the same wallet are collected, to
+    /// be paid in as few transactions as possible. If None, each upload batch is paid on its own.
+    pub payment_batch_window: Option<Duration>,
+    /// Where the proofs of payment are kept, to be reused instead of paying again for the same
+    /// addresses, e.g. when retrying a failed upload. If None, proofs are only kept in memory.
+    pub receipt_store: Option<Arc<ReceiptStore>>,
+    /// How [`crate::Client::scratchpad_update`] keeps the data of the forks it finds when updating,
+    /// e.g. for apps writing the same scratchpad from several devices: merged under the updated data.
+    /// If None, the update replaces the forks.
+    pub scratchpad_fork_merge: Option<ForkMerge>,
+    /// How long [`crate::Client::pointer_resolve`] reuses the targets of the pointers it followed,
+    /// counted from the time they were fetched, at the risk of missing their latest updates. If None,
+    /// the default, the pointers are fetched on every resolution.
+    /// [`crate::client::pointer_cache::DEFAULT_POINTER_CACHE_TTL`] is a sensible value to enable it.
+    pub pointer_cache_ttl: Option<Duration>,
+    /// Bounds of the polling interval of the watch streams, e.g. [`crate::Client::watch_pointer`].
+    pub watch_interval: WatchInterval,
+}
+
+/// Backend of the chunk cache, see [`ClientOperatingStrategy::chunk_cache_enabled`].
+#[derive(Debug, Clone, Default)]
+pub enum ChunkCacheBackend {
+    /// Chunks are kept in files of the [`ClientOperatingStrategy::chunk_cache_dir`] directory
+    #[default]
+    Disk,
+    /// Chunks are kept in memory, for short-lived services
+    Memory,
+    /// A custom cache implementation
+    Custom(Arc<dyn ChunkCache>),
 }
 
 impl ClientOperatingStrategy {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Apply the [`ClientOperatingStrategy::retry_policy`], if any, to the network.
+    pub(crate) fn apply_retry_policy(&self, network: Network) -> Network {
+        match &self.retry_policy {
+            Some(retry_policy) => network.with_retry_policy(Arc::clone(retry_policy)),
+            None => network,
+        }
+    }
 }
 
 /// The default configuration for the client.
@@ -185,6 +247,16 @@ impl Default for ClientOperatingStrategy {
             },
             chunk_cache_enabled: true,
             chunk_cache_dir: None,
+            chunk_cache_backend: ChunkCacheBackend::Disk,
+            chunk_cache_max_size: DEFAULT_CHUNK_CACHE_MAX_SIZE,
+            retry_policy: None,
+            cost_budget: None,
+            quote_validity: None,
+            payment_batch_window: None,
+            receipt_store: None,
+            scratchpad_fork_merge: None,
+            pointer_cache_ttl: None,
+            watch_interval: WatchInterval::default(),
         }
     }
 }
@@ -0,0 +1,323 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::Client;
+use crate::client::payment::PayError;
+use crate::client::quote::{DataTypes, StoreQuote};
+use ant_evm::{Amount, AttoTokens};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use xor_name::XorName;
+
+/// Time to wait before asking again for the quotes above the price ceiling.
+const REQUOTE_DELAY: Duration = Duration::from_secs(5);
+
+/// Limits on what a client pays for its uploads, set in [`crate::ClientOperatingStrategy::cost_budget`].
+///
+/// Payments that would go above the limits are refused with [`PayError::BudgetExceeded`] or
+/// [`PayError::PriceCeilingExceeded`] before anything is paid.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CostBudget {
+    /// Maximum paid in total by an operation, such as uploading a file or a directory.
+    /// Payments made outside of an operation, e.g. with [`Client::chunk_put`], are checked one by one.
+    pub max_total: Option<AttoTokens>,
+    /// Maximum paid for storing a single address
+    pub max_per_address: Option<AttoTokens>,
+    /// Number of times to ask the close peers for new quotes when an address is priced above
+    /// `max_per_address`, before giving up
+    pub requote_attempts: usize,
+}
+
+impl CostBudget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuse to pay more than `max_total` in total for an operation.
+    pub fn with_max_total(mut self, max_total: AttoTokens) -> Self {
+        self.max_total = Some(max_total);
+        self
+    }
+
+    /// Refuse to pay more than `max_per_address` for storing a single address.
+    pub fn with_max_per_address(mut self, max_per_address: AttoTokens) -> Self {
+        self.max_per_address = Some(max_per_address);
+        self
+    }
+
+    /// Ask for new quotes up to `attempts` times when an address is priced above the ceiling.
+    pub fn with_requote_attempts(mut self, attempts: usize) -> Self {
+        self.requote_attempts = attempts;
+        self
+    }
+}
+
+/// What an operation paid so far, shared by the clients running it.
+#[derive(Debug, Default)]
+pub(crate) struct OperationSpend {
+    spent: Mutex<Amount>,
+}
+
+impl OperationSpend {
+    fn spent(&self) -> MutexGuard<'_, Amount> {
+        // the amount stays consistent even if a panic occurred while holding the lock
+        self.spent.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Reserve `cost` from the budget, unless it would go above `max_total`.
+    fn reserve(
+        self: &Arc<Self>,
+        cost: Amount,
+        max_total: AttoTokens,
+    ) -> Result<BudgetReservation, PayError> {
+        let mut spent = self.spent();
+        let total = spent.saturating_add(cost);
+        if total > max_total.as_atto() {
+            return Err(PayError::BudgetExceeded {
+                cost: AttoTokens::from_atto(cost),
+                spent: AttoTokens::from_atto(*spent),
+                budget: max_total,
+            });
+        }
+        *spent = total;
+        Ok(BudgetReservation {
+            spend: Arc::clone(self),
+            cost,
+            committed: false,
+        })
+    }
+}
+
+/// Cost reserved from the budget of an operation, released unless the payment went through.
+#[derive(Debug)]
+pub(crate) struct BudgetReservation {
+    spend: Arc<OperationSpend>,
+    cost: Amount,
+    committed: bool,
+}
+
+impl BudgetReservation {
+    /// The payment was made, keep the cost in the spending of the operation.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+
+    /// Only `paid` out of the reserved cost was paid before the payment failed, keep it in the
+    /// spending of the operation and release the rest.
+    pub fn commit_partial(mut self, paid: Amount) {
+        self.cost = self.cost.saturating_sub(paid);
+    }
+}
+
+impl Drop for BudgetReservation {
+    fn drop(&mut self) {
+        if !self.committed {
+            let mut spent = self.spend.spent();
+            *spent = spent.saturating_sub(self.cost);
+        }
+    }
+}
+
+impl Client {
+    /// A client accounting the payments of an operation against the [`CostBudget`],
+    /// reusing the spending of the enclosing operation if any.
+    pub(crate) fn with_operation_spend(&self) -> Client {
+        let mut client = self.clone();
+        if client.operation_spend.is_none() {
+            client.operation_spend = Some(Default::default());
+        }
+        client
+    }
+
+    /// Check the quotes against the [`CostBudget`] of the client before paying them.
+    ///
+    /// Addresses priced above the ceiling are quoted again, keeping the cheapest quotes. Returns the
+    /// quotes to pay, along with their cost reserved from the budget of the operation.
+    pub(crate) async fn apply_cost_budget(
+        &self,
+        data_type: DataTypes,
+        mut quotes: StoreQuote,
+        content_addrs: &HashMap<XorName, usize>,
+    ) -> Result<(StoreQuote, Option<BudgetReservation>), PayError> {
+        let Some(budget) = &self.config.cost_budget else {
+            return Ok((quotes, None));
+        };
+
+        if let Some(ceiling) = budget.max_per_address {
+            let mut attempt = 0;
+            loop {
+                let above_ceiling: Vec<XorName> = quotes
+                    .0
+                    .iter()
+                    .filter(|(_, quote)| quote.price() > ceiling.as_atto())
+                    .map(|(addr, _)| *addr)
+                    .collect();
+                let Some(first) = above_ceiling.first() else {
+                    break;
+                };
+                if attempt >= budget.requote_attempts {
+                    return Err(PayError::PriceCeilingExceeded {
+                        address: *first,
+                        price: AttoTokens::from_atto(quotes.0[first].price()),
+                        ceiling,
+                    });
+                }
+                attempt += 1;
+                info!(
+                    "{} addresses priced above the ceiling of {ceiling}, asking for new quotes ({attempt}/{})",
+                    above_ceiling.len(),
+                    budget.requote_attempts
+                );
+                self.operation_options.sleep(REQUOTE_DELAY).await;
+                if let Some(cache) = &self.quote_cache {
+                    cache.remove(above_ceiling.iter());
+                }
+
+                let mut requoted = self
+                    .get_store_quotes(
+                        data_type,
+                        above_ceiling
+                            .iter()
+                            .map(|addr| (*addr, content_addrs.get(addr).copied().unwrap_or(0))),
+                    )
+                    .await?
+                    .0;
+                for addr in above_ceiling {
+                    match requoted.remove(&addr) {
+                        Some(quote) if quote.price() < quotes.0[&addr].price() => {
+                            quotes.0.insert(addr, quote);
+                        }
+                        Some(_) => {}
+                        // stored in the meantime, no need to pay for it anymore
+                        None => {
+                            quotes.0.remove(&addr);
+                        }
+                    }
+                }
+            }
+        }
+
+        let Some(max_total) = budget.max_total else {
+            return Ok((quotes, None));
+        };
+        let spend = self.operation_spend.clone().unwrap_or_default();
+        let reservation = spend.reserve(quotes.price(), max_total)?;
+        Ok((quotes, Some(reservation)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::OperationKind;
+    use crate::client::config::ClientOperatingStrategy;
+    use crate::client::quote::QuoteForAddress;
+    use crate::networking::common::Addresses;
+    use crate::test_utils::{offline_client, quote_issued_at};
+    use libp2p::PeerId;
+    use std::time::SystemTime;
+
+    fn quotes_priced(price: u64) -> StoreQuote {
+        let addr = XorName::random(&mut rand::thread_rng());
+        let quote = quote_issued_at(addr, SystemTime::now());
+        let quotes = vec![(
+            PeerId::random(),
+            Addresses(vec![]),
+            quote,
+            Amount::from(price),
+        )];
+        StoreQuote(HashMap::from([(addr, QuoteForAddress(quotes))]))
+    }
+
+    #[test]
+    fn test_budget_reservation() {
+        let spend = Arc::new(OperationSpend::default());
+        let budget = AttoTokens::from_u64(100);
+
+        let reservation = spend
+            .reserve(Amount::from(60), budget)
+            .expect("within budget");
+        reservation.commit();
+        assert_eq!(*spend.spent(), Amount::from(60));
+
+        match spend.reserve(Amount::from(50), budget) {
+            Err(PayError::BudgetExceeded {
+                cost,
+                spent,
+                budget: max,
+            }) => {
+                assert_eq!(cost, AttoTokens::from_u64(50));
+                assert_eq!(spent, AttoTokens::from_u64(60));
+                assert_eq!(max, budget);
+            }
+            other => panic!("Expected a BudgetExceeded error, got {other:?}"),
+        }
+
+        // a payment that did not go through gives its reservation back
+        let reservation = spend
+            .reserve(Amount::from(40), budget)
+            .expect("within budget");
+        drop(reservation);
+        assert_eq!(*spend.spent(), Amount::from(60));
+
+        // a payment failing halfway keeps what was paid
+        let reservation = spend
+            .reserve(Amount::from(40), budget)
+            .expect("within budget");
+        reservation.commit_partial(Amount::from(15));
+        assert_eq!(*spend.spent(), Amount::from(75));
+    }
+
+    #[tokio::test]
+    async fn test_budget_shared_by_the_batches_of_an_operation() {
+        let strategy = ClientOperatingStrategy {
+            chunk_cache_enabled: false,
+            cost_budget: Some(CostBudget::new().with_max_total(AttoTokens::from_u64(100))),
+            ..Default::default()
+        };
+        let client = offline_client(strategy).await;
+
+        // each batch is within the budget, but not all of them together
+        let result = client
+            .in_operation(OperationKind::Upload, |client| async move {
+                let pay_batch = |client: Client| async move {
+                    let (_, reservation) = client
+                        .apply_cost_budget(DataTypes::Chunk, quotes_priced(40), &HashMap::new())
+                        .await?;
+                    if let Some(reservation) = reservation {
+                        reservation.commit();
+                    }
+                    Ok::<_, PayError>(())
+                };
+                pay_batch(client.clone()).await?;
+                // nested operations account their payments in the outer one
+                client
+                    .in_operation(OperationKind::Upload, &pay_batch)
+                    .await?;
+                pay_batch(client).await
+            })
+            .await;
+        match result {
+            Err(PayError::BudgetExceeded { spent, .. }) => {
+                assert_eq!(spent, AttoTokens::from_u64(80));
+            }
+            other => panic!("Expected a BudgetExceeded error, got {other:?}"),
+        }
+
+        // a new operation starts with a new budget
+        client
+            .in_operation(OperationKind::Upload, |client| async move {
+                client
+                    .apply_cost_budget(DataTypes::Chunk, quotes_priced(40), &HashMap::new())
+                    .await
+            })
+            .await
+            .expect("within budget");
+    }
+}
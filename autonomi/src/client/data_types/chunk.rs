@@ -6,15 +6,12 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::client::chunk_cache::{
-    default_cache_dir, delete_chunks, is_chunk_cached, load_chunk, store_chunk,
-};
 use crate::client::config::{CHUNK_DOWNLOAD_BATCH_SIZE, CHUNK_UPLOAD_BATCH_SIZE};
 use crate::networking::PeerInfo;
 use crate::{
     Client,
     client::{
-        ChunkBatchUploadState, GetError, PutError,
+        ChunkBatchUploadState, ClientEvent, GetError, PutError,
         payment::{PaymentOption, Receipt},
         quote::CostError,
     },
@@ -33,6 +30,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     hash::{DefaultHasher, Hash, Hasher},
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 /// Private data on the network can be accessed with this
@@ -84,50 +82,23 @@ fn hash_to_short_string(input: &str) -> String {
 }
 
 impl Client {
-    fn get_chunk_cache_dir(&self) -> Result<std::path::PathBuf, GetError> {
-        match &self.config.chunk_cache_dir {
-            Some(dir) => Ok(dir.clone()),
-            None => {
-                default_cache_dir().map_err(|_| {
-                    GetError::Configuration(
-                        "Chunk caching is enabled but no cache directory is specified. \
-                         Please set a cache directory in the client config or disable chunk caching.".to_string()
-                    )
-                })
-            }
-        }
+    fn try_load_chunk_from_cache(&self, addr: &ChunkAddress) -> Option<Chunk> {
+        let cached_chunk = self.chunk_cache.as_ref()?.load(addr)?;
+        debug!("Loaded chunk from cache: {addr:?}");
+        Some(cached_chunk)
     }
 
-    fn try_load_chunk_from_cache(&self, addr: &ChunkAddress) -> Result<Option<Chunk>, GetError> {
-        if !self.config.chunk_cache_enabled {
-            return Ok(None);
-        }
-
-        let cache_dir = self.get_chunk_cache_dir()?;
-        if is_chunk_cached(cache_dir.clone(), addr)
-            && let Ok(Some(cached_chunk)) = load_chunk(cache_dir, addr)
+    fn try_cache_chunk(&self, addr: &ChunkAddress, chunk: &Chunk) {
+        if let Some(cache) = &self.chunk_cache
+            && let Err(e) = cache.store(addr, chunk)
         {
-            debug!("Loaded chunk from cache: {addr:?}");
-            return Ok(Some(cached_chunk));
-        }
-        Ok(None)
-    }
-
-    fn try_cache_chunk(&self, addr: &ChunkAddress, chunk: &Chunk) -> Result<(), GetError> {
-        if self.config.chunk_cache_enabled {
-            let cache_dir = self.get_chunk_cache_dir()?;
-            if let Err(e) = store_chunk(cache_dir, addr, chunk) {
-                warn!("Failed to cache chunk {}: {}", addr.to_hex(), e);
-            }
+            warn!("Failed to cache chunk {}: {}", addr.to_hex(), e);
         }
-        Ok(())
     }
 
     pub(crate) fn cleanup_cached_chunks(&self, chunk_addrs: &[ChunkAddress]) {
-        if self.config.chunk_cache_enabled
-            && let Ok(cache_dir) = self.get_chunk_cache_dir()
-        {
-            if let Err(e) = delete_chunks(cache_dir, chunk_addrs) {
+        if let Some(cache) = &self.chunk_cache {
+            if let Err(e) = cache.remove(chunk_addrs) {
                 warn!("Failed to delete cached chunks after download: {e}");
             } else {
                 debug!(
@@ -169,12 +140,22 @@ impl Client {
     pub async fn chunk_get(&self, addr: &ChunkAddress) -> Result<Chunk, GetError> {
         info!("Getting chunk: {addr:?}");
 
-        if let Some(cached_chunk) = self.try_load_chunk_from_cache(addr)? {
+        if let Some(cached_chunk) = self.try_load_chunk_from_cache(addr) {
+            self.send_event(|op| ClientEvent::ChunkDownloaded {
+                op,
+                address: *addr,
+                from_cache: true,
+            });
             return Ok(cached_chunk);
         }
 
         let chunk = self.fetch_chunk_from_network(addr).await?;
-        self.try_cache_chunk(addr, &chunk)?;
+        self.try_cache_chunk(addr, &chunk);
+        self.send_event(|op| ClientEvent::ChunkDownloaded {
+            op,
+            address: *addr,
+            from_cache: false,
+        });
         Ok(chunk)
     }
 
@@ -185,6 +166,17 @@ impl Client {
         chunk: &Chunk,
         payment_option: PaymentOption,
     ) -> Result<(AttoTokens, ChunkAddress), PutError> {
+        let cost = self.chunk_put_unless_stored(chunk, payment_option).await?;
+        Ok((cost.unwrap_or(AttoTokens::zero()), *chunk.address()))
+    }
+
+    /// Upload a chunk to the network, returning its cost, or `None` if nothing was stored because
+    /// the quoting nodes already hold the chunk.
+    pub(crate) async fn chunk_put_unless_stored(
+        &self,
+        chunk: &Chunk,
+        payment_option: PaymentOption,
+    ) -> Result<Option<AttoTokens>, PutError> {
         let address = chunk.network_address();
 
         if chunk.size() > Chunk::MAX_SIZE {
@@ -212,12 +204,12 @@ impl Client {
             Some((proof, price)) => (proof, price),
             None => {
                 info!("Chunk at address: {address:?} was already paid for");
-                return Ok((AttoTokens::zero(), *chunk.address()));
+                return Ok(None);
             }
         };
         let total_cost = *price;
 
-        let payees = proof
+        let payees: Vec<PeerInfo> = proof
             .payees()
             .iter()
             .map(|(peer_id, addrs)| PeerInfo {
@@ -244,7 +236,7 @@ impl Client {
         debug!("Storing chunk at address: {address:?} to the network");
 
         self.network
-            .put_record_with_retries(record, payees, &self.config.chunks)
+            .put_record_with_retries(record, payees.clone(), &self.config.chunks)
             .await
             .inspect_err(|err| {
                 error!("Failed to put record - chunk {address:?} to the network: {err}")
@@ -254,8 +246,14 @@ impl Client {
                 network_error: err.clone(),
                 payment: Some(payment_proofs),
             })?;
-
-        Ok((total_cost, *chunk.address()))
+        self.forget_stored_receipts([xor_name]);
+        self.send_event(|op| ClientEvent::ChunkStored {
+            op,
+            address: *chunk.address(),
+            peers: payees.iter().map(|peer| peer.peer_id).collect(),
+        });
+
+        Ok(Some(total_cost))
     }
 
     /// Get the cost of a chunk.
@@ -346,6 +344,16 @@ impl Client {
             };
 
             upload_tasks.push(async move {
+                // paid for chunks not started yet are left to the caller once interrupted
+                if let Err(reason) = self_clone.operation_options.check() {
+                    return Err((
+                        chunk,
+                        PutError::Interrupted {
+                            reason,
+                            state: Default::default(),
+                        },
+                    ));
+                }
                 let res = self_clone
                     .chunk_upload_with_payment(chunk, proof.clone(), *price)
                     .await
@@ -440,7 +448,13 @@ impl Client {
                     payment: Some(receipt),
                 }
             })?;
+        self.forget_stored_receipts([*chunk.name()]);
         debug!("Successfully stored chunk: {chunk:?} to {storing_nodes:?}");
+        self.send_event(|op| ClientEvent::ChunkStored {
+            op,
+            address: *chunk.address(),
+            peers: storing_nodes.iter().map(|peer| peer.peer_id).collect(),
+        });
         Ok(*chunk.address())
     }
 
@@ -451,6 +465,7 @@ impl Client {
         println!("Fetching {total_chunks} encrypted data chunks from network.");
         debug!("Fetching {total_chunks} encrypted data chunks from datamap {data_map:?}");
 
+        let fetched_chunks = AtomicUsize::new(0);
         let mut download_tasks = vec![];
         let chunk_addrs: Vec<ChunkAddress> = data_map
             .infos()
@@ -458,6 +473,7 @@ impl Client {
             .map(|info| ChunkAddress::new(info.dst_hash))
             .collect();
 
+        let fetched_chunks = &fetched_chunks;
         for (i, info) in data_map.infos().into_iter().enumerate() {
             download_tasks.push(async move {
                 let idx = i + 1;
@@ -467,11 +483,26 @@ impl Client {
                 println!("Fetching chunk {idx}/{total_chunks} ...");
                 info!("Fetching chunk {idx}/{total_chunks}({chunk_addr:?})");
 
-                match self.chunk_get(&chunk_addr).await {
+                match self
+                    .operation_options
+                    .interruptible(self.chunk_get(&chunk_addr))
+                    .await?
+                {
                     Ok(chunk) => {
                         #[cfg(feature = "loud")]
                         println!("Fetching chunk {idx}/{total_chunks} [DONE]");
                         info!("Successfully fetched chunk {idx}/{total_chunks}({chunk_addr:?})");
+                        // progress is reported once per download batch
+                        let fetched = fetched_chunks.fetch_add(1, Ordering::Relaxed) + 1;
+                        if fetched.is_multiple_of(*CHUNK_DOWNLOAD_BATCH_SIZE)
+                            || fetched == total_chunks
+                        {
+                            self.send_event(|op| ClientEvent::DecryptionProgress {
+                                op,
+                                chunks: fetched,
+                                total_chunks,
+                            });
+                        }
                         Ok(EncryptedChunk {
                             content: chunk.value,
                         })
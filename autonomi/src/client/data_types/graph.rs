@@ -175,6 +175,7 @@ impl Client {
                     payment: Some(payment_proofs.clone()),
                 })
             })?;
+        self.forget_stored_receipts([xor_name]);
 
         // send client event
         if let Some(channel) = self.client_event_sender.as_ref() {
@@ -10,8 +10,9 @@ use super::resolve_split_records;
 
 use crate::{
     client::{
-        Client, GetError, PutError,
+        Client, GetError, OperationKind, PutError,
         payment::{PayError, PaymentOption},
+        pointer_cache::PointerCache,
         quote::CostError,
     },
     networking::{NetworkError, PeerInfo, Record},
@@ -23,6 +24,7 @@ use ant_protocol::{
     storage::{DataTypes, RecordHeader, RecordKind, try_deserialize_record, try_serialize_record},
 };
 use std::collections::HashSet;
+use std::future::Future;
 use tracing::{debug, error, trace};
 
 pub use ant_protocol::storage::{Pointer, PointerAddress, PointerTarget};
@@ -57,6 +59,19 @@ pub enum PointerError {
     CannotUpdateNewPointer,
     #[error("Got multiple conflicting pointers with the latest version")]
     Fork(Vec<Pointer>),
+    #[error("Pointer chain loops back to {:?}", .0.last())]
+    Cycle(Vec<PointerAddress>),
+    #[error("Pointer chain is longer than the maximum depth of {0}")]
+    MaxDepthExceeded(usize),
+}
+
+/// A chain of pointers followed to its target by [`Client::pointer_resolve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerResolution {
+    /// The pointers followed, starting with the one resolved
+    pub path: Vec<PointerAddress>,
+    /// The target of the last pointer, which is not a pointer
+    pub target: PointerTarget,
 }
 
 impl Client {
@@ -224,6 +239,7 @@ impl Client {
                     payment: Some(payment_proofs),
                 })
             })?;
+        self.forget_stored_receipts([xor_name]);
 
         Ok((total_cost, address))
     }
@@ -256,6 +272,17 @@ impl Client {
         &self,
         owner: &SecretKey,
         target: PointerTarget,
+    ) -> Result<(), PointerError> {
+        self.in_operation(OperationKind::PointerUpdate, |client| async move {
+            client.pointer_update_latest(owner, target).await
+        })
+        .await
+    }
+
+    async fn pointer_update_latest(
+        &self,
+        owner: &SecretKey,
+        target: PointerTarget,
     ) -> Result<(), PointerError> {
         let address = PointerAddress::new(owner.public_key());
         info!("Updating pointer at address {address:?} to {target:?}");
@@ -283,6 +310,27 @@ impl Client {
         }
     }
 
+    /// Follow a chain of pointers pointing at pointers, e.g. "latest" -> "v3" -> data, to the first
+    /// target that is not a pointer.
+    ///
+    /// At most `max_depth` pointers are followed. Fails with [`PointerError::Cycle`] if the chain
+    /// loops back to a pointer already followed. If
+    /// [`crate::ClientOperatingStrategy::pointer_cache_ttl`] is set, the targets are reused for that
+    /// long, so resolving chains sharing hops fetches them only once.
+    pub async fn pointer_resolve(
+        &self,
+        address: &PointerAddress,
+        max_depth: usize,
+    ) -> Result<PointerResolution, PointerError> {
+        resolve_pointer_chain(
+            address,
+            max_depth,
+            self.pointer_cache.as_deref(),
+            |addr| async move { Ok(self.pointer_get(&addr).await?.target().clone()) },
+        )
+        .await
+    }
+
     /// Update an existing pointer from a specific pointer
     ///
     /// This will increment the counter of the pointer and update the target
@@ -338,6 +386,9 @@ impl Client {
                 })
             })?;
 
+        if let Some(cache) = &self.pointer_cache {
+            cache.insert(address, pointer.target().clone());
+        }
         Ok(pointer)
     }
 
@@ -385,3 +436,107 @@ fn pointer_from_record(record: Record) -> Result<Pointer, PointerError> {
 
     Ok(pointer)
 }
+
+/// Follow the chain of pointers from the address, fetching the targets missing from the cache.
+async fn resolve_pointer_chain<F, Fut>(
+    address: &PointerAddress,
+    max_depth: usize,
+    cache: Option<&PointerCache>,
+    fetch: F,
+) -> Result<PointerResolution, PointerError>
+where
+    F: Fn(PointerAddress) -> Fut,
+    Fut: Future<Output = Result<PointerTarget, PointerError>>,
+{
+    let mut path: Vec<PointerAddress> = Vec::new();
+    let mut current = *address;
+    loop {
+        if path.contains(&current) {
+            path.push(current);
+            warn!("Pointer chain from {address:?} has a cycle: {path:?}");
+            return Err(PointerError::Cycle(path));
+        }
+        if path.len() >= max_depth {
+            return Err(PointerError::MaxDepthExceeded(max_depth));
+        }
+        path.push(current);
+
+        let target = match cache.and_then(|cache| cache.get(&current)) {
+            Some(target) => target,
+            None => {
+                let target = fetch(current).await?;
+                if let Some(cache) = cache {
+                    cache.insert(current, target.clone());
+                }
+                target
+            }
+        };
+
+        match target {
+            PointerTarget::PointerAddress(next) => current = next,
+            target => {
+                debug!("Resolved pointer {address:?} to {target:?} through {path:?}");
+                return Ok(PointerResolution { path, target });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant_protocol::storage::ChunkAddress;
+    use std::time::Duration;
+    use xor_name::XorName;
+
+    fn pointer_address() -> PointerAddress {
+        PointerAddress::new(SecretKey::random().public_key())
+    }
+
+    async fn resolve_cached(
+        cache: &PointerCache,
+        address: &PointerAddress,
+        max_depth: usize,
+    ) -> Result<PointerResolution, PointerError> {
+        // every hop is expected to be cached
+        resolve_pointer_chain(address, max_depth, Some(cache), |_| async {
+            Err(PointerError::CannotUpdateNewPointer)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_pointer_resolve_from_cache() {
+        let (latest, v3) = (pointer_address(), pointer_address());
+        let data = PointerTarget::ChunkAddress(ChunkAddress::new(XorName::random(
+            &mut rand::thread_rng(),
+        )));
+        let cache = PointerCache::new(Duration::from_secs(60));
+        cache.insert(latest, PointerTarget::PointerAddress(v3));
+        cache.insert(v3, data.clone());
+
+        let resolution = resolve_cached(&cache, &latest, 2)
+            .await
+            .expect("chain to resolve");
+        assert_eq!(resolution.path, vec![latest, v3]);
+        assert_eq!(resolution.target, data);
+
+        assert!(matches!(
+            resolve_cached(&cache, &latest, 1).await,
+            Err(PointerError::MaxDepthExceeded(1))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pointer_resolve_cycle() {
+        let (a, b) = (pointer_address(), pointer_address());
+        let cache = PointerCache::new(Duration::from_secs(60));
+        cache.insert(a, PointerTarget::PointerAddress(b));
+        cache.insert(b, PointerTarget::PointerAddress(a));
+
+        match resolve_cached(&cache, &a, 10).await {
+            Err(PointerError::Cycle(path)) => assert_eq!(path, vec![a, b, a]),
+            other => panic!("expected a cycle, got {other:?}"),
+        }
+    }
+}
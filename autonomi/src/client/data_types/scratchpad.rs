@@ -11,7 +11,7 @@ use super::resolve_split_records;
 use crate::{
     Amount, AttoTokens, Client,
     client::{
-        PutError,
+        OperationKind, PutError,
         payment::{PayError, PaymentOption},
         quote::CostError,
     },
@@ -24,6 +24,7 @@ use ant_protocol::{
 };
 use libp2p::kad::Record;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 pub use crate::Bytes;
 pub use ant_protocol::storage::{Scratchpad, ScratchpadAddress};
@@ -61,6 +62,136 @@ pub enum ScratchpadError {
         "Got multiple conflicting scratchpads with the latest version, the fork can be resolved by putting a new scratchpad with a higher counter"
     )]
     Fork(Vec<Scratchpad>),
+    #[error("None of the forked scratchpads is a valid scratchpad of the owner")]
+    NoForkToResolve,
+    #[error("Failed to decrypt scratchpad data: {0}")]
+    Decryption(ant_protocol::Error),
+    #[error("Scratchpad counter is {found} on the network, expected {expected}")]
+    CounterConflict { expected: u64, found: u64 },
+    #[error("Scratchpad at {0:?} reached the highest counter and cannot be updated anymore")]
+    CounterOverflow(ScratchpadAddress),
+    /// A plain update would revoke the readers of the shared scratchpad, see
+    /// [`Client::scratchpad_update_shared`].
+    #[error(
+        "Scratchpad at {0:?} is shared, it must be updated with scratchpad_update_shared to keep its readers"
+    )]
+    SharedScratchpad(ScratchpadAddress),
+}
+
+/// Reads the timestamp an application embedded in the decrypted data of a scratchpad, see
+/// [`ForkStrategy::LastWriter`].
+pub type EmbeddedTimestamp = Arc<dyn Fn(&Bytes) -> Option<u64> + Send + Sync>;
+
+/// Merges the decrypted data of forked scratchpads into new data, see [`ForkStrategy::Merge`].
+pub type MergeData = Arc<dyn Fn(&[Bytes]) -> Bytes + Send + Sync>;
+
+/// How [`Client::scratchpad_resolve_fork`] picks the data of a forked scratchpad.
+///
+/// Every strategy is deterministic, so devices resolving the same fork publish the same data.
+#[derive(Clone)]
+pub enum ForkStrategy {
+    /// Keep the version with the highest signature.
+    HighestSignature,
+    /// Keep the version whose data holds the latest timestamp, as read by the function. Versions
+    /// without a timestamp come last, ties are broken by the highest signature.
+    LastWriter(EmbeddedTimestamp),
+    /// Merge the data of all the versions, given in the order of their signatures, highest first.
+    /// The data encoding of the version with the highest signature is kept.
+    Merge(MergeData),
+}
+
+impl std::fmt::Debug for ForkStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForkStrategy::HighestSignature => write!(f, "HighestSignature"),
+            ForkStrategy::LastWriter(_) => write!(f, "LastWriter"),
+            ForkStrategy::Merge(_) => write!(f, "Merge"),
+        }
+    }
+}
+
+/// Merges the data of the forks found by [`Client::scratchpad_update`] under the updated data, see
+/// [`ClientOperatingStrategy::scratchpad_fork_merge`].
+///
+/// The function is given the updated data first, then the data of the forks merged as with
+/// [`ForkStrategy::Merge`].
+///
+/// [`ClientOperatingStrategy::scratchpad_fork_merge`]: crate::ClientOperatingStrategy::scratchpad_fork_merge
+#[derive(Clone)]
+pub struct ForkMerge(pub MergeData);
+
+impl std::fmt::Debug for ForkMerge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ForkMerge")
+    }
+}
+
+/// Refuse to update a shared scratchpad as a plain one, which would revoke all its readers.
+fn ensure_not_shared(scratchpad: &Scratchpad) -> Result<(), ScratchpadError> {
+    if scratchpad.shared_readers().is_ok() {
+        return Err(ScratchpadError::SharedScratchpad(*scratchpad.address()));
+    }
+    Ok(())
+}
+
+/// The counter of the version following `counter`, failing once the highest counter is reached.
+fn next_counter(address: &ScratchpadAddress, counter: u64) -> Result<u64, ScratchpadError> {
+    counter
+        .checked_add(1)
+        .ok_or(ScratchpadError::CounterOverflow(*address))
+}
+
+/// The data encoding and the data resolving the fork, along with the highest counter of the forks.
+fn resolve_fork_data(
+    owner: &SecretKey,
+    forks: &[Scratchpad],
+    strategy: &ForkStrategy,
+) -> Result<(u64, u64, Bytes), ScratchpadError> {
+    let address = ScratchpadAddress::new(owner.public_key());
+    let mut versions: Vec<&Scratchpad> = forks
+        .iter()
+        .filter(|scratchpad| *scratchpad.address() == address && scratchpad.verify_signature())
+        .collect();
+    versions.sort_by_key(|scratchpad| std::cmp::Reverse(scratchpad.signature().to_bytes()));
+    let highest = *versions.first().ok_or(ScratchpadError::NoForkToResolve)?;
+    let counter = versions
+        .iter()
+        .map(|scratchpad| scratchpad.counter())
+        .max()
+        .unwrap_or_default();
+    let decrypt = |scratchpad: &Scratchpad| {
+        scratchpad
+            .decrypt_data(owner)
+            .map_err(ScratchpadError::Decryption)
+    };
+
+    let (data_encoding, data) = match strategy {
+        ForkStrategy::HighestSignature => (highest.data_encoding(), decrypt(highest)?),
+        ForkStrategy::LastWriter(timestamp) => {
+            let mut latest: Option<(Option<u64>, &Scratchpad, Bytes)> = None;
+            for scratchpad in versions {
+                let data = decrypt(scratchpad)?;
+                let written_at = timestamp(&data);
+                // strictly later only, the versions being sorted by signature
+                if latest
+                    .as_ref()
+                    .is_none_or(|(latest_at, _, _)| written_at > *latest_at)
+                {
+                    latest = Some((written_at, scratchpad, data));
+                }
+            }
+            let (_, scratchpad, data) = latest.ok_or(ScratchpadError::NoForkToResolve)?;
+            (scratchpad.data_encoding(), data)
+        }
+        ForkStrategy::Merge(merge) => {
+            let payloads = versions
+                .into_iter()
+                .map(decrypt)
+                .collect::<Result<Vec<_>, _>>()?;
+            (highest.data_encoding(), merge(&payloads))
+        }
+    };
+    Ok((counter, data_encoding, data))
 }
 
 /// Print detailed fork analysis for conflicting scratchpads
@@ -305,6 +436,7 @@ impl Client {
                     payment: Some(payment_proofs),
                 })
             })?;
+        self.forget_stored_receipts([xor_name]);
 
         Ok((total_cost, *address))
     }
@@ -334,40 +466,267 @@ impl Client {
         self.scratchpad_put(scratchpad, payment_option).await
     }
 
+    /// Create a new scratchpad whose data can be read by the readers, without giving them write
+    /// access, see [`Scratchpad::new_shared`].
+    ///
+    /// The owner is always a reader. The readers can be changed later with
+    /// [`Client::scratchpad_add_readers`] and [`Client::scratchpad_revoke_readers`].
+    ///
+    /// Returns the cost and the address of the scratchpad.
+    pub async fn scratchpad_create_shared(
+        &self,
+        owner: &SecretKey,
+        content_type: u64,
+        initial_data: &Bytes,
+        readers: &[PublicKey],
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, ScratchpadAddress), ScratchpadError> {
+        self.in_operation(OperationKind::ScratchpadUpdate, |client| async move {
+            let address = ScratchpadAddress::new(owner.public_key());
+            let already_exists = client.scratchpad_check_existence(&address).await?;
+            if already_exists {
+                return Err(ScratchpadError::ScratchpadAlreadyExists(address));
+            }
+
+            let scratchpad = Scratchpad::new_shared(owner, content_type, initial_data, readers, 0)
+                .map_err(|_| ScratchpadError::Serialization)?;
+            client.scratchpad_put(scratchpad, payment_option).await
+        })
+        .await
+    }
+
+    /// Get the data of a shared scratchpad, decrypted with the key of one of its readers
+    pub async fn scratchpad_get_shared(
+        &self,
+        reader: &SecretKey,
+        address: &ScratchpadAddress,
+    ) -> Result<Bytes, ScratchpadError> {
+        let scratchpad = self.scratchpad_get(address).await?;
+        scratchpad
+            .decrypt_shared_data(reader)
+            .map_err(ScratchpadError::Decryption)
+    }
+
+    /// Update the data of a shared scratchpad, keeping its readers.
+    ///
+    /// Returns the new scratchpad.
+    pub async fn scratchpad_update_shared(
+        &self,
+        owner: &SecretKey,
+        content_type: u64,
+        data: &Bytes,
+    ) -> Result<Scratchpad, ScratchpadError> {
+        self.in_operation(OperationKind::ScratchpadUpdate, |client| async move {
+            let current = client.scratchpad_get_latest(owner).await?;
+            let readers = current
+                .shared_readers()
+                .map_err(ScratchpadError::Decryption)?;
+            client
+                .scratchpad_reshare(owner, &current, content_type, data, &readers)
+                .await
+        })
+        .await
+    }
+
+    /// Give the readers access to the shared scratchpad, from its next version on.
+    ///
+    /// Returns the new scratchpad.
+    pub async fn scratchpad_add_readers(
+        &self,
+        owner: &SecretKey,
+        readers: &[PublicKey],
+    ) -> Result<Scratchpad, ScratchpadError> {
+        self.in_operation(OperationKind::ScratchpadUpdate, |client| async move {
+            let current = client.scratchpad_get_latest(owner).await?;
+            let data = current
+                .decrypt_shared_data(owner)
+                .map_err(ScratchpadError::Decryption)?;
+            let mut new_readers = current
+                .shared_readers()
+                .map_err(ScratchpadError::Decryption)?;
+            new_readers.extend(readers);
+            client
+                .scratchpad_reshare(
+                    owner,
+                    &current,
+                    current.data_encoding(),
+                    &data,
+                    &new_readers,
+                )
+                .await
+        })
+        .await
+    }
+
+    /// Remove the readers of the shared scratchpad, by re-encrypting its data with a new key only
+    /// given to the remaining readers.
+    ///
+    /// The revoked readers cannot read the new versions, but they may have kept the previous ones.
+    /// The owner cannot be revoked. Returns the new scratchpad.
+    pub async fn scratchpad_revoke_readers(
+        &self,
+        owner: &SecretKey,
+        readers: &[PublicKey],
+    ) -> Result<Scratchpad, ScratchpadError> {
+        self.in_operation(OperationKind::ScratchpadUpdate, |client| async move {
+            let current = client.scratchpad_get_latest(owner).await?;
+            let data = current
+                .decrypt_shared_data(owner)
+                .map_err(ScratchpadError::Decryption)?;
+            let new_readers: Vec<PublicKey> = current
+                .shared_readers()
+                .map_err(ScratchpadError::Decryption)?
+                .into_iter()
+                .filter(|reader| !readers.contains(reader))
+                .collect();
+            client
+                .scratchpad_reshare(
+                    owner,
+                    &current,
+                    current.data_encoding(),
+                    &data,
+                    &new_readers,
+                )
+                .await
+        })
+        .await
+    }
+
+    /// The current version of the owner's scratchpad, the one with the highest counter if forked
+    async fn scratchpad_get_latest(
+        &self,
+        owner: &SecretKey,
+    ) -> Result<Scratchpad, ScratchpadError> {
+        let address = ScratchpadAddress::new(owner.public_key());
+        match self.scratchpad_get(&address).await {
+            Ok(scratchpad) => Ok(scratchpad),
+            Err(ScratchpadError::NotFound(..)) => Err(ScratchpadError::CannotUpdateNewScratchpad),
+            Err(ScratchpadError::Fork(scratchpads)) => scratchpads
+                .into_iter()
+                .max_by_key(|scratchpad| scratchpad.counter())
+                .ok_or(ScratchpadError::Corrupt(address)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Store a new version of a shared scratchpad, with a fresh content key for the readers
+    async fn scratchpad_reshare(
+        &self,
+        owner: &SecretKey,
+        current: &Scratchpad,
+        content_type: u64,
+        data: &Bytes,
+        readers: &[PublicKey],
+    ) -> Result<Scratchpad, ScratchpadError> {
+        let new_counter = next_counter(current.address(), current.counter())?;
+        info!(
+            "Updating shared scratchpad at address {:?} to version {new_counter} for {} readers",
+            current.address(),
+            readers.len()
+        );
+        let scratchpad = Scratchpad::new_shared(owner, content_type, data, readers, new_counter)
+            .map_err(|_| ScratchpadError::Serialization)?;
+        self.scratchpad_put_update(scratchpad.clone()).await?;
+        Ok(scratchpad)
+    }
+
     /// Update an existing scratchpad to the network.
     /// The scratchpad needs to be created first with [`Client::scratchpad_create`].
     /// This operation is free as the scratchpad was already paid for at creation.
     /// Only the latest version of the scratchpad is kept on the Network, previous versions will be overwritten and unrecoverable.
+    ///
+    /// A forked scratchpad is resolved by the update itself, stored with a counter above those of
+    /// all the forks. With a [`ForkMerge`] set in [`ClientOperatingStrategy::scratchpad_fork_merge`],
+    /// the data is merged on top of the merged forks, see [`Client::scratchpad_resolve_fork`].
+    /// Otherwise the data replaces them. Fails with [`ScratchpadError::CounterOverflow`] once the
+    /// highest counter is reached, and with [`ScratchpadError::SharedScratchpad`] for a shared
+    /// scratchpad, see [`Client::scratchpad_update_shared`].
+    ///
+    /// [`ClientOperatingStrategy::scratchpad_fork_merge`]: crate::ClientOperatingStrategy::scratchpad_fork_merge
     pub async fn scratchpad_update(
         &self,
         owner: &SecretKey,
         content_type: u64,
         data: &Bytes,
+    ) -> Result<(), ScratchpadError> {
+        self.in_operation(OperationKind::ScratchpadUpdate, |client| async move {
+            client
+                .scratchpad_update_latest(owner, content_type, data)
+                .await
+        })
+        .await
+    }
+
+    async fn scratchpad_update_latest(
+        &self,
+        owner: &SecretKey,
+        content_type: u64,
+        data: &Bytes,
     ) -> Result<(), ScratchpadError> {
         let address = ScratchpadAddress::new(owner.public_key());
-        let current = match self.scratchpad_get(&address).await {
-            Ok(scratchpad) => Some(scratchpad),
-            Err(ScratchpadError::NotFound(..)) => None,
-            // forks should not stop updates as updates are here to resolve forks, hence the max_by_key
-            Err(ScratchpadError::Fork(scratchpads)) => scratchpads
-                .into_iter()
-                .max_by_key(|scratchpad: &Scratchpad| scratchpad.counter()),
+        let (counter, data) = match self.scratchpad_get(&address).await {
+            Ok(scratchpad) => {
+                ensure_not_shared(&scratchpad)?;
+                (scratchpad.counter(), data.clone())
+            }
+            Err(ScratchpadError::NotFound(..)) => {
+                warn!(
+                    "Scratchpad at address {address:?} cannot be updated as it does not exist, please create it first or wait for it to be created"
+                );
+                return Err(ScratchpadError::CannotUpdateNewScratchpad);
+            }
+            // forks should not stop updates as updates are here to resolve forks
+            Err(ScratchpadError::Fork(scratchpads)) => {
+                scratchpads.iter().try_for_each(ensure_not_shared)?;
+                match &self.config.scratchpad_fork_merge {
+                    Some(ForkMerge(merge)) => {
+                        let strategy = ForkStrategy::Merge(Arc::clone(merge));
+                        let (counter, _, merged) =
+                            resolve_fork_data(owner, &scratchpads, &strategy)?;
+                        (counter, merge(&[data.clone(), merged]))
+                    }
+                    None => {
+                        let counter = scratchpads
+                            .iter()
+                            .map(Scratchpad::counter)
+                            .max()
+                            .unwrap_or_default();
+                        (counter, data.clone())
+                    }
+                }
+            }
             Err(err) => {
                 return Err(err);
             }
         };
 
-        if let Some(p) = current {
-            let _new = self
-                .scratchpad_update_from(&p, owner, content_type, data)
-                .await?;
-            Ok(())
-        } else {
-            warn!(
-                "Scratchpad at address {address:?} cannot be updated as it does not exist, please create it first or wait for it to be created"
-            );
-            Err(ScratchpadError::CannotUpdateNewScratchpad)
-        }
+        let new_counter = next_counter(&address, counter)?;
+        info!("Updating scratchpad at address {address:?} to version {new_counter}");
+        let scratchpad = Scratchpad::new(owner, content_type, &data, new_counter);
+        self.scratchpad_put_update(scratchpad).await
+    }
+
+    /// Resolve a fork of the owner's scratchpad, as returned in [`ScratchpadError::Fork`], by
+    /// publishing the data picked by the strategy with a counter above those of all the forks.
+    ///
+    /// The forks not signed by the owner are ignored. This operation is free as the scratchpad was
+    /// already paid for at creation. Returns the new scratchpad.
+    pub async fn scratchpad_resolve_fork(
+        &self,
+        owner: &SecretKey,
+        forks: &[Scratchpad],
+        strategy: &ForkStrategy,
+    ) -> Result<Scratchpad, ScratchpadError> {
+        let (counter, data_encoding, data) = resolve_fork_data(owner, forks, strategy)?;
+        let address = ScratchpadAddress::new(owner.public_key());
+        let new_counter = next_counter(&address, counter)?;
+        info!(
+            "Resolving fork of {} scratchpads at address {address:?} with {strategy:?}, to version {new_counter}",
+            forks.len(),
+        );
+        let scratchpad = Scratchpad::new(owner, data_encoding, &data, new_counter);
+        self.scratchpad_put_update(scratchpad.clone()).await?;
+        Ok(scratchpad)
     }
 
     /// Update an existing scratchpad from a specific scratchpad
@@ -376,6 +735,7 @@ impl Client {
     /// This function is used internally by [`Client::scratchpad_update`] after the scratchpad has been retrieved from the network.
     /// To skip the retrieval step if you already have the scratchpad, use this function directly
     /// This function will return the new scratchpad after it has been updated
+    /// A shared scratchpad is refused with [`ScratchpadError::SharedScratchpad`].
     pub async fn scratchpad_update_from(
         &self,
         current: &Scratchpad,
@@ -384,8 +744,9 @@ impl Client {
         data: &Bytes,
     ) -> Result<Scratchpad, ScratchpadError> {
         // prepare the new scratchpad to be stored
+        ensure_not_shared(current)?;
         let address = ScratchpadAddress::new(owner.public_key());
-        let new_counter = current.counter() + 1;
+        let new_counter = next_counter(&address, current.counter())?;
         info!("Updating scratchpad at address {address:?} to version {new_counter}");
         let scratchpad = Scratchpad::new(owner, content_type, data, new_counter);
 
@@ -395,6 +756,70 @@ impl Client {
         Ok(scratchpad)
     }
 
+    /// Update an existing scratchpad only if its counter on the network is `expected_counter`, as a
+    /// compare-and-swap.
+    ///
+    /// The new version is stored in strict mode, nodes rejecting it unless its counter is exactly one
+    /// above the one they hold, so concurrent writers cannot overwrite each other's updates. Fails
+    /// with [`ScratchpadError::CounterConflict`] when another version was written in the meantime,
+    /// in which case the caller can fetch the scratchpad again and retry. A forked scratchpad fails
+    /// with [`ScratchpadError::Fork`], see [`Client::scratchpad_resolve_fork`], and a shared one with
+    /// [`ScratchpadError::SharedScratchpad`]. Returns the new scratchpad.
+    pub async fn scratchpad_update_if(
+        &self,
+        owner: &SecretKey,
+        expected_counter: u64,
+        content_type: u64,
+        data: &Bytes,
+    ) -> Result<Scratchpad, ScratchpadError> {
+        self.in_operation(OperationKind::ScratchpadUpdate, |client| async move {
+            let address = ScratchpadAddress::new(owner.public_key());
+            let current = match client.scratchpad_get(&address).await {
+                Ok(scratchpad) => {
+                    ensure_not_shared(&scratchpad)?;
+                    scratchpad.counter()
+                }
+                Err(ScratchpadError::NotFound(..)) => {
+                    return Err(ScratchpadError::CannotUpdateNewScratchpad);
+                }
+                // forks are returned as is, they must be resolved before a compare-and-swap
+                Err(err) => return Err(err),
+            };
+            if current != expected_counter {
+                return Err(ScratchpadError::CounterConflict {
+                    expected: expected_counter,
+                    found: current,
+                });
+            }
+
+            let new_counter = next_counter(&address, expected_counter)?;
+            info!("Updating scratchpad at address {address:?} from version {expected_counter} to {new_counter}");
+            let scratchpad = Scratchpad::new(owner, content_type, data, new_counter);
+            let put_result = client
+                .put_scratchpad_record(
+                    scratchpad.clone(),
+                    RecordKind::StrictUpdate(DataTypes::Scratchpad),
+                )
+                .await;
+
+            if let Err(err) = put_result {
+                // put errors do not tell which nodes refused the counter, so check what was stored
+                match client.scratchpad_get(&address).await {
+                    Ok(stored) if stored == scratchpad => {}
+                    Ok(stored) if stored.counter() != expected_counter => {
+                        return Err(ScratchpadError::CounterConflict {
+                            expected: expected_counter,
+                            found: stored.counter(),
+                        });
+                    }
+                    _ => return Err(err),
+                }
+            }
+            Ok(scratchpad)
+        })
+        .await
+    }
+
     /// Store a fully formed, pre-signed scratchpad verbatim after verification.
     /// This method is intended for updates and does not require payment.
     ///
@@ -406,6 +831,15 @@ impl Client {
     pub async fn scratchpad_put_update(
         &self,
         scratchpad: Scratchpad,
+    ) -> Result<(), ScratchpadError> {
+        self.put_scratchpad_record(scratchpad, RecordKind::DataOnly(DataTypes::Scratchpad))
+            .await
+    }
+
+    async fn put_scratchpad_record(
+        &self,
+        scratchpad: Scratchpad,
+        record_kind: RecordKind,
     ) -> Result<(), ScratchpadError> {
         let address = scratchpad.address();
         Self::scratchpad_verify(&scratchpad)?;
@@ -414,7 +848,7 @@ impl Client {
         let net_addr = NetworkAddress::from(*address);
         let record = Record {
             key: net_addr.to_record_key(),
-            value: try_serialize_record(&scratchpad, RecordKind::DataOnly(DataTypes::Scratchpad))
+            value: try_serialize_record(&scratchpad, record_kind)
                 .map_err(|_| ScratchpadError::Serialization)?
                 .to_vec(),
             publisher: None,
@@ -476,3 +910,88 @@ impl Client {
         Ok(total_cost)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forks(owner: &SecretKey, data: &[&'static [u8]]) -> Vec<Scratchpad> {
+        data.iter()
+            .map(|data| Scratchpad::new(owner, 1, &Bytes::from_static(data), 3))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_fork_is_deterministic() -> Result<(), ScratchpadError> {
+        let owner = SecretKey::random();
+        let mut versions = forks(&owner, &[b"laptop", b"phone"]);
+        // a scratchpad of another owner is not part of the fork
+        versions.push(Scratchpad::new(
+            &SecretKey::random(),
+            1,
+            &Bytes::from_static(b"intruder"),
+            9,
+        ));
+
+        let (counter, encoding, data) =
+            resolve_fork_data(&owner, &versions, &ForkStrategy::HighestSignature)?;
+        versions.reverse();
+        let (_, _, reversed) =
+            resolve_fork_data(&owner, &versions, &ForkStrategy::HighestSignature)?;
+        assert_eq!((counter, encoding), (3, 1));
+        assert_eq!(data, reversed);
+        assert_ne!(data, Bytes::from_static(b"intruder"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_fork_strategies() -> Result<(), ScratchpadError> {
+        let owner = SecretKey::random();
+        let versions = forks(&owner, &[b"2:phone", b"7:laptop", b"tablet"]);
+
+        let timestamp: EmbeddedTimestamp = Arc::new(|data: &Bytes| {
+            let text = std::str::from_utf8(data).ok()?;
+            text.split_once(':')?.0.parse().ok()
+        });
+        let (_, _, latest) =
+            resolve_fork_data(&owner, &versions, &ForkStrategy::LastWriter(timestamp))?;
+        assert_eq!(latest, Bytes::from_static(b"7:laptop"));
+
+        let merge: MergeData = Arc::new(|payloads: &[Bytes]| {
+            let mut lines: Vec<&[u8]> = payloads.iter().map(|data| data.as_ref()).collect();
+            lines.sort();
+            Bytes::from(lines.join(&b'\n'))
+        });
+        let (_, _, merged) = resolve_fork_data(&owner, &versions, &ForkStrategy::Merge(merge))?;
+        assert_eq!(merged, Bytes::from_static(b"2:phone\n7:laptop\ntablet"));
+
+        assert!(matches!(
+            resolve_fork_data(&owner, &[], &ForkStrategy::HighestSignature),
+            Err(ScratchpadError::NoForkToResolve)
+        ));
+        Ok(())
+    }
+    #[test]
+    fn test_next_counter_does_not_overflow() {
+        let address = ScratchpadAddress::new(SecretKey::random().public_key());
+        assert!(matches!(next_counter(&address, 3), Ok(4)));
+        assert!(matches!(
+            next_counter(&address, u64::MAX),
+            Err(ScratchpadError::CounterOverflow(overflowed)) if overflowed == address
+        ));
+    }
+
+    #[test]
+    fn test_plain_update_refuses_shared_scratchpads() {
+        let owner = SecretKey::random();
+        let data = Bytes::from_static(b"shared notes");
+        let shared =
+            Scratchpad::new_shared(&owner, 1, &data, &[SecretKey::random().public_key()], 0)
+                .expect("shared scratchpad");
+        assert!(matches!(
+            ensure_not_shared(&shared),
+            Err(ScratchpadError::SharedScratchpad(address)) if address == *shared.address()
+        ));
+        assert!(ensure_not_shared(&Scratchpad::new(&owner, 1, &data, 0)).is_ok());
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::Client;
+use crate::client::data_types::chunk::ChunkAddress;
+use ant_evm::{Amount, TxHash};
+use libp2p::PeerId;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::mpsc::error::TrySendError;
+
+static NEXT_OPERATION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Identifies a client operation in the [`ClientEvent`]s it emits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OperationId(pub u64);
+
+impl OperationId {
+    fn next() -> Self {
+        Self(NEXT_OPERATION_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl std::fmt::Display for OperationId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "op-{}", self.0)
+    }
+}
+
+/// Kind of a client operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    /// Upload of data, a file or a directory
+    Upload,
+    /// Download of data, a file or a directory
+    Download,
+    /// Update of a pointer
+    PointerUpdate,
+    /// Update of a scratchpad
+    ScratchpadUpdate,
+    /// Update of a register
+    RegisterUpdate,
+    /// Write to a vault
+    VaultWrite,
+}
+
+/// Events that can be sent by the client, see [`Client::enable_client_events`].
+///
+/// Uploads, downloads, pointer, scratchpad and register updates and vault writes are operations:
+/// they emit [`ClientEvent::OperationStarted`], then progress events tagged with their [`OperationId`],
+/// and [`ClientEvent::OperationFinished`]. Nested operations, e.g. the upload of a directory's files,
+/// are reported as part of the outer operation.
+///
+/// Progress events are dropped rather than slowing the operation down if the receiver falls behind.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ClientEvent {
+    UploadComplete(UploadSummary),
+    OperationStarted {
+        op: OperationId,
+        kind: OperationKind,
+    },
+    /// The operation is over, `error` being set if it failed
+    OperationFinished {
+        op: OperationId,
+        error: Option<String>,
+    },
+    /// Quotes were requested for these many addresses
+    QuotesRequested {
+        op: OperationId,
+        addresses: usize,
+    },
+    /// Quotes were received: `to_pay` addresses need a payment, the others are already stored
+    QuotesReceived {
+        op: OperationId,
+        to_pay: usize,
+        already_paid: usize,
+    },
+    /// A payment transaction for these many quotes was submitted
+    PaymentSubmitted {
+        op: OperationId,
+        quotes: usize,
+    },
+    /// The payment transactions were confirmed
+    PaymentConfirmed {
+        op: OperationId,
+        transactions: Vec<TxHash>,
+    },
+    /// A chunk was stored on the network, sent to these peers
+    ChunkStored {
+        op: OperationId,
+        address: ChunkAddress,
+        peers: Vec<PeerId>,
+    },
+    /// A chunk failed to be stored and is scheduled for a retry
+    ChunkRetried {
+        op: OperationId,
+        address: ChunkAddress,
+    },
+    /// A chunk was fetched from the network, or loaded from the chunk cache
+    ChunkDownloaded {
+        op: OperationId,
+        address: ChunkAddress,
+        from_cache: bool,
+    },
+    /// Chunks were fetched and handed over to decryption
+    DecryptionProgress {
+        op: OperationId,
+        chunks: usize,
+        total_chunks: usize,
+    },
+}
+
+impl ClientEvent {
+    /// The operation which emitted the event, if any.
+    pub fn operation(&self) -> Option<OperationId> {
+        match self {
+            ClientEvent::UploadComplete(_) => None,
+            ClientEvent::OperationStarted { op, .. }
+            | ClientEvent::OperationFinished { op, .. }
+            | ClientEvent::QuotesRequested { op, .. }
+            | ClientEvent::QuotesReceived { op, .. }
+            | ClientEvent::PaymentSubmitted { op, .. }
+            | ClientEvent::PaymentConfirmed { op, .. }
+            | ClientEvent::ChunkStored { op, .. }
+            | ClientEvent::ChunkRetried { op, .. }
+            | ClientEvent::ChunkDownloaded { op, .. }
+            | ClientEvent::DecryptionProgress { op, .. } => Some(*op),
+        }
+    }
+}
+
+/// Summary of an upload operation.
+#[derive(Debug, Clone)]
+pub struct UploadSummary {
+    /// Records that were uploaded to the network
+    pub records_paid: usize,
+    /// Records that were already paid for so were not re-uploaded
+    pub records_already_paid: usize,
+    /// Total cost of the upload
+    pub tokens_spent: Amount,
+}
+
+impl Client {
+    /// Run `f` as an operation of the given kind, with a client tagging the events it emits.
+    /// If this client is already running an operation, `f` is reported as part of it.
+    pub(crate) async fn in_operation<T, E, F, Fut>(&self, kind: OperationKind, f: F) -> Result<T, E>
+    where
+        E: std::fmt::Display,
+        F: FnOnce(Client) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        if self.operation.is_some() || self.client_event_sender.is_none() {
+            return f(self.with_operation_spend()).await;
+        }
+
+        let op = OperationId::next();
+        let mut client = self.with_operation_spend();
+        client.operation = Some(op);
+        debug!("Starting {kind:?} operation {op}");
+        client.send_event(|op| ClientEvent::OperationStarted { op, kind });
+
+        let result = f(client.clone()).await;
+
+        let error = result.as_ref().err().map(|err| err.to_string());
+        debug!("Finished {kind:?} operation {op}, error: {error:?}");
+        client.send_event(|op| ClientEvent::OperationFinished { op, error });
+        result
+    }
+
+    /// Emit a progress event of the current operation, if any.
+    pub(crate) fn send_event(&self, event: impl FnOnce(OperationId) -> ClientEvent) {
+        let (Some(sender), Some(op)) = (&self.client_event_sender, self.operation) else {
+            return;
+        };
+        match sender.try_send(event(op)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(event)) => {
+                debug!("Client event channel is full, dropping event {event:?}");
+            }
+            Err(TrySendError::Closed(_)) => {
+                trace!("Client event receiver dropped");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_ids_are_unique() {
+        let first = OperationId::next();
+        let second = OperationId::next();
+        assert_ne!(first, second);
+        assert_eq!(
+            ClientEvent::QuotesRequested {
+                op: first,
+                addresses: 1
+            }
+            .operation(),
+            Some(first)
+        );
+    }
+}
@@ -1,19 +1,124 @@
 use crate::Client;
 use crate::client::PutError;
-use crate::client::quote::DataTypes;
+use crate::client::config::CHUNK_UPLOAD_BATCH_SIZE;
+use crate::client::payment::{Receipt, receipt_from_store_quotes};
+use crate::client::quote::{DataTypes, StoreQuote};
 use crate::self_encryption::encrypt;
-use ant_evm::QuotePayment;
+use crate::utils::process_tasks_with_max_concurrency;
+use ant_evm::payment_vault::verify_data_payment;
+use ant_evm::{Amount, AttoTokens, ClientProofOfPayment, EvmNetwork, QuotePayment, RewardsAddress};
 use ant_protocol::storage::Chunk;
 use bytes::Bytes;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 use xor_name::XorName;
 
 #[allow(unused_imports)]
 pub use ant_evm::external_signer::*;
+pub use ant_evm::{QuoteHash, TxHash};
+pub use evmlib::common::Calldata;
 
 use super::quote::QuoteForAddress;
 
+/// Version of the [`PaymentRequest`] format, bumped on incompatible changes.
+const PAYMENT_REQUEST_VERSION: u8 = 1;
+
+/// Errors that can occur while creating or completing a [`PaymentRequest`].
+#[derive(Debug, thiserror::Error)]
+pub enum PaymentRequestError {
+    #[error("Failed to get quotes: {0}")]
+    Quotes(#[from] PutError),
+    #[error("Failed to create the payment transactions: {0}")]
+    Calldata(#[from] Error),
+    #[error("Unsupported payment request version {0}, expected {PAYMENT_REQUEST_VERSION}")]
+    UnsupportedVersion(u8),
+    #[error("The payment request is for the {request} EVM network, but the client uses {client}")]
+    EvmNetworkMismatch { request: String, client: String },
+    #[error("The data does not match the chunks of the payment request")]
+    ManifestMismatch,
+    #[error("Failed to check the payment transaction: {0}")]
+    Transaction(Error),
+    #[error("{unpaid} quotes of the payment request were not paid by the given transactions")]
+    UnpaidQuotes { unpaid: usize },
+    #[error("The payment for {address:?} was rejected by the payment vault: {reason}")]
+    InvalidPayment { address: XorName, reason: String },
+}
+
+/// A transaction to sign and send with the paying wallet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    /// The contract called by the transaction
+    pub to: RewardsAddress,
+    /// The input of the transaction
+    pub calldata: Calldata,
+}
+
+/// A payment transaction and the quotes it pays for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentTransaction {
+    pub transaction: UnsignedTransaction,
+    pub quote_hashes: Vec<QuoteHash>,
+}
+
+/// Everything needed to pay for an upload with a wallet kept on another machine.
+///
+/// The request is created on a machine connected to the network with
+/// [`Client::create_payment_request`], then serialized and carried over to the machine holding the
+/// wallet keys. There, the [`PaymentRequest::approve`] transaction and each of the
+/// [`PaymentRequest::transactions`] are signed and sent, in that order. Once their hashes are back
+/// on the connected machine, [`Client::payment_request_receipt`] checks the payments and gives the
+/// receipt to upload the data with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentRequest {
+    pub version: u8,
+    /// The EVM network the transactions are to be sent on
+    pub evm_network: EvmNetwork,
+    /// Index of the [`DataTypes`] of the uploaded data
+    pub data_type: u32,
+    /// The content addresses of the uploaded data and their sizes, including those already stored
+    pub manifest: Vec<(XorName, usize)>,
+    /// The quotes of the addresses to pay for, which become the proofs of payment once paid
+    pub quotes: Vec<(XorName, ClientProofOfPayment, AttoTokens)>,
+    /// Allows the payment vault to spend the tokens paid for the quotes
+    pub approve: UnsignedTransaction,
+    /// Transactions paying for the quotes, as many as needed to fit the transfers
+    pub transactions: Vec<PaymentTransaction>,
+    /// Total paid for the quotes
+    pub total_cost: AttoTokens,
+}
+
+impl PaymentRequest {
+    /// Check the request can be completed by a client using `evm_network`.
+    pub fn check(&self, evm_network: &EvmNetwork) -> Result<(), PaymentRequestError> {
+        if self.version != PAYMENT_REQUEST_VERSION {
+            return Err(PaymentRequestError::UnsupportedVersion(self.version));
+        }
+        if &self.evm_network != evm_network {
+            return Err(PaymentRequestError::EvmNetworkMismatch {
+                request: self.evm_network.to_string(),
+                client: evm_network.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether the content addresses are the ones the request was created for.
+    pub fn matches_manifest(&self, content_addrs: &[(XorName, usize)]) -> bool {
+        let manifest: HashSet<&(XorName, usize)> = self.manifest.iter().collect();
+        content_addrs.len() == self.manifest.len()
+            && content_addrs.iter().all(|addr| manifest.contains(addr))
+    }
+
+    /// The receipt holding the quotes of the request, whether they were paid or not.
+    fn receipt(&self) -> Receipt {
+        self.quotes
+            .iter()
+            .map(|(addr, proof, price)| (*addr, (proof.clone(), *price)))
+            .collect()
+    }
+}
+
 impl Client {
     /// Get quotes for data.
     /// Returns a cost map, data payments to be executed and a list of free (already paid for) chunks.
@@ -46,6 +151,140 @@ impl Client {
     }
 }
 
+impl Client {
+    /// Import the hashes of the transactions sent for a [`PaymentRequest`], returning the receipt
+    /// to upload its data with.
+    ///
+    /// The quotes paid by the transactions are read from their payment vault events, and must cover
+    /// all the quotes of the request. Each payment is then verified by the payment vault, as the
+    /// nodes do, so that nothing is uploaded before everything is paid.
+    pub async fn payment_request_receipt(
+        &self,
+        request: &PaymentRequest,
+        tx_hashes: &[TxHash],
+    ) -> Result<Receipt, PaymentRequestError> {
+        request.check(self.evm_network())?;
+
+        let mut paid = HashSet::new();
+        for tx_hash in tx_hashes {
+            let quote_hashes = paid_quote_hashes(self.evm_network(), *tx_hash)
+                .await
+                .map_err(PaymentRequestError::Transaction)?;
+            info!(
+                "Transaction {tx_hash:?} paid for {} quotes",
+                quote_hashes.len()
+            );
+            paid.extend(quote_hashes);
+        }
+        let unpaid = request
+            .transactions
+            .iter()
+            .flat_map(|transaction| &transaction.quote_hashes)
+            .filter(|quote_hash| !paid.contains(*quote_hash))
+            .count();
+        if unpaid > 0 {
+            return Err(PaymentRequestError::UnpaidQuotes { unpaid });
+        }
+
+        let verifications = request.quotes.iter().map(|(address, proof, _)| async move {
+            verify_data_payment(
+                self.evm_network(),
+                vec![],
+                proof.to_proof_of_payment().digest(),
+            )
+            .await
+            .map_err(|err| PaymentRequestError::InvalidPayment {
+                address: *address,
+                reason: err.to_string(),
+            })
+        });
+        for result in
+            process_tasks_with_max_concurrency(verifications, *CHUNK_UPLOAD_BATCH_SIZE).await
+        {
+            result?;
+        }
+        Ok(request.receipt())
+    }
+
+    /// Create a request to pay for storing the content addresses with a wallet kept on another
+    /// machine, see [`PaymentRequest`].
+    pub async fn create_payment_request(
+        &self,
+        data_type: DataTypes,
+        content_addrs: impl Iterator<Item = (XorName, usize)> + Clone,
+    ) -> Result<PaymentRequest, PaymentRequestError> {
+        let manifest: Vec<(XorName, usize)> = content_addrs.clone().collect();
+        let (quotes, quote_payments, free_chunks) = self
+            .get_quotes_for_content_addresses(data_type, content_addrs)
+            .await?;
+        debug!(
+            "Payment request for {} addresses, {} already stored",
+            manifest.len(),
+            free_chunks.len()
+        );
+
+        // payments with 0 amount are not sent to the network
+        let quote_payments: Vec<QuotePayment> = quote_payments
+            .into_iter()
+            .filter(|(_, _, amount)| *amount > Amount::ZERO)
+            .collect();
+        let calldata = pay_for_quotes_calldata(self.evm_network(), quote_payments)?;
+        let (approve_calldata, approve_to) = approve_to_spend_tokens_calldata(
+            self.evm_network(),
+            calldata.approve_spender,
+            calldata.approve_amount,
+        );
+        let transactions = calldata
+            .batched_calldata_map
+            .into_iter()
+            .map(|(calldata_batch, quote_hashes)| PaymentTransaction {
+                transaction: UnsignedTransaction {
+                    to: calldata.to,
+                    calldata: calldata_batch,
+                },
+                quote_hashes,
+            })
+            .collect();
+
+        Ok(PaymentRequest {
+            version: PAYMENT_REQUEST_VERSION,
+            evm_network: self.evm_network().clone(),
+            data_type: data_type.get_index(),
+            manifest,
+            quotes: receipt_from_store_quotes(StoreQuote(quotes))
+                .into_iter()
+                .map(|(addr, (proof, price))| (addr, proof, price))
+                .collect(),
+            approve: UnsignedTransaction {
+                to: approve_to,
+                calldata: approve_calldata,
+            },
+            transactions,
+            total_cost: AttoTokens::from_atto(calldata.approve_amount),
+        })
+    }
+}
+
+/// The content addresses to pay for when uploading the data with [`Client::data_put`], or with
+/// [`Client::data_put_public`] when `public`, along with their sizes.
+///
+/// The data is encrypted in memory.
+pub fn data_content_addrs(
+    data: Bytes,
+    public: bool,
+) -> Result<Vec<(XorName, usize)>, crate::self_encryption::Error> {
+    let (data_map_chunk, chunks) = encrypt_data(data)?;
+    let mut content_addrs: Vec<(XorName, usize)> = chunks
+        .iter()
+        .map(|chunk| (*chunk.name(), chunk.size()))
+        .collect();
+    // the data map of private data is kept by the uploader
+    if public {
+        content_addrs.push((*data_map_chunk.name(), data_map_chunk.size()));
+    }
+    Ok(content_addrs)
+}
+
 /// Encrypts data as chunks.
 ///
 /// Returns the datamap chunk and file chunks.
@@ -57,3 +296,45 @@ pub fn encrypt_data(data: Bytes) -> Result<(Chunk, Vec<Chunk>), crate::self_encr
 
     Ok((result.0, result.1))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payment_request(manifest: Vec<(XorName, usize)>) -> PaymentRequest {
+        let transaction = UnsignedTransaction {
+            to: RewardsAddress::ZERO,
+            calldata: Calldata::new(),
+        };
+        PaymentRequest {
+            version: PAYMENT_REQUEST_VERSION,
+            evm_network: EvmNetwork::ArbitrumOne,
+            data_type: DataTypes::Chunk.get_index(),
+            manifest,
+            quotes: vec![],
+            approve: transaction.clone(),
+            transactions: vec![PaymentTransaction {
+                transaction,
+                quote_hashes: vec![QuoteHash::repeat_byte(1)],
+            }],
+            total_cost: AttoTokens::zero(),
+        }
+    }
+
+    #[test]
+    fn test_payment_request_completion() {
+        let addr = XorName::random(&mut rand::thread_rng());
+        let request = payment_request(vec![(addr, 10)]);
+
+        assert!(request.check(&EvmNetwork::ArbitrumOne).is_ok());
+        assert!(matches!(
+            request.check(&EvmNetwork::ArbitrumSepoliaTest),
+            Err(PaymentRequestError::EvmNetworkMismatch { .. })
+        ));
+
+        assert!(request.matches_manifest(&[(addr, 10)]));
+        assert!(!request.matches_manifest(&[(addr, 11)]));
+        assert!(!request.matches_manifest(&[]));
+        assert!(request.receipt().is_empty());
+    }
+}
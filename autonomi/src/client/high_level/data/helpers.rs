@@ -8,17 +8,18 @@
 
 use crate::Client;
 use crate::client::config::UPLOAD_FLOW_BATCH_SIZE;
+use crate::client::data_types::chunk::ChunkAddress;
 use crate::client::payment::PayError::EvmWalletError;
 use crate::client::payment::PaymentOption;
 use crate::client::payment::Receipt;
-use crate::client::{ClientEvent, PutError, UploadSummary};
+use crate::client::put_error_state::interrupted_upload;
+use crate::client::{ClientEvent, OperationKind, PutError, UploadSummary};
 use crate::self_encryption::EncryptionStream;
 use crate::utils::format_upload_error;
 use ant_evm::{Amount, AttoTokens};
 use ant_protocol::storage::{Chunk, DataTypes};
 use evmlib::wallet::Error::InsufficientTokensForQuotes;
 use std::time::Duration;
-use tokio::time::sleep;
 
 type AggregatedChunks = Vec<((String, usize, usize), Chunk)>;
 
@@ -52,13 +53,26 @@ impl Client {
         AttoTokens::from_atto(total_tokens)
     }
 
-    /// Processes file uploads with payment in batches
+    /// Processes file uploads with payment in batches, as an [`OperationKind::Upload`] operation
     /// Will try to carry out retry if `retry_failed` configured
     /// Returns total cost of uploads or error, once completed or cann't recover from failures
     pub(crate) async fn pay_and_upload(
         &self,
         payment_option: PaymentOption,
         encryption_streams: &mut [EncryptionStream],
+    ) -> Result<AttoTokens, PutError> {
+        self.in_operation(OperationKind::Upload, |client| async move {
+            client
+                .pay_and_upload_streams(payment_option, encryption_streams)
+                .await
+        })
+        .await
+    }
+
+    async fn pay_and_upload_streams(
+        &self,
+        payment_option: PaymentOption,
+        encryption_streams: &mut [EncryptionStream],
     ) -> Result<AttoTokens, PutError> {
         let start = tokio::time::Instant::now();
         let total_files = encryption_streams.len();
@@ -131,6 +145,7 @@ impl Client {
         let mut processed_chunks = 0;
         let mut total_free_chunks = 0;
         let mut receipts = vec![];
+        let mut stored = vec![];
 
         // Allow up to `retry_failed` * est_total_chunks total uploads to be attempted
         let mut retry_on_failure = true;
@@ -139,9 +154,15 @@ impl Client {
             est_total_todo + std::cmp::max(20, est_total_todo * self.retry_failed as usize);
 
         // Process all chunks for this file in batches
-        let mut current_batch = vec![];
+        let mut current_batch: AggregatedChunks = vec![];
 
         loop {
+            // Stop scheduling new chunks once interrupted, the chunks left in the batch were paid for
+            if let Err(reason) = self.operation_options.check() {
+                let pending = current_batch.iter().map(|(_, chunk)| *chunk.address());
+                return Err(interrupted_upload(reason, stored, pending, receipts));
+            }
+
             // Get next batch if current_batch has space and file has more chunks
             if let Some(next_batch) = file.next_batch(*UPLOAD_FLOW_BATCH_SIZE - current_batch.len())
             {
@@ -165,6 +186,10 @@ impl Client {
             }
 
             attempted_uploads += current_batch.len();
+            let batch_addrs: Vec<ChunkAddress> = current_batch
+                .iter()
+                .map(|(_, chunk)| *chunk.address())
+                .collect();
 
             let (retry_chunks, receipt, free_chunks_count, put_error) = self
                 .process_chunk_batch(current_batch, payment_option.clone(), retry_on_failure)
@@ -176,6 +201,11 @@ impl Client {
             if let Some(err) = put_error {
                 return Err(err);
             }
+            stored.extend(batch_addrs.into_iter().filter(|addr| {
+                !retry_chunks
+                    .iter()
+                    .any(|(_, chunk)| chunk.address() == addr)
+            }));
 
             // retry failed chunks
             if !retry_chunks.is_empty() {
@@ -190,7 +220,7 @@ impl Client {
                 info!("Encountered upload failure, take 1 minute pause before continue...");
 
                 // Wait 1 minute before retry
-                sleep(Duration::from_secs(60)).await;
+                self.operation_options.sleep(Duration::from_secs(60)).await;
                 #[cfg(feature = "loud")]
                 println!("🔄 continue with upload...");
                 info!("🔄 continue with upload...");
@@ -295,6 +325,12 @@ impl Client {
                         upload_state.failed.iter().map(|(addr, _)| *addr).collect();
                     // Filter out failed entries
                     batch.retain(|(_, chunk)| failed_chunks.contains(chunk.address()));
+                    for (_, chunk) in batch.iter() {
+                        self.send_event(|op| ClientEvent::ChunkRetried {
+                            op,
+                            address: *chunk.address(),
+                        });
+                    }
                     // Push back failed entries
                     retry_chunks.extend(batch);
                 } else {
@@ -16,9 +16,12 @@ pub mod private;
 pub mod public;
 
 mod helpers;
+mod range;
 mod stream;
+mod verify;
 
 pub use stream::DataStream;
+pub use verify::{ChunkReplication, RepairOutcome, ReplicationStatus, VerifyReport};
 
 /// A [`DataAddress`] which points to a DataMap
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
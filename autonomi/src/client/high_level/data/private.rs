@@ -11,8 +11,9 @@ use std::time::Instant;
 use crate::AttoTokens;
 use crate::Client;
 use crate::client::payment::PaymentOption;
-use crate::client::{GetError, PutError};
+use crate::client::{GetError, OperationKind, PutError};
 use crate::self_encryption::EncryptionStream;
+use tokio::io::AsyncRead;
 
 pub use crate::Bytes;
 pub use crate::client::data_types::chunk::DataMapChunk;
@@ -35,6 +36,13 @@ impl Client {
     /// # }
     /// ```
     pub async fn data_get(&self, data_map: &DataMapChunk) -> Result<Bytes, GetError> {
+        self.in_operation(OperationKind::Download, |client| async move {
+            client.fetch_data(data_map).await
+        })
+        .await
+    }
+
+    async fn fetch_data(&self, data_map: &DataMapChunk) -> Result<Bytes, GetError> {
         info!(
             "Fetching private data from datamap {:?}",
             data_map.0.address()
@@ -128,6 +136,93 @@ impl Client {
             .await
             .map(|total_cost| (total_cost, data_map_chunk))
     }
+
+    /// Upload private data read from any [`AsyncRead`] source, such as a socket, a pipe or stdin.
+    /// This data will be self-encrypted, the [`DataMapChunk`] is not uploaded to the network.
+    ///
+    /// Self-encryption needs the total size of the data upfront. When `size_hint` is given for large
+    /// data, the data is encrypted and uploaded as it is read; if the reader then yields a different
+    /// number of bytes, the upload fails after the chunks read so far were paid for and uploaded.
+    /// Otherwise the reader is drained before anything is paid for: small data is kept in memory,
+    /// large data of unknown size is staged in a temporary file, and a size mismatch with
+    /// `size_hint` fails the upload before paying anything.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use autonomi::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = Client::init().await?;
+    /// # let wallet = todo!();
+    /// let file = tokio::fs::File::open("dump.sql").await?;
+    /// let size = file.metadata().await?.len();
+    /// let (total_cost, data_map) = client.data_put_stream(file, Some(size), wallet).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn data_put_stream<R>(
+        &self,
+        reader: R,
+        size_hint: Option<u64>,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, DataMapChunk), PutError>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        self.stream_put(reader, size_hint, payment_option, false)
+            .await
+    }
+
+    /// Encrypt and upload the data read from `reader`, returning the cost and the datamap chunk.
+    pub(crate) async fn stream_put<R>(
+        &self,
+        reader: R,
+        size_hint: Option<u64>,
+        payment_option: PaymentOption,
+        is_public: bool,
+    ) -> Result<(AttoTokens, DataMapChunk), PutError>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        self.in_operation(OperationKind::Upload, |client| async move {
+            client
+                .encrypt_and_upload_stream(reader, size_hint, payment_option, is_public)
+                .await
+        })
+        .await
+    }
+
+    async fn encrypt_and_upload_stream<R>(
+        &self,
+        reader: R,
+        size_hint: Option<u64>,
+        payment_option: PaymentOption,
+        is_public: bool,
+    ) -> Result<(AttoTokens, DataMapChunk), PutError>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        let size = size_hint.map(|size| size as usize);
+        let mut chunk_stream = EncryptionStream::new_from_reader(reader, size, is_public).await?;
+
+        let (processed_chunks, free_chunks, receipts) = self
+            .pay_and_upload_file(payment_option, &mut chunk_stream)
+            .await?;
+
+        // the stream ends without datamap if the reader failed or did not yield the expected size
+        let data_map_chunk = chunk_stream.data_map_chunk().ok_or_else(|| {
+            crate::self_encryption::Error::Stream(format!(
+                "No datamap produced, the reader may have failed or not yielded the {} bytes expected",
+                size_hint.unwrap_or_default()
+            ))
+        })?;
+
+        let total_cost = self
+            .calculate_total_cost(processed_chunks, receipts, free_chunks)
+            .await;
+        Ok((total_cost, data_map_chunk))
+    }
 }
 
 #[cfg(test)]
@@ -9,10 +9,11 @@
 use ant_protocol::storage::DataTypes;
 use bytes::Bytes;
 use std::time::Instant;
+use tokio::io::AsyncRead;
 
 use crate::client::payment::PaymentOption;
 use crate::client::quote::CostError;
-use crate::client::{GetError, PutError};
+use crate::client::{GetError, OperationKind, PutError};
 use crate::data::DataStream;
 use crate::self_encryption::EncryptionStream;
 use crate::{
@@ -29,6 +30,13 @@ impl Client {
     /// Fetch a blob of public data from the network. In-memory only - fails for large files.
     /// Use file_download_public for large files that need streaming.
     pub async fn data_get_public(&self, addr: &DataAddress) -> Result<Bytes, GetError> {
+        self.in_operation(OperationKind::Download, |client| async move {
+            client.fetch_public_data(addr).await
+        })
+        .await
+    }
+
+    async fn fetch_public_data(&self, addr: &DataAddress) -> Result<Bytes, GetError> {
         info!("Fetching public data from Data Address: {addr:?}");
         let datamap_chunk =
             DataMapChunk(self.chunk_get(&ChunkAddress::new(*addr.xorname())).await?);
@@ -83,6 +91,27 @@ impl Client {
             .map(|total_cost| (total_cost, data_address))
     }
 
+    /// Upload public data read from any [`AsyncRead`] source, such as a socket, a pipe or stdin.
+    ///
+    /// See [`Client::data_put_stream`] for the meaning of `size_hint`.
+    /// Returns the Data Address at which the data was stored.
+    pub async fn data_put_stream_public<R>(
+        &self,
+        reader: R,
+        size_hint: Option<u64>,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, DataAddress), PutError>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        let (total_cost, data_map_chunk) = self
+            .stream_put(reader, size_hint, payment_option, true)
+            .await?;
+        let data_address = DataAddress::new(*data_map_chunk.0.address().xorname());
+        info!("Uploaded public data stream at: {data_address:?}");
+        Ok((total_cost, data_address))
+    }
+
     /// Get the estimated cost of storing a piece of data.
     pub async fn data_cost(&self, data: Bytes) -> Result<AttoTokens, CostError> {
         let content_addrs = self.get_content_addrs(data)?;
@@ -0,0 +1,239 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::Client;
+use crate::client::data_types::chunk::{ChunkAddress, DataMapChunk};
+use crate::client::{ClientEvent, GetError, OperationKind};
+use bytes::Bytes;
+use self_encryption::{ChunkInfo, DataMap};
+use std::collections::HashMap;
+use std::ops::Range;
+use xor_name::XorName;
+
+use super::DataAddress;
+
+impl Client {
+    /// Fetch a byte range of (private) data from the network.
+    ///
+    /// Only the chunks covering the requested range are fetched and decrypted, which makes this suitable
+    /// for seeking into large data such as videos or reading the tail of a log archive.
+    /// The range is clamped to the size of the data, so a range past the end returns empty bytes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use autonomi::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = Client::init().await?;
+    /// # let data_map = todo!();
+    /// // read the 1KB following the first MB
+    /// let bytes = client.data_read_range(&data_map, 1_048_576..1_049_600).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn data_read_range(
+        &self,
+        data_map: &DataMapChunk,
+        range: Range<u64>,
+    ) -> Result<Bytes, GetError> {
+        self.in_operation(OperationKind::Download, |client| async move {
+            client.fetch_range(data_map, range).await
+        })
+        .await
+    }
+
+    async fn fetch_range(
+        &self,
+        data_map: &DataMapChunk,
+        range: Range<u64>,
+    ) -> Result<Bytes, GetError> {
+        info!(
+            "Fetching range {range:?} of private data from datamap {:?}",
+            data_map.0.address()
+        );
+        let datamap = self.restore_data_map_from_chunk(data_map).await?;
+        self.read_range_from_data_map(&datamap, range).await
+    }
+
+    /// Fetch a byte range of public data from the network.
+    ///
+    /// See [`Client::data_read_range`].
+    pub async fn data_read_range_public(
+        &self,
+        addr: &DataAddress,
+        range: Range<u64>,
+    ) -> Result<Bytes, GetError> {
+        self.in_operation(OperationKind::Download, |client| async move {
+            client.fetch_public_range(addr, range).await
+        })
+        .await
+    }
+
+    async fn fetch_public_range(
+        &self,
+        addr: &DataAddress,
+        range: Range<u64>,
+    ) -> Result<Bytes, GetError> {
+        info!("Fetching range {range:?} of public data from Data Address: {addr:?}");
+        let datamap_chunk =
+            DataMapChunk(self.chunk_get(&ChunkAddress::new(*addr.xorname())).await?);
+        self.data_read_range(&datamap_chunk, range).await
+    }
+
+    /// Fetch the chunks covering the range in parallel, then decrypt the range out of them.
+    pub(crate) async fn read_range_from_data_map(
+        &self,
+        datamap: &DataMap,
+        range: Range<u64>,
+    ) -> Result<Bytes, GetError> {
+        let infos = datamap.infos();
+        let data_size = data_size(&infos);
+        let start = range.start.min(data_size);
+        let end = range.end.min(data_size);
+        if start >= end {
+            return Ok(Bytes::new());
+        }
+
+        let covering = chunks_covering_range(&infos, start..end);
+        debug!(
+            "Range {start}..{end} is covered by {} of {} chunks",
+            covering.len(),
+            infos.len()
+        );
+
+        let chunk_addresses: Vec<(usize, ChunkAddress)> = covering
+            .iter()
+            .map(|(i, name)| (*i, ChunkAddress::new(*name)))
+            .collect();
+        let fetched = self
+            .fetch_chunks_parallel(&chunk_addresses, infos.len())
+            .await
+            .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))?;
+        self.send_event(|op| ClientEvent::DecryptionProgress {
+            op,
+            chunks: fetched.len(),
+            total_chunks: covering.len(),
+        });
+
+        let names: HashMap<usize, XorName> = covering.into_iter().collect();
+        let fetched: HashMap<XorName, Bytes> = fetched
+            .into_iter()
+            .filter_map(|(i, bytes)| names.get(&i).map(|name| (*name, bytes)))
+            .collect();
+
+        decrypt_range(datamap, fetched, start..end)
+    }
+}
+
+/// Total size of the original data described by the datamap chunk infos.
+fn data_size(infos: &[ChunkInfo]) -> u64 {
+    infos.iter().map(|info| info.src_size as u64).sum()
+}
+
+/// Returns the `(index, dst_hash)` of the chunks holding bytes of the given range of the original data.
+pub(crate) fn chunks_covering_range(
+    infos: &[ChunkInfo],
+    range: Range<u64>,
+) -> Vec<(usize, XorName)> {
+    let mut sorted: Vec<&ChunkInfo> = infos.iter().collect();
+    sorted.sort_by_key(|info| info.index);
+
+    let mut covering = vec![];
+    let mut offset = 0u64;
+    for info in sorted {
+        let chunk_end = offset + info.src_size as u64;
+        if offset < range.end && chunk_end > range.start {
+            covering.push((info.index, info.dst_hash));
+        }
+        offset = chunk_end;
+    }
+    covering
+}
+
+/// Decrypt a range out of a set of already fetched chunks, keyed by their content address.
+fn decrypt_range(
+    datamap: &DataMap,
+    chunks: HashMap<XorName, Bytes>,
+    range: Range<u64>,
+) -> Result<Bytes, GetError> {
+    let chunk_fetcher =
+        move |chunk_names: &[(usize, XorName)]| -> self_encryption::Result<Vec<(usize, Bytes)>> {
+            chunk_names
+                .iter()
+                .map(|(i, name)| {
+                    chunks
+                        .get(name)
+                        .map(|bytes| (*i, bytes.clone()))
+                        .ok_or_else(|| {
+                            self_encryption::Error::Generic(format!(
+                                "Chunk {name:?} outside of the requested range"
+                            ))
+                        })
+                })
+                .collect()
+        };
+
+    let streaming_decrypt = self_encryption::streaming_decrypt(datamap, chunk_fetcher)
+        .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))?;
+    streaming_decrypt
+        .get_range(range.start as usize, (range.end - range.start) as usize)
+        .map_err(|e| GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::encrypt_locally;
+
+    #[test]
+    fn test_chunks_covering_range() {
+        let data = Bytes::from((0..=255u8).cycle().take(3_000_000).collect::<Vec<u8>>());
+        let (data_map, _) = encrypt_locally(&data);
+        let infos = data_map.infos();
+        assert!(infos.len() > 2);
+        let first_size = infos[0].src_size as u64;
+
+        // a range within the first chunk only needs the first chunk
+        let covering = chunks_covering_range(&infos, 0..10);
+        assert_eq!(covering, vec![(infos[0].index, infos[0].dst_hash)]);
+
+        // a range across the boundary of the first two chunks needs both
+        let covering = chunks_covering_range(&infos, first_size - 1..first_size + 1);
+        assert_eq!(covering.len(), 2);
+
+        // the tail of the data only needs the last chunk
+        let last = infos.last().unwrap();
+        let covering = chunks_covering_range(&infos, data.len() as u64 - 1..data.len() as u64);
+        assert_eq!(covering, vec![(last.index, last.dst_hash)]);
+
+        // the full range needs all chunks
+        let covering = chunks_covering_range(&infos, 0..data.len() as u64);
+        assert_eq!(covering.len(), infos.len());
+    }
+
+    #[test]
+    fn test_decrypt_range_with_covering_chunks_only() {
+        let data = Bytes::from((0..=255u8).cycle().take(3_000_000).collect::<Vec<u8>>());
+        let (data_map, all_chunks) = encrypt_locally(&data);
+        let infos = data_map.infos();
+
+        let range = 1_500_000..1_500_100u64;
+        let covering = chunks_covering_range(&infos, range.clone());
+        let chunks: HashMap<XorName, Bytes> = covering
+            .iter()
+            .map(|(_, name)| (*name, all_chunks[name].clone()))
+            .collect();
+
+        let bytes = decrypt_range(&data_map, chunks, range.clone()).unwrap();
+        assert_eq!(
+            bytes.as_ref(),
+            &data[range.start as usize..range.end as usize]
+        );
+    }
+}
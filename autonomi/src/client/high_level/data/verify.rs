@@ -0,0 +1,605 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::Client;
+use crate::client::config::CHUNK_DOWNLOAD_BATCH_SIZE;
+use crate::client::data_types::chunk::{Chunk, ChunkAddress, DataMapChunk};
+use crate::client::payment::PaymentOption;
+use crate::client::quote::CostError;
+use crate::client::{GetError, PutError};
+use crate::networking::common::Addresses;
+use crate::networking::{NetworkError, PeerInfo, Quorum};
+use crate::utils::process_tasks_with_max_concurrency;
+use ant_evm::{AttoTokens, ClientProofOfPayment, EvmWallet, PaymentQuote};
+use ant_protocol::storage::{
+    DataTypes, RecordHeader, RecordKind, try_deserialize_record, try_serialize_record,
+};
+use ant_protocol::{CLOSE_GROUP_SIZE, NetworkAddress};
+use libp2p::PeerId;
+use libp2p::kad::Record;
+use self_encryption::DataMap;
+use std::collections::HashMap;
+
+use super::DataAddress;
+
+/// How well a chunk is replicated across its close group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicationStatus {
+    /// Every node of the close group holds the chunk.
+    Healthy,
+    /// Some nodes of the close group hold the chunk, others don't.
+    UnderReplicated,
+    /// No node handed the chunk over, it cannot be downloaded nor repaired.
+    Lost,
+}
+
+/// Outcome of re-uploading an under-replicated chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepairOutcome {
+    /// The chunk was stored again, at the given cost.
+    Repaired { cost: AttoTokens },
+    /// Nothing was stored, the nodes missing the chunk holding it by now.
+    AlreadyStored,
+    /// The chunk could not be stored again.
+    Failed(String),
+}
+
+/// The replication of a chunk of the data, as seen while verifying it.
+#[derive(Debug, Clone)]
+pub struct ChunkReplication {
+    pub address: ChunkAddress,
+    /// The nodes that handed over a valid copy of the chunk.
+    pub holders: Vec<PeerId>,
+    /// The nodes of the close group that did not hand over a valid copy of the chunk.
+    pub missing: Vec<PeerId>,
+    /// Set if the chunk was under-replicated and a repair was requested.
+    pub repair: Option<RepairOutcome>,
+}
+
+impl ChunkReplication {
+    pub fn status(&self) -> ReplicationStatus {
+        if self.holders.is_empty() {
+            ReplicationStatus::Lost
+        } else if self.holders.len() < CLOSE_GROUP_SIZE {
+            ReplicationStatus::UnderReplicated
+        } else {
+            ReplicationStatus::Healthy
+        }
+    }
+}
+
+/// Result of [`Client::data_verify`]: the replication of every chunk of the data.
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    pub chunks: Vec<ChunkReplication>,
+}
+
+impl VerifyReport {
+    /// Whether every chunk is held by its whole close group.
+    pub fn is_healthy(&self) -> bool {
+        self.chunks
+            .iter()
+            .all(|chunk| chunk.status() == ReplicationStatus::Healthy)
+    }
+
+    /// The chunks held by only part of their close group.
+    pub fn under_replicated(&self) -> impl Iterator<Item = &ChunkReplication> {
+        self.chunks
+            .iter()
+            .filter(|chunk| chunk.status() == ReplicationStatus::UnderReplicated)
+    }
+
+    /// The chunks no node handed over.
+    pub fn lost(&self) -> impl Iterator<Item = &ChunkReplication> {
+        self.chunks
+            .iter()
+            .filter(|chunk| chunk.status() == ReplicationStatus::Lost)
+    }
+
+    /// The chunks stored again during the verification.
+    pub fn repaired(&self) -> impl Iterator<Item = &ChunkReplication> {
+        self.chunks
+            .iter()
+            .filter(|chunk| matches!(chunk.repair, Some(RepairOutcome::Repaired { .. })))
+    }
+}
+
+impl Client {
+    /// Verify the replication of every chunk of (private) data, asking all the nodes of each
+    /// chunk's close group for their copy.
+    ///
+    /// When `repair` is given, the under-replicated chunks are fetched from the nodes still holding
+    /// them, checked against their address, and sent to the nodes of the close group missing them.
+    /// With a wallet, those nodes are paid afresh, a receipt must hold a payment to them. Lost chunks
+    /// cannot be repaired, their content being gone from the network.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use autonomi::Client;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = Client::init().await?;
+    /// # let data_map = todo!();
+    /// let report = client.data_verify(&data_map, None).await?;
+    /// for chunk in report.under_replicated() {
+    ///     println!("{:?} is only held by {} nodes", chunk.address, chunk.holders.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn data_verify(
+        &self,
+        data_map: &DataMapChunk,
+        repair: Option<PaymentOption>,
+    ) -> Result<VerifyReport, GetError> {
+        info!(
+            "Verifying replication of private data from datamap {:?}",
+            data_map.0.address()
+        );
+        let addrs = self.data_chunk_addrs(data_map).await?;
+        self.verify_chunks(addrs, repair).await
+    }
+
+    /// Verify the replication of every chunk of public data, including the chunk of its datamap.
+    ///
+    /// See [`Client::data_verify`].
+    pub async fn data_verify_public(
+        &self,
+        addr: &DataAddress,
+        repair: Option<PaymentOption>,
+    ) -> Result<VerifyReport, GetError> {
+        info!("Verifying replication of public data from Data Address: {addr:?}");
+        let datamap_addr = ChunkAddress::new(*addr.xorname());
+        let data_map = DataMapChunk(self.chunk_get(&datamap_addr).await?);
+        let mut addrs = vec![datamap_addr];
+        addrs.extend(self.data_chunk_addrs(&data_map).await?);
+        self.verify_chunks(addrs, repair).await
+    }
+
+    /// The addresses of the chunks needed to download the data: those of the datamap chunks, then
+    /// those of the content.
+    async fn data_chunk_addrs(
+        &self,
+        data_map: &DataMapChunk,
+    ) -> Result<Vec<ChunkAddress>, GetError> {
+        let mut addrs = vec![];
+        if let Ok(root) = rmp_serde::from_slice::<DataMap>(data_map.0.value()) {
+            addrs.extend(
+                root.infos()
+                    .iter()
+                    .map(|info| ChunkAddress::new(info.dst_hash)),
+            );
+        }
+        let data_map = self.restore_data_map_from_chunk(data_map).await?;
+        for info in data_map.infos() {
+            let addr = ChunkAddress::new(info.dst_hash);
+            if !addrs.contains(&addr) {
+                addrs.push(addr);
+            }
+        }
+        Ok(addrs)
+    }
+
+    async fn verify_chunks(
+        &self,
+        addrs: Vec<ChunkAddress>,
+        repair: Option<PaymentOption>,
+    ) -> Result<VerifyReport, GetError> {
+        let tasks = addrs.into_iter().map(|addr| {
+            let repair = repair.clone();
+            async move {
+                let (mut replication, missing) = self.chunk_replication(addr).await?;
+                if let Some(payment_option) = repair
+                    && replication.status() == ReplicationStatus::UnderReplicated
+                {
+                    replication.repair =
+                        Some(self.repair_chunk(&addr, missing, payment_option).await);
+                }
+                Ok::<_, GetError>(replication)
+            }
+        });
+        let chunks = process_tasks_with_max_concurrency(tasks, *CHUNK_DOWNLOAD_BATCH_SIZE)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let report = VerifyReport { chunks };
+        info!(
+            "Verified {} chunks: {} under-replicated, {} lost, {} repaired",
+            report.chunks.len(),
+            report.under_replicated().count(),
+            report.lost().count(),
+            report.repaired().count()
+        );
+        Ok(report)
+    }
+
+    /// Ask the whole close group of the chunk for their copy.
+    /// Returns the replication of the chunk along with the nodes missing it.
+    async fn chunk_replication(
+        &self,
+        addr: ChunkAddress,
+    ) -> Result<(ChunkReplication, Vec<PeerInfo>), GetError> {
+        let key = NetworkAddress::from(addr);
+        let close_group = self
+            .network
+            .get_closest_peers(key.clone(), Some(CLOSE_GROUP_SIZE))
+            .await?;
+        let result = self.network.get_record_and_holders(key, Quorum::All).await;
+        let holders = valid_holders(&addr, result)?;
+        let missing = missing_peers(close_group, &holders);
+        let replication = ChunkReplication {
+            address: addr,
+            holders,
+            missing: missing.iter().map(|peer| peer.peer_id).collect(),
+            repair: None,
+        };
+        debug!(
+            "Chunk {addr:?} is {:?}, held by {} nodes",
+            replication.status(),
+            replication.holders.len()
+        );
+        Ok((replication, missing))
+    }
+
+    /// Send the chunk to the nodes missing it, from a copy fetched from the nodes still holding it.
+    async fn repair_chunk(
+        &self,
+        addr: &ChunkAddress,
+        missing: Vec<PeerInfo>,
+        payment_option: PaymentOption,
+    ) -> RepairOutcome {
+        let chunk = match self.chunk_get(addr).await {
+            Ok(chunk) if chunk.address() == addr => chunk,
+            Ok(_) => {
+                return RepairOutcome::Failed(
+                    "fetched copy does not match its address".to_string(),
+                );
+            }
+            Err(err) => return RepairOutcome::Failed(format!("failed to fetch a copy: {err}")),
+        };
+
+        let payment = match payment_option {
+            PaymentOption::Wallet(wallet) => self.pay_missing_peers(&chunk, missing, &wallet).await,
+            PaymentOption::Receipt(receipt) => match receipt.get(chunk.name()) {
+                Some((proof, cost)) => Ok(Some((missing, proof.clone(), *cost))),
+                None => {
+                    return RepairOutcome::Failed(
+                        "the receipt holds no payment for the chunk".to_string(),
+                    );
+                }
+            },
+        };
+        let result = match payment {
+            Ok(Some((targets, proof, cost))) => self
+                .chunk_put_to_peers(&chunk, &proof, targets)
+                .await
+                .map(|()| Some(cost)),
+            Ok(None) => Ok(None),
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(Some(cost)) => {
+                info!("Repaired chunk {addr:?} for {cost}");
+                RepairOutcome::Repaired { cost }
+            }
+            Ok(None) => {
+                info!("Chunk {addr:?} was not stored again, the nodes missing it hold it by now");
+                RepairOutcome::AlreadyStored
+            }
+            Err(err) => {
+                warn!("Failed to repair chunk {addr:?}: {err}");
+                RepairOutcome::Failed(err.to_string())
+            }
+        }
+    }
+
+    /// Pay the nodes missing the chunk for storing it.
+    /// Returns the nodes paid along with the payment, or `None` if they all hold the chunk by now.
+    async fn pay_missing_peers(
+        &self,
+        chunk: &Chunk,
+        missing: Vec<PeerInfo>,
+        wallet: &EvmWallet,
+    ) -> Result<Option<(Vec<PeerInfo>, ClientProofOfPayment, AttoTokens)>, PutError> {
+        let quoted = self.quotes_from_peers(chunk, missing.clone()).await;
+        if quoted.is_empty() {
+            return Ok(None);
+        }
+        let (targets, mut quotes): (Vec<PeerInfo>, Vec<RawQuote>) = quoted.into_iter().unzip();
+
+        // a payment needs the quotes of a whole close group, the closest other nodes make up for it
+        if quotes.len() < CLOSE_GROUP_SIZE {
+            let key = chunk.network_address();
+            let others = self
+                .network
+                .get_closest_peers(key.clone(), Some(CLOSE_GROUP_SIZE * 2))
+                .await
+                .map_err(CostError::from)?
+                .into_iter()
+                .filter(|peer| !missing.iter().any(|m| m.peer_id == peer.peer_id))
+                .collect();
+            let others = self.quotes_from_peers(chunk, others).await;
+            quotes = repair_quotes(&key, quotes, others.into_iter().map(|(_, q)| q).collect());
+        }
+
+        let xor_name = *chunk.name();
+        let store_quote = self.store_quote_from_raw(xor_name, quotes).await?;
+        let sizes = HashMap::from([(xor_name, chunk.size())]);
+        let (receipt, _) = self
+            .pay_store_quotes(DataTypes::Chunk, store_quote, &sizes, wallet)
+            .await?;
+        let (proof, cost) = receipt.get(&xor_name).ok_or(PutError::PayeesMissing)?;
+        Ok(Some((targets, proof.clone(), *cost)))
+    }
+
+    /// Ask each peer for a quote to store the chunk, leaving out the peers holding it already or
+    /// not answering.
+    async fn quotes_from_peers(
+        &self,
+        chunk: &Chunk,
+        peers: Vec<PeerInfo>,
+    ) -> Vec<(PeerInfo, RawQuote)> {
+        let tasks = peers.into_iter().map(|peer| async move {
+            let quote = self
+                .get_raw_quote_from_peer(
+                    *chunk.name(),
+                    peer.clone(),
+                    DataTypes::Chunk,
+                    chunk.size(),
+                )
+                .await;
+            match quote {
+                Ok(Some(quote)) => Some((peer, quote)),
+                Ok(None) => None,
+                Err(err) => {
+                    warn!(
+                        "Peer {:?} did not quote for {:?}: {err}",
+                        peer.peer_id,
+                        chunk.address()
+                    );
+                    None
+                }
+            }
+        });
+        process_tasks_with_max_concurrency(tasks, CLOSE_GROUP_SIZE * 2)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Store the chunk on the given nodes with the payment.
+    async fn chunk_put_to_peers(
+        &self,
+        chunk: &Chunk,
+        proof: &ClientProofOfPayment,
+        targets: Vec<PeerInfo>,
+    ) -> Result<(), PutError> {
+        let address = chunk.network_address();
+        let record = Record {
+            key: address.to_record_key(),
+            value: try_serialize_record(
+                &(proof.to_proof_of_payment(), chunk),
+                RecordKind::DataWithPayment(DataTypes::Chunk),
+            )
+            .map_err(|_| {
+                PutError::Serialization("Failed to serialize chunk with payment".to_string())
+            })?
+            .to_vec(),
+            publisher: None,
+            expires: None,
+        };
+        self.network
+            .put_record_with_retries(record, targets, &self.config.chunks)
+            .await
+            .map_err(|err| PutError::Network {
+                address: Box::new(address),
+                network_error: err,
+                payment: None,
+            })
+    }
+}
+
+/// A quote of a node, not priced yet.
+type RawQuote = (PeerId, Addresses, PaymentQuote);
+
+/// The nodes of the close group not holding a valid copy of the chunk.
+fn missing_peers(close_group: Vec<PeerInfo>, holders: &[PeerId]) -> Vec<PeerInfo> {
+    close_group
+        .into_iter()
+        .filter(|peer| !holders.contains(&peer.peer_id))
+        .collect()
+}
+
+/// The quotes to pay for storing a chunk on the nodes missing it: all of theirs, filled up with the
+/// quotes of the closest other nodes to make a whole close group.
+fn repair_quotes(
+    addr: &NetworkAddress,
+    missing: Vec<RawQuote>,
+    mut others: Vec<RawQuote>,
+) -> Vec<RawQuote> {
+    others.sort_by_key(|(peer_id, _, _)| NetworkAddress::from(*peer_id).distance(addr));
+    others.truncate(CLOSE_GROUP_SIZE.saturating_sub(missing.len()));
+    let mut quotes = missing;
+    quotes.extend(others);
+    quotes
+}
+
+/// The nodes that handed over a valid copy of the chunk, from the outcome of getting it with
+/// [`Quorum::All`].
+fn valid_holders(
+    addr: &ChunkAddress,
+    result: Result<(Option<Record>, Vec<PeerId>), NetworkError>,
+) -> Result<Vec<PeerId>, NetworkError> {
+    match result {
+        Ok((_, holders)) => Ok(holders),
+        Err(NetworkError::GetRecordQuorumFailed { holders, .. }) => Ok(holders),
+        Err(NetworkError::GetRecordTimeout(holders)) => Ok(holders),
+        // copies not matching the address are corrupted, their holders don't count
+        Err(NetworkError::SplitRecord(records)) => Ok(records
+            .into_iter()
+            .filter(|(_, record)| is_valid_copy(addr, record))
+            .map(|(peer_id, _)| peer_id)
+            .collect()),
+        Err(err) => Err(err),
+    }
+}
+
+fn is_valid_copy(addr: &ChunkAddress, record: &Record) -> bool {
+    RecordHeader::is_record_of_type_chunk(record).unwrap_or(false)
+        && try_deserialize_record::<Chunk>(record).is_ok_and(|chunk| chunk.address() == addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    fn chunk_record(chunk: &Chunk) -> Record {
+        Record {
+            key: chunk.network_address().to_record_key(),
+            value: try_serialize_record(chunk, RecordKind::DataOnly(DataTypes::Chunk))
+                .expect("chunk to serialize")
+                .to_vec(),
+            publisher: None,
+            expires: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_holders() {
+        let chunk = Chunk::new(Bytes::from_static(b"verified"));
+        let addr = *chunk.address();
+        let honest = PeerId::random();
+        let corrupted = PeerId::random();
+
+        // holders that answered before the quorum failed still count
+        let holders = valid_holders(
+            &addr,
+            Err(NetworkError::GetRecordQuorumFailed {
+                got_holders: 1,
+                expected_holders: CLOSE_GROUP_SIZE,
+                holders: vec![honest],
+            }),
+        )
+        .expect("partial holders");
+        assert_eq!(holders, vec![honest]);
+
+        // a copy not matching its address is not a valid one
+        let records = HashMap::from_iter([
+            (honest, chunk_record(&chunk)),
+            (
+                corrupted,
+                chunk_record(&Chunk::new(Bytes::from_static(b"tampered"))),
+            ),
+        ]);
+        let holders =
+            valid_holders(&addr, Err(NetworkError::SplitRecord(records))).expect("split holders");
+        assert_eq!(holders, vec![honest]);
+
+        assert!(valid_holders(&addr, Err(NetworkError::GetClosestPeersTimeout)).is_err());
+    }
+
+    #[test]
+    fn test_replication_status() {
+        let mut replication = ChunkReplication {
+            address: ChunkAddress::new(xor_name::XorName::random(&mut rand::thread_rng())),
+            holders: vec![],
+            missing: vec![],
+            repair: None,
+        };
+        assert_eq!(replication.status(), ReplicationStatus::Lost);
+
+        replication.holders = vec![PeerId::random(); CLOSE_GROUP_SIZE - 1];
+        assert_eq!(replication.status(), ReplicationStatus::UnderReplicated);
+
+        replication.holders.push(PeerId::random());
+        assert_eq!(replication.status(), ReplicationStatus::Healthy);
+
+        let report = VerifyReport {
+            chunks: vec![replication.clone()],
+        };
+        assert!(report.is_healthy());
+
+        // a chunk the missing nodes hold by now is not repaired
+        replication.holders.pop();
+        replication.repair = Some(RepairOutcome::AlreadyStored);
+        let report = VerifyReport {
+            chunks: vec![replication],
+        };
+        assert_eq!(report.repaired().count(), 0);
+        assert_eq!(report.under_replicated().count(), 1);
+    }
+
+    #[test]
+    fn test_missing_peers_are_the_repair_targets() {
+        let close_group: Vec<PeerInfo> = (0..CLOSE_GROUP_SIZE)
+            .map(|_| PeerInfo {
+                peer_id: PeerId::random(),
+                addrs: vec![],
+            })
+            .collect();
+        let holders = vec![close_group[0].peer_id, close_group[2].peer_id];
+
+        let targets: Vec<PeerId> = missing_peers(close_group.clone(), &holders)
+            .into_iter()
+            .map(|peer| peer.peer_id)
+            .collect();
+        let expected: Vec<PeerId> = close_group
+            .iter()
+            .map(|peer| peer.peer_id)
+            .filter(|peer_id| !holders.contains(peer_id))
+            .collect();
+        assert_eq!(targets, expected);
+    }
+
+    #[test]
+    fn test_repair_quotes_keep_the_missing_peers() {
+        let name = xor_name::XorName::random(&mut rand::thread_rng());
+        let addr = NetworkAddress::from(ChunkAddress::new(name));
+        let raw_quote = |peer_id: PeerId| -> RawQuote {
+            (
+                peer_id,
+                Addresses(vec![]),
+                crate::test_utils::quote_issued_at(name, std::time::SystemTime::now()),
+            )
+        };
+        let missing: Vec<RawQuote> = (0..2).map(|_| raw_quote(PeerId::random())).collect();
+        let others: Vec<RawQuote> = (0..CLOSE_GROUP_SIZE * 2)
+            .map(|_| raw_quote(PeerId::random()))
+            .collect();
+
+        let quotes = repair_quotes(&addr, missing.clone(), others.clone());
+        assert_eq!(quotes.len(), CLOSE_GROUP_SIZE);
+        for (peer_id, _, _) in &missing {
+            assert!(quotes.iter().any(|(quoted, _, _)| quoted == peer_id));
+        }
+
+        // the quotes of the closest other nodes make up the close group
+        let mut closest: Vec<PeerId> = others.iter().map(|(peer_id, _, _)| *peer_id).collect();
+        closest.sort_by_key(|peer_id| NetworkAddress::from(*peer_id).distance(&addr));
+        closest.truncate(CLOSE_GROUP_SIZE - missing.len());
+        let fillers: Vec<PeerId> = quotes[missing.len()..]
+            .iter()
+            .map(|(peer_id, _, _)| *peer_id)
+            .collect();
+        assert_eq!(fillers, closest);
+
+        // a whole close group missing the chunk needs no other quote
+        let missing: Vec<RawQuote> = (0..CLOSE_GROUP_SIZE)
+            .map(|_| raw_quote(PeerId::random()))
+            .collect();
+        assert_eq!(
+            repair_quotes(&addr, missing, others).len(),
+            CLOSE_GROUP_SIZE
+        );
+    }
+}
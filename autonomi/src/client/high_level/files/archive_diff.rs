@@ -0,0 +1,356 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::Metadata;
+use super::posix::{PosixAttributes, PosixEntries, SpecialEntry};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::hash::Hash;
+use std::path::PathBuf;
+
+/// An archive entry: the address of the content of a file and its metadata.
+/// The address is a [`crate::data::DataAddress`] for public archives and a [`crate::chunk::DataMapChunk`] for private ones.
+pub type ArchiveEntry<A> = (A, Metadata);
+
+/// Differences between an old and a new version of an archive.
+///
+/// Files are compared by the address of their content: metadata only changes are not reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveDiff<A> {
+    /// Files only in the new archive
+    pub added: BTreeMap<PathBuf, ArchiveEntry<A>>,
+    /// Files only in the old archive
+    pub removed: BTreeMap<PathBuf, ArchiveEntry<A>>,
+    /// Files moved to another path with the same content: old path to new path
+    pub renamed: BTreeMap<PathBuf, PathBuf>,
+    /// Files at the same path with a different content, with their entry in the new archive
+    pub modified: BTreeMap<PathBuf, ArchiveEntry<A>>,
+}
+
+impl<A> ArchiveDiff<A> {
+    /// Returns true if both archives hold the same content at the same paths.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.modified.is_empty()
+    }
+}
+
+/// A path changed differently on both sides of a three-way merge.
+///
+/// Each side is the value at that path, or `None` if there is nothing at that path on that side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathConflict<V> {
+    /// The conflicting path
+    pub path: PathBuf,
+    /// Value in the common base archive
+    pub base: Option<V>,
+    /// Value in our archive
+    pub ours: Option<V>,
+    /// Value in their archive
+    pub theirs: Option<V>,
+}
+
+/// A file changed differently on both sides of a three-way merge.
+pub type MergeConflict<A> = PathConflict<ArchiveEntry<A>>;
+
+/// A POSIX entry changed differently on both sides of a three-way merge, see [`PosixEntries`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PosixConflict {
+    /// Different POSIX attributes for the same path
+    Attributes(PathConflict<PosixAttributes>),
+    /// A different directory or symlink at the same path
+    Special(MergeConflict<SpecialEntry>),
+}
+
+/// Result of a three-way merge of archives.
+///
+/// The merged archive holds all the changes that could be merged.
+/// Conflicting paths are left out of it, for the caller to resolve and add back.
+/// This applies to the files as well as to the POSIX entries of the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveMerge<T, A> {
+    /// The merged archive, without the conflicting paths
+    pub merged: T,
+    /// The files changed differently on both sides
+    pub conflicts: Vec<MergeConflict<A>>,
+    /// The POSIX entries changed differently on both sides
+    pub posix_conflicts: Vec<PosixConflict>,
+}
+
+impl<T, A> ArchiveMerge<T, A> {
+    /// Returns true if the merge had no conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty() && self.posix_conflicts.is_empty()
+    }
+}
+
+/// Compute the differences from the `old` to the `new` archive entries.
+pub(crate) fn diff_entries<A: Clone + Eq + Hash>(
+    old: &BTreeMap<PathBuf, ArchiveEntry<A>>,
+    new: &BTreeMap<PathBuf, ArchiveEntry<A>>,
+) -> ArchiveDiff<A> {
+    let mut added = BTreeMap::new();
+    let mut removed = BTreeMap::new();
+    let mut modified = BTreeMap::new();
+
+    for (path, entry) in new {
+        match old.get(path) {
+            None => {
+                added.insert(path.clone(), entry.clone());
+            }
+            Some((old_addr, _)) if *old_addr != entry.0 => {
+                modified.insert(path.clone(), entry.clone());
+            }
+            Some(_) => {}
+        }
+    }
+    for (path, entry) in old {
+        if !new.contains_key(path) {
+            removed.insert(path.clone(), entry.clone());
+        }
+    }
+
+    // a removed file with the same content as an added file was renamed
+    let mut removed_by_addr: HashMap<&A, VecDeque<&PathBuf>> = HashMap::new();
+    for (path, (addr, _)) in &removed {
+        removed_by_addr.entry(addr).or_default().push_back(path);
+    }
+    let mut renamed = BTreeMap::new();
+    for (new_path, (addr, _)) in &added {
+        if let Some(old_path) = removed_by_addr
+            .get_mut(addr)
+            .and_then(|paths| paths.pop_front())
+        {
+            renamed.insert(old_path.clone(), new_path.clone());
+        }
+    }
+    for (old_path, new_path) in &renamed {
+        removed.remove(old_path);
+        added.remove(new_path);
+    }
+
+    ArchiveDiff {
+        added,
+        removed,
+        renamed,
+        modified,
+    }
+}
+
+/// Three-way merge of archive entries changed on both sides since a common `base`.
+///
+/// Paths are merged independently, comparing the content address at that path on each side:
+/// - a change made on one side only is taken
+/// - the same change made on both sides is taken
+/// - different changes on both sides (including a modification on one side and a removal on the other) are a conflict
+///
+/// Returns the merged entries without the conflicting paths, and the conflicts.
+pub(crate) fn merge_entries<A: Clone + PartialEq>(
+    base: &BTreeMap<PathBuf, ArchiveEntry<A>>,
+    ours: &BTreeMap<PathBuf, ArchiveEntry<A>>,
+    theirs: &BTreeMap<PathBuf, ArchiveEntry<A>>,
+) -> (BTreeMap<PathBuf, ArchiveEntry<A>>, Vec<MergeConflict<A>>) {
+    merge_paths(base, ours, theirs, |(addr, _)| addr)
+}
+
+/// Three-way merge of values by path, comparing the `key` of the values at a path on each side.
+///
+/// See [`merge_entries`] for how each path is merged.
+pub(crate) fn merge_paths<V: Clone, K: PartialEq>(
+    base: &BTreeMap<PathBuf, V>,
+    ours: &BTreeMap<PathBuf, V>,
+    theirs: &BTreeMap<PathBuf, V>,
+    key: impl Fn(&V) -> &K,
+) -> (BTreeMap<PathBuf, V>, Vec<PathConflict<V>>) {
+    let paths: BTreeSet<&PathBuf> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .collect();
+
+    let mut merged = BTreeMap::new();
+    let mut conflicts = vec![];
+    for path in paths {
+        let b = base.get(path);
+        let o = ours.get(path);
+        let t = theirs.get(path);
+        let (kb, ko, kt) = (b.map(&key), o.map(&key), t.map(&key));
+
+        let resolved = if ko == kt || kt == kb {
+            // same change on both sides, or only changed on our side
+            o
+        } else if ko == kb {
+            // only changed on their side
+            t
+        } else {
+            conflicts.push(PathConflict {
+                path: path.clone(),
+                base: b.cloned(),
+                ours: o.cloned(),
+                theirs: t.cloned(),
+            });
+            continue;
+        };
+
+        if let Some(value) = resolved {
+            merged.insert(path.clone(), value.clone());
+        }
+    }
+
+    (merged, conflicts)
+}
+
+/// Three-way merge of the POSIX entries of archives, see [`merge_entries`].
+///
+/// Attributes are compared as a whole, directories and symlinks by what they are and point to,
+/// ignoring their metadata.
+pub(crate) fn merge_posix(
+    base: &PosixEntries,
+    ours: &PosixEntries,
+    theirs: &PosixEntries,
+) -> (PosixEntries, Vec<PosixConflict>) {
+    let (attributes, attribute_conflicts) = merge_paths(
+        &base.attributes,
+        &ours.attributes,
+        &theirs.attributes,
+        |attributes| attributes,
+    );
+    let (special, special_conflicts) = merge_paths(
+        &base.special,
+        &ours.special,
+        &theirs.special,
+        |(entry, _)| entry,
+    );
+
+    let conflicts = attribute_conflicts
+        .into_iter()
+        .map(PosixConflict::Attributes)
+        .chain(special_conflicts.into_iter().map(PosixConflict::Special))
+        .collect();
+    (
+        PosixEntries {
+            attributes,
+            special,
+        },
+        conflicts,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn entries(files: &[(&str, u8)]) -> BTreeMap<PathBuf, ArchiveEntry<u8>> {
+        files
+            .iter()
+            .map(|(path, addr)| (PathBuf::from(path), (*addr, Metadata::empty())))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_entries() {
+        let old = entries(&[
+            ("same", 1),
+            ("modified", 2),
+            ("removed", 3),
+            ("old_name", 4),
+        ]);
+        let new = entries(&[("same", 1), ("modified", 5), ("added", 6), ("new_name", 4)]);
+
+        let diff = diff_entries(&old, &new);
+        assert_eq!(diff.added, entries(&[("added", 6)]));
+        assert_eq!(diff.removed, entries(&[("removed", 3)]));
+        assert_eq!(diff.modified, entries(&[("modified", 5)]));
+        assert_eq!(
+            diff.renamed,
+            BTreeMap::from([(PathBuf::from("old_name"), PathBuf::from("new_name"))])
+        );
+
+        assert!(diff_entries(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_merge_entries() {
+        let base = entries(&[("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5)]);
+        // ours modifies a and c, removes d, adds f
+        let ours = entries(&[("a", 10), ("b", 2), ("c", 30), ("e", 5), ("f", 6)]);
+        // theirs modifies b and c differently, modifies e, adds g
+        let theirs = entries(&[
+            ("a", 1),
+            ("b", 20),
+            ("c", 31),
+            ("d", 4),
+            ("e", 50),
+            ("g", 7),
+        ]);
+
+        let (merged, conflicts) = merge_entries(&base, &ours, &theirs);
+        assert_eq!(
+            merged,
+            entries(&[("a", 10), ("b", 20), ("e", 50), ("f", 6), ("g", 7)])
+        );
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].path, PathBuf::from("c"));
+        assert_eq!(conflicts[0].ours.as_ref().map(|e| e.0), Some(30));
+        assert_eq!(conflicts[0].theirs.as_ref().map(|e| e.0), Some(31));
+    }
+
+    #[test]
+    fn test_merge_entries_modified_and_removed() {
+        let base = entries(&[("a", 1)]);
+        let ours = entries(&[]);
+        let theirs = entries(&[("a", 2)]);
+
+        let (merged, conflicts) = merge_entries(&base, &ours, &theirs);
+        assert!(merged.is_empty());
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].ours, None);
+        assert_eq!(conflicts[0].base.as_ref().map(|e| e.0), Some(1));
+
+        // the same removal on both sides is no conflict
+        let (merged, conflicts) = merge_entries(&base, &ours, &ours);
+        assert!(merged.is_empty());
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_merge_posix() {
+        let mode = |mode| PosixAttributes { mode, xattrs: None };
+        let mut base = PosixEntries::default();
+        base.set_attributes(PathBuf::from("run.sh"), mode(0o755));
+        base.add_symlink(PathBuf::from("link"), PathBuf::from("a"), Metadata::empty());
+
+        let mut ours = base.clone();
+        ours.set_attributes(PathBuf::from("run.sh"), mode(0o700));
+        ours.add_symlink(PathBuf::from("link"), PathBuf::from("b"), Metadata::empty());
+        let mut theirs = base.clone();
+        theirs.set_attributes(PathBuf::from("run.sh"), mode(0o750));
+        theirs.add_directory(PathBuf::from("empty"), Metadata::empty());
+
+        let (merged, conflicts) = merge_posix(&base, &ours, &theirs);
+        // the symlink only changed on our side, the directory only added on theirs
+        assert_eq!(
+            merged.special.get(Path::new("link")).map(|e| &e.0),
+            Some(&SpecialEntry::Symlink(PathBuf::from("b")))
+        );
+        assert!(merged.special.contains_key(Path::new("empty")));
+        // the mode changed differently on both sides
+        assert!(!merged.attributes.contains_key(Path::new("run.sh")));
+        assert_eq!(
+            conflicts,
+            vec![PosixConflict::Attributes(PathConflict {
+                path: PathBuf::from("run.sh"),
+                base: Some(mode(0o755)),
+                ours: Some(mode(0o700)),
+                theirs: Some(mode(0o750)),
+            })]
+        );
+    }
+}
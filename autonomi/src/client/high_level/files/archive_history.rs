@@ -0,0 +1,371 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::archive_private::PrivateArchiveDataMap;
+use super::archive_public::ArchiveAddress;
+use crate::client::data_types::chunk::{Chunk, ChunkAddress, DataMapChunk};
+use crate::client::high_level::register::{RegisterAddress, RegisterError, RegisterValue};
+use crate::client::key_derivation::{DerivationIndex, MainPubkey, MainSecretKey};
+use crate::client::payment::PaymentOption;
+use crate::client::{GetError, PutError};
+use crate::{AttoTokens, Client, PublicKey, SecretKey};
+use bls::Ciphertext;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use xor_name::XorName;
+
+/// Errors that can occur when dealing with the version history of an archive.
+#[derive(Error, Debug)]
+pub enum ArchiveHistoryError {
+    #[error("Underlying RegisterError: {0}")]
+    Register(#[from] RegisterError),
+    #[error("Failed to upload the private archive datamap: {0}")]
+    Put(#[from] PutError),
+    #[error("Failed to fetch the private archive datamap: {0}")]
+    Get(#[from] GetError),
+    #[error("Archive version {0} not found in the history")]
+    VersionNotFound(usize),
+    #[error("Failed to decrypt the private archive datamap, is this the right key?")]
+    Decryption,
+    #[error(
+        "The archive version was stored at {chunk:?} but adding it to the history failed: {source}"
+    )]
+    VersionNotPublished {
+        /// The chunk holding the encrypted archive datamap, already paid for
+        chunk: ChunkAddress,
+        source: RegisterError,
+    },
+}
+
+/// Archives are immutable, [`ArchiveHistory`] gives a stable address to successive versions of an archive.
+///
+/// Each published version is a value of a register whose key is derived from the history owner's key,
+/// so the history can be browsed from the first to the latest version. Public and private histories of
+/// the same owner are kept in distinct registers.
+/// Use [`Client::register_key_from_name`] to derive a history key from a name, e.g. the name of a website or a dataset.
+///
+/// Public archive versions are stored as their [`ArchiveAddress`] and can be read by anyone knowing the history address.
+/// Private archive versions are stored as a chunk holding their [`PrivateArchiveDataMap`] encrypted with the
+/// history owner's key, so only the owner can read them.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, Debug)]
+pub struct ArchiveHistory(RegisterAddress);
+
+impl ArchiveHistory {
+    /// The history of the public archives published by `owner`
+    pub fn public(owner: PublicKey) -> Self {
+        Self::derived(owner, HistoryKind::Public)
+    }
+
+    /// The history of the private archives published by `owner`
+    pub fn private(owner: PublicKey) -> Self {
+        Self::derived(owner, HistoryKind::Private)
+    }
+
+    fn derived(owner: PublicKey, kind: HistoryKind) -> Self {
+        let register_pk = MainPubkey::new(owner).derive_key(&kind.derivation_index());
+        Self(RegisterAddress::new(register_pk.into()))
+    }
+
+    /// The register holding the versions
+    pub fn register(&self) -> RegisterAddress {
+        self.0
+    }
+
+    /// Convert the history address to a hex string
+    pub fn to_hex(&self) -> String {
+        self.0.to_hex()
+    }
+
+    /// Convert a hex string to a history address
+    pub fn from_hex(hex: &str) -> Result<Self, bls::Error> {
+        RegisterAddress::from_hex(hex).map(Self)
+    }
+}
+
+impl std::fmt::Display for ArchiveHistory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum HistoryKind {
+    Public,
+    Private,
+}
+
+impl HistoryKind {
+    fn derivation_index(self) -> DerivationIndex {
+        let label: &[u8] = match self {
+            HistoryKind::Public => b"public archive history",
+            HistoryKind::Private => b"private archive history",
+        };
+        DerivationIndex::from_bytes(XorName::from_content(label).0)
+    }
+
+    /// The key of the register holding the versions of the owner's history
+    fn register_key(self, owner: &SecretKey) -> SecretKey {
+        MainSecretKey::new(owner.clone())
+            .derive_key(&self.derivation_index())
+            .into()
+    }
+}
+
+impl Client {
+    /// Publish a new version of a public archive to the history owned by `owner`.
+    /// The history is created with this archive as its first version if it doesn't exist yet.
+    ///
+    /// Returns the cost and the address of the history.
+    pub async fn archive_publish_version_public(
+        &self,
+        owner: &SecretKey,
+        archive: &ArchiveAddress,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, ArchiveHistory), ArchiveHistoryError> {
+        info!(
+            "Publishing public archive {archive:?} to history {:?}",
+            owner.public_key()
+        );
+        let value: RegisterValue = archive.xorname().0;
+        self.archive_history_push(owner, HistoryKind::Public, value, payment_option)
+            .await
+            .map_err(ArchiveHistoryError::from)
+    }
+
+    /// Publish a new version of a private archive to the history owned by `owner`.
+    /// The history is created with this archive as its first version if it doesn't exist yet.
+    ///
+    /// The archive datamap is encrypted with the owner's key and uploaded as a chunk, which requires a payment
+    /// in addition to the register update. If the register update fails, the chunk is reported in
+    /// [`ArchiveHistoryError::VersionNotPublished`].
+    /// Returns the total cost and the address of the history.
+    pub async fn archive_publish_version(
+        &self,
+        owner: &SecretKey,
+        archive: &PrivateArchiveDataMap,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, ArchiveHistory), ArchiveHistoryError> {
+        info!(
+            "Publishing private archive to history {:?}",
+            owner.public_key()
+        );
+        let chunk = encrypt_data_map(&owner.public_key(), archive);
+        let (chunk_cost, chunk_addr) = self.chunk_put(&chunk, payment_option.clone()).await?;
+        let value: RegisterValue = chunk_addr.xorname().0;
+
+        let (register_cost, history) = self
+            .archive_history_push(owner, HistoryKind::Private, value, payment_option)
+            .await
+            .map_err(|source| {
+                error!(
+                    "Private archive version stored at {chunk_addr:?} was not published: {source}"
+                );
+                ArchiveHistoryError::VersionNotPublished {
+                    chunk: chunk_addr,
+                    source,
+                }
+            })?;
+        let total_cost = chunk_cost
+            .checked_add(register_cost)
+            .ok_or(RegisterError::InvalidCost)?;
+        Ok((total_cost, history))
+    }
+
+    /// Get the latest version of a public archive history.
+    pub async fn archive_latest_public(
+        &self,
+        history: &ArchiveHistory,
+    ) -> Result<ArchiveAddress, ArchiveHistoryError> {
+        let value = self.register_get(&history.register()).await?;
+        Ok(ArchiveAddress::new(XorName(value)))
+    }
+
+    /// Get the latest version of the private archive history owned by `owner`.
+    pub async fn archive_latest(
+        &self,
+        owner: &SecretKey,
+    ) -> Result<PrivateArchiveDataMap, ArchiveHistoryError> {
+        let history = ArchiveHistory::private(owner.public_key());
+        let value = self.register_get(&history.register()).await?;
+        self.archive_history_private_version(owner, value).await
+    }
+
+    /// Get all the versions of a public archive history, from the first to the latest.
+    /// The version number of an archive is its index in the returned list.
+    pub async fn archive_versions_public(
+        &self,
+        history: &ArchiveHistory,
+    ) -> Result<Vec<ArchiveAddress>, ArchiveHistoryError> {
+        let values = self.register_history(&history.register()).collect().await?;
+        Ok(values
+            .into_iter()
+            .map(|value| ArchiveAddress::new(XorName(value)))
+            .collect())
+    }
+
+    /// Get all the versions of the private archive history owned by `owner`, from the first to the latest.
+    /// The version number of an archive is its index in the returned list.
+    pub async fn archive_versions(
+        &self,
+        owner: &SecretKey,
+    ) -> Result<Vec<PrivateArchiveDataMap>, ArchiveHistoryError> {
+        let history = ArchiveHistory::private(owner.public_key());
+        let values = self.register_history(&history.register()).collect().await?;
+        let versions = values
+            .into_iter()
+            .map(|value| self.archive_history_private_version(owner, value));
+        futures::future::try_join_all(versions).await
+    }
+
+    /// Get a given version of a public archive history, version 0 being the first one.
+    pub async fn archive_at_public(
+        &self,
+        history: &ArchiveHistory,
+        version: usize,
+    ) -> Result<ArchiveAddress, ArchiveHistoryError> {
+        let value = self.archive_history_value_at(history, version).await?;
+        Ok(ArchiveAddress::new(XorName(value)))
+    }
+
+    /// Get a given version of the private archive history owned by `owner`, version 0 being the first one.
+    pub async fn archive_at(
+        &self,
+        owner: &SecretKey,
+        version: usize,
+    ) -> Result<PrivateArchiveDataMap, ArchiveHistoryError> {
+        let history = ArchiveHistory::private(owner.public_key());
+        let value = self.archive_history_value_at(&history, version).await?;
+        self.archive_history_private_version(owner, value).await
+    }
+
+    /// Append a value to the history register, creating it if it doesn't exist yet.
+    async fn archive_history_push(
+        &self,
+        owner: &SecretKey,
+        kind: HistoryKind,
+        value: RegisterValue,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, ArchiveHistory), RegisterError> {
+        let history = ArchiveHistory::derived(owner.public_key(), kind);
+        let register_key = kind.register_key(owner);
+        match self
+            .register_update(&register_key, value, payment_option.clone())
+            .await
+        {
+            Ok(cost) => Ok((cost, history)),
+            Err(RegisterError::CannotUpdateNewRegister) => {
+                debug!("Archive history {history:?} doesn't exist yet, creating it");
+                let (cost, _register) = self
+                    .register_create(&register_key, value, payment_option)
+                    .await?;
+                Ok((cost, history))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Walk the history register up to the given version.
+    async fn archive_history_value_at(
+        &self,
+        history: &ArchiveHistory,
+        version: usize,
+    ) -> Result<RegisterValue, ArchiveHistoryError> {
+        let mut register_history = self.register_history(&history.register());
+        for _ in 0..version {
+            if register_history.next().await?.is_none() {
+                return Err(ArchiveHistoryError::VersionNotFound(version));
+            }
+        }
+        register_history
+            .next()
+            .await?
+            .ok_or(ArchiveHistoryError::VersionNotFound(version))
+    }
+
+    /// Fetch and decrypt the private archive datamap stored in the chunk a history value points to.
+    async fn archive_history_private_version(
+        &self,
+        owner: &SecretKey,
+        value: RegisterValue,
+    ) -> Result<PrivateArchiveDataMap, ArchiveHistoryError> {
+        let chunk = self.chunk_get(&ChunkAddress::new(XorName(value))).await?;
+        decrypt_data_map(owner, &chunk)
+    }
+}
+
+/// Encrypt a private archive datamap for the history owner, into a chunk.
+///
+/// BLS decryption is not authenticated, so the hash of the datamap is encrypted along with it, to
+/// tell a wrong key from a right one on decryption.
+fn encrypt_data_map(owner: &PublicKey, data_map: &PrivateArchiveDataMap) -> Chunk {
+    let mut plaintext = XorName::from_content(data_map.0.value()).0.to_vec();
+    plaintext.extend_from_slice(data_map.0.value());
+    let ciphertext = owner.encrypt(plaintext);
+    Chunk::new(ciphertext.to_bytes().into())
+}
+
+/// Decrypt a private archive datamap from a chunk encrypted for the history owner.
+fn decrypt_data_map(
+    owner: &SecretKey,
+    chunk: &Chunk,
+) -> Result<PrivateArchiveDataMap, ArchiveHistoryError> {
+    let ciphertext =
+        Ciphertext::from_bytes(chunk.value()).map_err(|_| ArchiveHistoryError::Decryption)?;
+    let plaintext = owner
+        .decrypt(&ciphertext)
+        .ok_or(ArchiveHistoryError::Decryption)?;
+    let (hash, data_map) = plaintext
+        .split_at_checked(XorName::default().0.len())
+        .ok_or(ArchiveHistoryError::Decryption)?;
+    if XorName::from_content(data_map).0 != hash {
+        return Err(ArchiveHistoryError::Decryption);
+    }
+    Ok(DataMapChunk(Chunk::new(data_map.to_vec().into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[test]
+    fn test_private_version_encryption() {
+        let owner = SecretKey::random();
+        let data_map = DataMapChunk(Chunk::new(Bytes::from_static(b"private archive datamap")));
+
+        let chunk = encrypt_data_map(&owner.public_key(), &data_map);
+        assert_ne!(chunk.value(), data_map.0.value());
+        assert_eq!(decrypt_data_map(&owner, &chunk).unwrap(), data_map);
+
+        let other = SecretKey::random();
+        assert!(matches!(
+            decrypt_data_map(&other, &chunk),
+            Err(ArchiveHistoryError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn test_public_and_private_histories_are_distinct() {
+        let owner = SecretKey::random();
+        let public = ArchiveHistory::public(owner.public_key());
+        let private = ArchiveHistory::private(owner.public_key());
+        assert_ne!(public, private);
+        assert_ne!(public.register().owner(), owner.public_key());
+
+        // the owner holds the keys of both registers
+        assert_eq!(
+            HistoryKind::Public.register_key(&owner).public_key(),
+            public.register().owner()
+        );
+        assert_eq!(
+            HistoryKind::Private.register_key(&owner).public_key(),
+            private.register().owner()
+        );
+        assert_eq!(ArchiveHistory::from_hex(&public.to_hex()).unwrap(), public);
+    }
+}
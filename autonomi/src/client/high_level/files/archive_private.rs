@@ -14,6 +14,8 @@ use std::{
 };
 
 use super::Metadata;
+use super::archive_diff::{ArchiveDiff, ArchiveMerge, diff_entries, merge_entries, merge_posix};
+use super::posix::PosixEntries;
 use crate::files::normalize_path;
 use crate::{
     Client,
@@ -39,14 +41,21 @@ pub struct PrivateArchive {
     ///           |         |             |
     ///           V         V             V
     map: BTreeMap<PathBuf, (DataMapChunk, Metadata)>,
+    /// POSIX modes, directories and symlinks, only stored in the `V1` format
+    #[serde(default, skip_serializing_if = "PosixEntries::is_empty")]
+    posix: PosixEntries,
 }
 
 /// This type essentially wraps archive in version marker. E.g. in JSON format:
 /// `{ "V0": { "map": <xxx> } }`
+///
+/// Archives are written as `V1` only when they hold [`PosixEntries`], so that archives without
+/// remain readable by older clients.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum PrivateArchiveVersioned {
     V0(PrivateArchive),
+    V1(PrivateArchive),
 }
 
 impl PrivateArchive {
@@ -55,6 +64,7 @@ impl PrivateArchive {
     pub fn new() -> Self {
         Self {
             map: BTreeMap::new(),
+            posix: PosixEntries::default(),
         }
     }
 
@@ -71,6 +81,7 @@ impl PrivateArchive {
             .as_secs();
         meta.modified = now;
         self.map.insert(new_path.to_path_buf(), (data_addr, meta));
+        self.posix.rename(old_path, new_path);
         debug!(
             "Renamed file successfully in the private archive, old path: {old_path:?} new_path: {new_path:?}"
         );
@@ -118,18 +129,33 @@ impl PrivateArchive {
         &self.map
     }
 
+    /// Get the POSIX modes, directories and symlinks of the archive
+    pub fn posix(&self) -> &PosixEntries {
+        &self.posix
+    }
+
+    /// Get the POSIX modes, directories and symlinks of the archive, for modification
+    /// Note that this does not upload the archive to the network
+    pub fn posix_mut(&mut self) -> &mut PosixEntries {
+        &mut self.posix
+    }
+
     /// Deserialize from bytes.
     pub fn from_bytes(data: Bytes) -> Result<PrivateArchive, rmp_serde::decode::Error> {
         let root: PrivateArchiveVersioned = rmp_serde::from_slice(&data[..])?;
-        // Currently we have only `V0`. If we add `V1`, then we need an upgrade/migration path here.
-        let PrivateArchiveVersioned::V0(root) = root;
+        // `V1` only adds the POSIX entries, which default to empty when reading a `V0` archive.
+        let (PrivateArchiveVersioned::V0(root) | PrivateArchiveVersioned::V1(root)) = root;
 
         Ok(root)
     }
 
     /// Serialize to bytes.
     pub fn to_bytes(&self) -> Result<Bytes, rmp_serde::encode::Error> {
-        let versioned = PrivateArchiveVersioned::V0(self.clone());
+        let versioned = if self.posix.is_empty() {
+            PrivateArchiveVersioned::V0(self.clone())
+        } else {
+            PrivateArchiveVersioned::V1(self.clone())
+        };
         let root_serialized = rmp_serde::to_vec_named(&versioned)?;
         let root_serialized = Bytes::from(root_serialized);
 
@@ -141,6 +167,30 @@ impl PrivateArchive {
     /// Note that if there are duplicate entries for the same filename, the files from the other archive will be the ones that are kept.
     pub fn merge(&mut self, other: &PrivateArchive) {
         self.map.extend(other.map.clone());
+        self.posix.merge(&other.posix);
+    }
+
+    /// Compute the differences from this archive to a newer version of it.
+    pub fn diff(&self, new: &PrivateArchive) -> ArchiveDiff<DataMapChunk> {
+        diff_entries(&self.map, &new.map)
+    }
+
+    /// Three-way merge of this archive with `theirs`, both changed since the common `base` archive.
+    ///
+    /// Unlike [`PrivateArchive::merge`], paths changed differently on both sides are reported as conflicts
+    /// instead of being overwritten, for the files as well as for their POSIX entries, see [`ArchiveMerge`].
+    pub fn merge_three_way(
+        &self,
+        base: &PrivateArchive,
+        theirs: &PrivateArchive,
+    ) -> ArchiveMerge<PrivateArchive, DataMapChunk> {
+        let (map, conflicts) = merge_entries(&base.map, &self.map, &theirs.map);
+        let (posix, posix_conflicts) = merge_posix(&base.posix, &self.posix, &theirs.posix);
+        ArchiveMerge {
+            merged: PrivateArchive { map, posix },
+            conflicts,
+            posix_conflicts,
+        }
     }
 }
 
@@ -188,6 +238,7 @@ impl Client {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::data_types::chunk::Chunk;
     use std::str::FromStr;
 
     #[test]
@@ -222,4 +273,31 @@ mod tests {
         assert_eq!(arch.map().get(&file1).unwrap().1.size, 5);
         assert_eq!(arch.map().get(&file2).unwrap().1.size, 2);
     }
+
+    #[test]
+    fn test_private_archive_posix_format() {
+        let mut arch = PrivateArchive::new();
+        arch.add_file(
+            PathBuf::from("dir/file"),
+            DataMapChunk(Chunk::new(Bytes::from_static(b"datamap"))),
+            Metadata::new_with_size(1),
+        );
+
+        // without POSIX entries the archive stays readable by older clients
+        let bytes = arch.to_bytes().unwrap();
+        let versioned: PrivateArchiveVersioned = rmp_serde::from_slice(&bytes).unwrap();
+        assert!(matches!(versioned, PrivateArchiveVersioned::V0(_)));
+
+        arch.posix_mut()
+            .add_directory(PathBuf::from("dir/empty"), Metadata::empty());
+        arch.posix_mut().add_symlink(
+            PathBuf::from("dir/link"),
+            PathBuf::from("file"),
+            Metadata::empty(),
+        );
+        let bytes = arch.to_bytes().unwrap();
+        let versioned: PrivateArchiveVersioned = rmp_serde::from_slice(&bytes).unwrap();
+        assert!(matches!(versioned, PrivateArchiveVersioned::V1(_)));
+        assert_eq!(PrivateArchive::from_bytes(bytes).unwrap(), arch);
+    }
 }
@@ -16,6 +16,8 @@ use std::{
 };
 
 use super::Metadata;
+use super::archive_diff::{ArchiveDiff, ArchiveMerge, diff_entries, merge_entries, merge_posix};
+use super::posix::PosixEntries;
 use crate::files::normalize_path;
 use crate::{
     Client,
@@ -40,14 +42,21 @@ pub struct PublicArchive {
     ///           |         |            |
     ///           V         V            V
     map: BTreeMap<PathBuf, (DataAddress, Metadata)>,
+    /// POSIX modes, directories and symlinks, only stored in the `V1` format
+    #[serde(default, skip_serializing_if = "PosixEntries::is_empty")]
+    posix: PosixEntries,
 }
 
 /// This type essentially wraps archive in version marker. E.g. in JSON format:
 /// `{ "V0": { "map": <xxx> } }`
+///
+/// Archives are written as `V1` only when they hold [`PosixEntries`], so that archives without
+/// remain readable by older clients.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum PublicArchiveVersioned {
     V0(PublicArchive),
+    V1(PublicArchive),
 }
 
 impl PublicArchive {
@@ -56,6 +65,7 @@ impl PublicArchive {
     pub fn new() -> Self {
         Self {
             map: BTreeMap::new(),
+            posix: PosixEntries::default(),
         }
     }
 
@@ -72,6 +82,7 @@ impl PublicArchive {
             .as_secs();
         meta.modified = now;
         self.map.insert(new_path.to_path_buf(), (data_addr, meta));
+        self.posix.rename(old_path, new_path);
         debug!(
             "Renamed file successfully in the archive, old path: {old_path:?} new_path: {new_path:?}"
         );
@@ -116,18 +127,33 @@ impl PublicArchive {
         &self.map
     }
 
+    /// Get the POSIX modes, directories and symlinks of the archive
+    pub fn posix(&self) -> &PosixEntries {
+        &self.posix
+    }
+
+    /// Get the POSIX modes, directories and symlinks of the archive, for modification
+    /// Note that this does not upload the archive to the network
+    pub fn posix_mut(&mut self) -> &mut PosixEntries {
+        &mut self.posix
+    }
+
     /// Deserialize from bytes.
     pub fn from_bytes(data: Bytes) -> Result<PublicArchive, rmp_serde::decode::Error> {
         let root: PublicArchiveVersioned = rmp_serde::from_slice(&data[..])?;
-        // Currently we have only `V0`. If we add `V1`, then we need an upgrade/migration path here.
-        let PublicArchiveVersioned::V0(root) = root;
+        // `V1` only adds the POSIX entries, which default to empty when reading a `V0` archive.
+        let (PublicArchiveVersioned::V0(root) | PublicArchiveVersioned::V1(root)) = root;
 
         Ok(root)
     }
 
     /// Serialize to bytes.
     pub fn to_bytes(&self) -> Result<Bytes, rmp_serde::encode::Error> {
-        let versioned = PublicArchiveVersioned::V0(self.clone());
+        let versioned = if self.posix.is_empty() {
+            PublicArchiveVersioned::V0(self.clone())
+        } else {
+            PublicArchiveVersioned::V1(self.clone())
+        };
         let root_serialized = rmp_serde::to_vec_named(&versioned)?;
         let root_serialized = Bytes::from(root_serialized);
 
@@ -139,6 +165,30 @@ impl PublicArchive {
     /// Note that if there are duplicate entries for the same filename, the files from the other archive will be the ones that are kept.
     pub fn merge(&mut self, other: &PublicArchive) {
         self.map.extend(other.map.clone());
+        self.posix.merge(&other.posix);
+    }
+
+    /// Compute the differences from this archive to a newer version of it.
+    pub fn diff(&self, new: &PublicArchive) -> ArchiveDiff<DataAddress> {
+        diff_entries(&self.map, &new.map)
+    }
+
+    /// Three-way merge of this archive with `theirs`, both changed since the common `base` archive.
+    ///
+    /// Unlike [`PublicArchive::merge`], paths changed differently on both sides are reported as conflicts
+    /// instead of being overwritten, for the files as well as for their POSIX entries, see [`ArchiveMerge`].
+    pub fn merge_three_way(
+        &self,
+        base: &PublicArchive,
+        theirs: &PublicArchive,
+    ) -> ArchiveMerge<PublicArchive, DataAddress> {
+        let (map, conflicts) = merge_entries(&base.map, &self.map, &theirs.map);
+        let (posix, posix_conflicts) = merge_posix(&base.posix, &self.posix, &theirs.posix);
+        ArchiveMerge {
+            merged: PublicArchive { map, posix },
+            conflicts,
+            posix_conflicts,
+        }
     }
 }
 
@@ -232,6 +282,7 @@ mod test {
         pub enum FuturePublicArchiveVersioned {
             V0(PublicArchive),
             V1(PublicArchive),
+            V2(PublicArchive),
             #[serde(other)]
             Unsupported,
         }
@@ -255,7 +306,7 @@ mod test {
         let _: FuturePublicArchiveVersioned = rmp_serde::from_slice(&arch_serialized[..]).unwrap();
 
         // Now we break forward compatibility by introducing a new version not supported by the old code.
-        let future_arch = FuturePublicArchiveVersioned::V1(arch.clone());
+        let future_arch = FuturePublicArchiveVersioned::V2(arch.clone());
         let future_arch_serialized = rmp_serde::to_vec_named(&future_arch).unwrap();
         // The old archive will not be able to decode this.
         assert!(PublicArchive::from_bytes(Bytes::from(future_arch_serialized)).is_err());
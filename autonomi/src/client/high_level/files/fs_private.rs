@@ -7,8 +7,10 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::archive_private::{PrivateArchive, PrivateArchiveDataMap};
+use super::posix::PosixEntries;
 use super::{DownloadError, UploadError};
 
+use crate::client::OperationKind;
 use crate::client::data_types::chunk::DataMapChunk;
 use crate::client::payment::PaymentOption;
 use crate::{AttoTokens, Client};
@@ -22,6 +24,17 @@ impl Client {
         &self,
         data_map: &DataMapChunk,
         to_dest: PathBuf,
+    ) -> Result<(), DownloadError> {
+        self.in_operation(OperationKind::Download, |client| async move {
+            client.fetch_file_to(data_map, to_dest).await
+        })
+        .await
+    }
+
+    async fn fetch_file_to(
+        &self,
+        data_map: &DataMapChunk,
+        to_dest: PathBuf,
     ) -> Result<(), DownloadError> {
         info!("Downloading private file to {to_dest:?}");
 
@@ -42,11 +55,23 @@ impl Client {
         &self,
         archive_access: &PrivateArchiveDataMap,
         to_dest: PathBuf,
+    ) -> Result<(), DownloadError> {
+        self.in_operation(OperationKind::Download, |client| async move {
+            client.fetch_dir_to(archive_access, to_dest).await
+        })
+        .await
+    }
+
+    async fn fetch_dir_to(
+        &self,
+        archive_access: &PrivateArchiveDataMap,
+        to_dest: PathBuf,
     ) -> Result<(), DownloadError> {
         let archive = self.archive_get(archive_access).await?;
         for (path, addr, _meta) in archive.iter() {
             self.file_download(addr, to_dest.join(path)).await?;
         }
+        archive.posix().restore(&to_dest).await?;
         debug!("Downloaded directory to {to_dest:?}");
         Ok(())
     }
@@ -63,6 +88,9 @@ impl Client {
         info!("Uploading directory as private: {dir_path:?}");
 
         // encrypt
+        // directories, symlinks and modes are not part of the encrypted files
+        let posix = PosixEntries::from_dir(&dir_path)?;
+
         let encryption_results = encrypt_directory_files(dir_path, false).await?;
         let mut chunk_iterators = vec![];
         for encryption_result in encryption_results {
@@ -104,11 +132,13 @@ impl Client {
             };
             private_archive.add_file(relative_path, datamap, file_metadata);
         }
+        *private_archive.posix_mut() = posix;
 
         Ok((total_cost, private_archive))
     }
 
     /// Same as [`Client::dir_content_upload`] but also uploads the archive (privately) to the network.
+    /// The files and the archive are uploaded as a single [`OperationKind::Upload`] operation.
     ///
     /// Returns the [`PrivateArchiveDataMap`] allowing the private archive to be downloaded from the network.
     pub async fn dir_upload(
@@ -116,6 +146,29 @@ impl Client {
         dir_path: PathBuf,
         payment_option: PaymentOption,
     ) -> Result<(AttoTokens, PrivateArchiveDataMap), UploadError> {
+        let (total_cost, _archive, archive_addr) = self
+            .dir_upload_with_archive(dir_path, payment_option)
+            .await?;
+        Ok((total_cost, archive_addr))
+    }
+
+    /// Same as [`Client::dir_upload`] but also returns the uploaded [`PrivateArchive`].
+    pub async fn dir_upload_with_archive(
+        &self,
+        dir_path: PathBuf,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, PrivateArchive, PrivateArchiveDataMap), UploadError> {
+        self.in_operation(OperationKind::Upload, |client| async move {
+            client.upload_dir(dir_path, payment_option).await
+        })
+        .await
+    }
+
+    async fn upload_dir(
+        &self,
+        dir_path: PathBuf,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, PrivateArchive, PrivateArchiveDataMap), UploadError> {
         let (cost1, archive) = self
             .dir_content_upload(dir_path, payment_option.clone())
             .await?;
@@ -124,7 +177,7 @@ impl Client {
             error!("Total cost overflowed: {cost1:?} + {cost2:?}");
             cost1
         });
-        Ok((total_cost, archive_addr))
+        Ok((total_cost, archive, archive_addr))
     }
 
     /// Upload the content of a private file to the network.
@@ -133,6 +186,17 @@ impl Client {
         &self,
         path: PathBuf,
         payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, DataMapChunk), UploadError> {
+        self.in_operation(OperationKind::Upload, |client| async move {
+            client.upload_file_content(path, payment_option).await
+        })
+        .await
+    }
+
+    async fn upload_file_content(
+        &self,
+        path: PathBuf,
+        payment_option: PaymentOption,
     ) -> Result<(AttoTokens, DataMapChunk), UploadError> {
         let (data_map_chunk, processed_chunks, free_chunks, receipts) =
             self.stream_upload_file(path, payment_option, false).await?;
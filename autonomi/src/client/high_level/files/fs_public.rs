@@ -7,12 +7,13 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use super::archive_public::{ArchiveAddress, PublicArchive};
+use super::posix::PosixEntries;
 use super::{DownloadError, FileCostError, Metadata, UploadError};
 use crate::AttoTokens;
-use crate::client::Client;
 use crate::client::data_types::chunk::{ChunkAddress, DataMapChunk};
 use crate::client::high_level::data::DataAddress;
 use crate::client::payment::PaymentOption;
+use crate::client::{Client, OperationKind};
 use bytes::Bytes;
 use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
@@ -23,6 +24,17 @@ impl Client {
         &self,
         data_addr: &DataAddress,
         to_dest: PathBuf,
+    ) -> Result<(), DownloadError> {
+        self.in_operation(OperationKind::Download, |client| async move {
+            client.fetch_public_file_to(data_addr, to_dest).await
+        })
+        .await
+    }
+
+    async fn fetch_public_file_to(
+        &self,
+        data_addr: &DataAddress,
+        to_dest: PathBuf,
     ) -> Result<(), DownloadError> {
         info!("Downloading public file to {to_dest:?} from {data_addr:?}");
 
@@ -40,12 +52,24 @@ impl Client {
         &self,
         archive_addr: &ArchiveAddress,
         to_dest: PathBuf,
+    ) -> Result<(), DownloadError> {
+        self.in_operation(OperationKind::Download, |client| async move {
+            client.fetch_public_dir_to(archive_addr, to_dest).await
+        })
+        .await
+    }
+
+    async fn fetch_public_dir_to(
+        &self,
+        archive_addr: &ArchiveAddress,
+        to_dest: PathBuf,
     ) -> Result<(), DownloadError> {
         let archive = self.archive_get_public(archive_addr).await?;
         debug!("Downloaded archive for the directory from the network at {archive_addr:?}");
         for (path, addr, _meta) in archive.iter() {
             self.file_download_public(addr, to_dest.join(path)).await?;
         }
+        archive.posix().restore(&to_dest).await?;
         debug!(
             "All files in the directory downloaded to {:?} from the network address {:?}",
             to_dest.parent(),
@@ -68,6 +92,9 @@ impl Client {
         info!("Uploading directory: {dir_path:?}");
 
         // encrypt
+        // directories, symlinks and modes are not part of the encrypted files
+        let posix = PosixEntries::from_dir(&dir_path)?;
+
         let encryption_results =
             crate::self_encryption::encrypt_directory_files(dir_path, true).await?;
         let mut chunk_iterators = vec![];
@@ -109,6 +136,7 @@ impl Client {
             };
             public_archive.add_file(relative_path, data_address, file_metadata);
         }
+        *public_archive.posix_mut() = posix;
 
         for (file_path, data_addr, _meta) in public_archive.iter() {
             info!("Uploaded file: {file_path:?} to: {data_addr}");
@@ -120,6 +148,7 @@ impl Client {
     }
 
     /// Same as [`Client::dir_content_upload_public`] but also uploads the archive to the network.
+    /// The files and the archive are uploaded as a single [`OperationKind::Upload`] operation.
     ///
     /// Returns the [`ArchiveAddress`] of the uploaded archive.
     pub async fn dir_upload_public(
@@ -127,6 +156,29 @@ impl Client {
         dir_path: PathBuf,
         payment_option: PaymentOption,
     ) -> Result<(AttoTokens, ArchiveAddress), UploadError> {
+        let (total_cost, _archive, archive_addr) = self
+            .dir_upload_public_with_archive(dir_path, payment_option)
+            .await?;
+        Ok((total_cost, archive_addr))
+    }
+
+    /// Same as [`Client::dir_upload_public`] but also returns the uploaded [`PublicArchive`].
+    pub async fn dir_upload_public_with_archive(
+        &self,
+        dir_path: PathBuf,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, PublicArchive, ArchiveAddress), UploadError> {
+        self.in_operation(OperationKind::Upload, |client| async move {
+            client.upload_public_dir(dir_path, payment_option).await
+        })
+        .await
+    }
+
+    async fn upload_public_dir(
+        &self,
+        dir_path: PathBuf,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, PublicArchive, ArchiveAddress), UploadError> {
         let (cost1, archive) = self
             .dir_content_upload_public(dir_path, payment_option.clone())
             .await?;
@@ -135,7 +187,7 @@ impl Client {
             error!("Total cost overflowed: {cost1:?} + {cost2:?}");
             cost1
         });
-        Ok((total_cost, archive_addr))
+        Ok((total_cost, archive, archive_addr))
     }
 
     /// Upload the content of a file to the network.
@@ -144,6 +196,19 @@ impl Client {
         &self,
         path: PathBuf,
         payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, DataAddress), UploadError> {
+        self.in_operation(OperationKind::Upload, |client| async move {
+            client
+                .upload_public_file_content(path, payment_option)
+                .await
+        })
+        .await
+    }
+
+    async fn upload_public_file_content(
+        &self,
+        path: PathBuf,
+        payment_option: PaymentOption,
     ) -> Result<(AttoTokens, DataAddress), UploadError> {
         let (data_map_chunk, processed_chunks, free_chunks, receipts) = self
             .stream_upload_file(path.clone(), payment_option, true)
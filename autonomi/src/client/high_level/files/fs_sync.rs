@@ -0,0 +1,404 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::archive_private::PrivateArchive;
+use super::archive_public::PublicArchive;
+use super::fs_public::metadata_from_entry;
+use super::posix::PosixEntries;
+use super::{Metadata, UploadError, get_relative_file_path_from_abs_file_and_folder_path};
+use crate::client::config::FILE_ENCRYPT_BATCH_SIZE;
+use crate::client::data_types::chunk::{ChunkAddress, DataMapChunk};
+use crate::client::high_level::data::DataAddress;
+use crate::client::payment::PaymentOption;
+use crate::client::{GetError, OperationKind};
+use crate::self_encryption::{EncryptionStream, encrypt_file};
+use crate::utils::process_tasks_with_max_concurrency;
+use crate::{AttoTokens, Client};
+use self_encryption::DataMap;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use xor_name::XorName;
+
+/// Changes found by [`Client::dir_sync`] between a directory and its previous archive.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirSyncReport {
+    /// Files not in the previous archive, which were uploaded
+    pub added: Vec<PathBuf>,
+    /// Files whose content changed since the previous archive, which were uploaded
+    pub modified: Vec<PathBuf>,
+    /// Files whose content did not change, re-using their entry in the previous archive
+    pub unchanged: Vec<PathBuf>,
+    /// Files of the previous archive that are no longer in the directory
+    pub removed: Vec<PathBuf>,
+}
+
+impl DirSyncReport {
+    /// Returns true if the directory has the same content as the previous archive.
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// An archive entry giving access to the datamap of a file, to compare its content hashes.
+trait SyncedEntry: Clone + Send + Sync {
+    fn datamap(&self, client: &Client) -> impl Future<Output = Result<DataMap, GetError>> + Send;
+}
+
+impl SyncedEntry for DataMapChunk {
+    async fn datamap(&self, client: &Client) -> Result<DataMap, GetError> {
+        client.restore_data_map_from_chunk(self).await
+    }
+}
+
+impl SyncedEntry for DataAddress {
+    async fn datamap(&self, client: &Client) -> Result<DataMap, GetError> {
+        let chunk = client
+            .chunk_get(&ChunkAddress::new(*self.xorname()))
+            .await?;
+        client
+            .restore_data_map_from_chunk(&DataMapChunk(chunk))
+            .await
+    }
+}
+
+/// Files to re-use from the previous archive and files to upload.
+struct SyncPlan<T> {
+    reused: Vec<(PathBuf, T, Metadata)>,
+    /// (relative path, file path, metadata)
+    to_upload: Vec<(PathBuf, PathBuf, Metadata)>,
+}
+
+impl Client {
+    /// Upload the changes of a directory since a previous [`PrivateArchive`] of it.
+    ///
+    /// Files with the same size and modification time as in the previous archive are re-used as is.
+    /// Files with the same size but a different modification time are compared against the content
+    /// hashes of their datamap, so that touched but unchanged files are not uploaded again.
+    /// Only added and modified files are encrypted and uploaded.
+    ///
+    /// Returns the new archive (not uploaded) along with a report of the changes.
+    pub async fn dir_sync(
+        &self,
+        dir_path: PathBuf,
+        previous: &PrivateArchive,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, PrivateArchive, DirSyncReport), UploadError> {
+        self.in_operation(OperationKind::Upload, |client| async move {
+            client.sync_dir(dir_path, previous, payment_option).await
+        })
+        .await
+    }
+
+    async fn sync_dir(
+        &self,
+        dir_path: PathBuf,
+        previous: &PrivateArchive,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, PrivateArchive, DirSyncReport), UploadError> {
+        info!("Syncing directory as private: {dir_path:?}");
+        let (plan, report) = self.dir_sync_plan(&dir_path, previous.map()).await?;
+        let (total_cost, uploaded) = self
+            .upload_sync_files(plan.to_upload, payment_option, false)
+            .await?;
+
+        let mut archive = PrivateArchive::new();
+        *archive.posix_mut() = PosixEntries::from_dir(&dir_path)?;
+        for (relative_path, data_map, metadata) in plan.reused {
+            archive.add_file(relative_path, data_map, metadata);
+        }
+        for file in uploaded {
+            let data_map = file.data_map_chunk().ok_or_else(|| {
+                error!(
+                    "Datamap chunk not found for file: {:?}, this is a BUG",
+                    file.file_path
+                );
+                UploadError::Encryption(
+                    "Data map chunk not found after encryption, this is a BUG".to_string(),
+                )
+            })?;
+            archive.add_file(file.relative_path.clone(), data_map, file.metadata.clone());
+        }
+
+        info!(
+            "Synced directory {dir_path:?}: {} added, {} modified, {} unchanged, {} removed",
+            report.added.len(),
+            report.modified.len(),
+            report.unchanged.len(),
+            report.removed.len()
+        );
+        Ok((total_cost, archive, report))
+    }
+
+    /// Upload the changes of a directory since a previous [`PublicArchive`] of it.
+    ///
+    /// See [`Client::dir_sync`] for how changes are detected.
+    /// Returns the new archive (not uploaded) along with a report of the changes.
+    pub async fn dir_sync_public(
+        &self,
+        dir_path: PathBuf,
+        previous: &PublicArchive,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, PublicArchive, DirSyncReport), UploadError> {
+        self.in_operation(OperationKind::Upload, |client| async move {
+            client
+                .sync_dir_public(dir_path, previous, payment_option)
+                .await
+        })
+        .await
+    }
+
+    async fn sync_dir_public(
+        &self,
+        dir_path: PathBuf,
+        previous: &PublicArchive,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, PublicArchive, DirSyncReport), UploadError> {
+        info!("Syncing directory as public: {dir_path:?}");
+        let (plan, report) = self.dir_sync_plan(&dir_path, previous.map()).await?;
+        let (total_cost, uploaded) = self
+            .upload_sync_files(plan.to_upload, payment_option, true)
+            .await?;
+
+        let mut archive = PublicArchive::new();
+        *archive.posix_mut() = PosixEntries::from_dir(&dir_path)?;
+        for (relative_path, data_addr, metadata) in plan.reused {
+            archive.add_file(relative_path, data_addr, metadata);
+        }
+        for file in uploaded {
+            let data_addr = file.data_address().ok_or_else(|| {
+                error!(
+                    "Data address not found for file: {:?}, this is a BUG",
+                    file.file_path
+                );
+                UploadError::Encryption(
+                    "Data address not found after encryption, this is a BUG".to_string(),
+                )
+            })?;
+            archive.add_file(file.relative_path.clone(), data_addr, file.metadata.clone());
+        }
+
+        info!(
+            "Synced directory {dir_path:?}: {} added, {} modified, {} unchanged, {} removed",
+            report.added.len(),
+            report.modified.len(),
+            report.unchanged.len(),
+            report.removed.len()
+        );
+        Ok((total_cost, archive, report))
+    }
+
+    /// Walk the directory and sort its files into the ones to re-use from the previous archive and the ones to upload.
+    async fn dir_sync_plan<T: SyncedEntry>(
+        &self,
+        dir_path: &Path,
+        previous: &BTreeMap<PathBuf, (T, Metadata)>,
+    ) -> Result<(SyncPlan<T>, DirSyncReport), UploadError> {
+        let mut plan = SyncPlan {
+            reused: vec![],
+            to_upload: vec![],
+        };
+        let mut report = DirSyncReport::default();
+
+        let mut seen = std::collections::HashSet::new();
+        let mut to_compare = vec![];
+        for entry in walkdir::WalkDir::new(dir_path) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let file_path = entry.path().to_path_buf();
+            let relative_path =
+                get_relative_file_path_from_abs_file_and_folder_path(&file_path, dir_path);
+            let metadata = metadata_from_entry(&entry);
+            seen.insert(relative_path.clone());
+
+            let Some((previous_entry, previous_metadata)) = previous.get(&relative_path) else {
+                report.added.push(relative_path.clone());
+                plan.to_upload.push((relative_path, file_path, metadata));
+                continue;
+            };
+
+            if previous_metadata.size != metadata.size {
+                report.modified.push(relative_path.clone());
+                plan.to_upload.push((relative_path, file_path, metadata));
+            } else if previous_metadata.modified == metadata.modified {
+                report.unchanged.push(relative_path.clone());
+                plan.reused
+                    .push((relative_path, previous_entry.clone(), metadata));
+            } else {
+                to_compare.push((relative_path, file_path, metadata, previous_entry.clone()));
+            }
+        }
+
+        // touched files are compared against their previous content hashes concurrently
+        let compare_tasks = to_compare.into_iter().map(
+            |(relative_path, file_path, metadata, previous_entry)| async move {
+                let unchanged = self.same_content(&file_path, &previous_entry).await;
+                (
+                    relative_path,
+                    file_path,
+                    metadata,
+                    previous_entry,
+                    unchanged,
+                )
+            },
+        );
+        let compared =
+            process_tasks_with_max_concurrency(compare_tasks, *FILE_ENCRYPT_BATCH_SIZE).await;
+        for (relative_path, file_path, metadata, previous_entry, unchanged) in compared {
+            if unchanged {
+                report.unchanged.push(relative_path.clone());
+                plan.reused.push((relative_path, previous_entry, metadata));
+            } else {
+                report.modified.push(relative_path.clone());
+                plan.to_upload.push((relative_path, file_path, metadata));
+            }
+        }
+
+        report.removed = previous
+            .keys()
+            .filter(|path| !seen.contains(*path))
+            .cloned()
+            .collect();
+
+        debug!(
+            "Sync plan for {dir_path:?}: {} files to re-use, {} files to upload",
+            plan.reused.len(),
+            plan.to_upload.len()
+        );
+        Ok((plan, report))
+    }
+
+    /// Compare a local file against the content hashes of an archive entry.
+    /// Any failure to compare is treated as a change, so the file gets uploaded again.
+    async fn same_content<T: SyncedEntry>(&self, file_path: &Path, entry: &T) -> bool {
+        let datamap = match entry.datamap(self).await {
+            Ok(datamap) => datamap,
+            Err(err) => {
+                warn!(
+                    "Failed to fetch the datamap of {file_path:?}, treating it as modified: {err}"
+                );
+                return false;
+            }
+        };
+
+        let path = file_path.to_path_buf();
+        match tokio::task::spawn_blocking(move || content_matches(&path, &datamap)).await {
+            Ok(Ok(matches)) => matches,
+            Ok(Err(err)) => {
+                warn!("Failed to hash {file_path:?}, treating it as modified: {err}");
+                false
+            }
+            Err(err) => {
+                warn!("Failed to hash {file_path:?}, treating it as modified: {err}");
+                false
+            }
+        }
+    }
+
+    /// Encrypt and upload the given files, returning their encryption streams to read the results from.
+    async fn upload_sync_files(
+        &self,
+        files: Vec<(PathBuf, PathBuf, Metadata)>,
+        payment_option: PaymentOption,
+        is_public: bool,
+    ) -> Result<(AttoTokens, Vec<EncryptionStream>), UploadError> {
+        if files.is_empty() {
+            info!("No changed files to upload");
+            return Ok((AttoTokens::zero(), vec![]));
+        }
+
+        let encryption_tasks = files
+            .into_iter()
+            .map(|(relative_path, file_path, metadata)| {
+                let file_size = metadata.size as usize;
+                encrypt_file(relative_path, file_path, file_size, metadata, is_public)
+            });
+        let encryption_results =
+            process_tasks_with_max_concurrency(encryption_tasks, *FILE_ENCRYPT_BATCH_SIZE).await;
+
+        let mut streams = vec![];
+        for encryption_result in encryption_results {
+            match encryption_result {
+                Ok(stream) => streams.push(stream),
+                Err(err_msg) => {
+                    error!("Error during file encryption: {err_msg}");
+                    #[cfg(feature = "loud")]
+                    println!("Error during file encryption: {err_msg}");
+                    return Err(UploadError::Encryption(err_msg));
+                }
+            }
+        }
+
+        let total_cost = self.pay_and_upload(payment_option, &mut streams).await?;
+        Ok((total_cost, streams))
+    }
+}
+
+/// Returns true if the file content hashes to the source hashes of the datamap chunks.
+fn content_matches(path: &Path, datamap: &DataMap) -> std::io::Result<bool> {
+    let mut infos = datamap.infos();
+    infos.sort_by_key(|info| info.index);
+
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = vec![];
+    for info in infos {
+        buf.resize(info.src_size, 0);
+        match file.read_exact(&mut buf) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(false),
+            Err(err) => return Err(err),
+        }
+        if XorName::from_content(&buf) != info.src_hash {
+            return Ok(false);
+        }
+    }
+
+    // the file must not hold more data than the datamap describes
+    Ok(file.read(&mut [0u8; 1])? == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::encrypt_locally;
+    use bytes::Bytes;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn temp_file_with(data: &[u8]) -> NamedTempFile {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(data).expect("Failed to write temp file");
+        file.flush().expect("Failed to flush temp file");
+        file
+    }
+
+    #[test]
+    fn test_content_matches() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(2_000_000).collect();
+        let (datamap, _) = encrypt_locally(&Bytes::from(data.clone()));
+
+        let same = temp_file_with(&data);
+        assert!(content_matches(same.path(), &datamap).unwrap());
+
+        let mut changed_data = data.clone();
+        changed_data[1_000_000] ^= 0xff;
+        let changed = temp_file_with(&changed_data);
+        assert!(!content_matches(changed.path(), &datamap).unwrap());
+
+        let truncated = temp_file_with(&data[..data.len() - 1]);
+        assert!(!content_matches(truncated.path(), &datamap).unwrap());
+
+        let mut appended_data = data.clone();
+        appended_data.push(0);
+        let appended = temp_file_with(&appended_data);
+        assert!(!content_matches(appended.path(), &datamap).unwrap());
+    }
+}
@@ -10,13 +10,14 @@ use self_encryption::DataMap;
 use serde::{Deserialize, Serialize};
 use std::{
     path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
 use tracing::info;
 
 use crate::client::data_types::chunk::ChunkAddress;
-use crate::client::{GetError, PutError, quote::CostError};
+use crate::client::{ClientEvent, GetError, PutError, quote::CostError};
 use crate::utils::process_tasks_with_max_concurrency;
 use crate::{
     Client,
@@ -27,13 +28,27 @@ use bytes::Bytes;
 use self_encryption::streaming_decrypt_from_storage;
 use xor_name::XorName;
 
+pub mod archive_diff;
+pub mod archive_history;
 pub mod archive_private;
 pub mod archive_public;
 pub mod fs_private;
 pub mod fs_public;
-
+pub mod fs_sync;
+pub mod network_file;
+pub mod posix;
+pub mod upload_journal;
+pub mod upload_plan;
+
+pub use archive_diff::{ArchiveDiff, ArchiveMerge, MergeConflict, PathConflict, PosixConflict};
+pub use archive_history::{ArchiveHistory, ArchiveHistoryError};
 pub use archive_private::PrivateArchive;
 pub use archive_public::PublicArchive;
+pub use fs_sync::DirSyncReport;
+pub use network_file::NetworkFile;
+pub use posix::{PosixAttributes, PosixEntries, SpecialEntry};
+pub use upload_journal::{UploadJournal, UploadJournalError, UploadJournalKind};
+pub use upload_plan::{FileUploadPlan, UploadPlan};
 
 /// Metadata for a file in an archive. Time values are UNIX timestamps (UTC).
 ///
@@ -105,6 +120,8 @@ pub enum UploadError {
     PutError(#[from] PutError),
     #[error("Encryption error")]
     Encryption(String),
+    #[error("Upload journal error: {0}")]
+    Journal(#[from] UploadJournalError),
 }
 
 /// Errors that can occur during the download operation.
@@ -187,6 +204,7 @@ impl Client {
 
         // Create parallel chunk fetcher for streaming decryption
         let client_clone = self.clone();
+        let fetched_chunks = AtomicUsize::new(0);
         let parallel_chunk_fetcher = move |chunk_names: &[(usize, XorName)]| -> Result<
             Vec<(usize, Bytes)>,
             self_encryption::Error,
@@ -197,21 +215,31 @@ impl Client {
                 .collect();
 
             // Use tokio::task::block_in_place to handle async in sync context
-            tokio::task::block_in_place(|| {
+            let chunks = tokio::task::block_in_place(|| {
                 tokio::runtime::Handle::current().block_on(async {
                     client_clone
                         .fetch_chunks_parallel(&chunk_addresses, total_chunks)
                         .await
                 })
-            })
+            })?;
+
+            let fetched = fetched_chunks.fetch_add(chunks.len(), Ordering::Relaxed) + chunks.len();
+            client_clone.send_event(|op| ClientEvent::DecryptionProgress {
+                op,
+                chunks: fetched,
+                total_chunks,
+            });
+            Ok(chunks)
         };
 
         // Stream decrypt directly to file
         streaming_decrypt_from_storage(&data_map, to_dest, parallel_chunk_fetcher).map_err(
-            |e| {
-                DownloadError::GetError(crate::client::GetError::Decryption(
+            |e| match self.operation_options.check() {
+                // the fetched chunks are kept in the chunk cache for the next attempt
+                Err(reason) => DownloadError::GetError(GetError::Interrupted(reason)),
+                Ok(()) => DownloadError::GetError(crate::client::GetError::Decryption(
                     crate::self_encryption::Error::SelfEncryption(e),
-                ))
+                )),
             },
         )?;
 
@@ -243,8 +271,11 @@ impl Client {
                 println!("Fetching chunk {i}/{total_chunks} ...");
                 info!("Fetching chunk {i}/{total_chunks}({addr_clone:?})");
                 let result = client_clone
-                    .chunk_get(&addr_clone)
+                    .operation_options
+                    .interruptible(client_clone.chunk_get(&addr_clone))
                     .await
+                    .map_err(GetError::from)
+                    .and_then(|res| res)
                     .map(|chunk| (*i, chunk.value))
                     .map_err(|e| {
                         self_encryption::Error::Generic(format!(
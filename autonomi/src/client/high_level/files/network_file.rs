@@ -0,0 +1,269 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::Client;
+use crate::client::data_types::chunk::{ChunkAddress, DataMapChunk};
+use crate::client::high_level::data::DataAddress;
+use crate::client::{GetError, OperationKind};
+use bytes::Bytes;
+use self_encryption::DataMap;
+use std::future::Future;
+use std::io::{self, SeekFrom};
+use std::ops::Range;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+use tokio::task::JoinHandle;
+
+/// Default number of chunks fetched together, the next group being prefetched while the current one is read.
+pub const DEFAULT_READ_AHEAD_CHUNKS: usize = 2;
+
+/// A file on the network, readable through [`AsyncRead`] and [`AsyncSeek`].
+///
+/// Chunks are fetched and decrypted in groups of [`NetworkFile::with_read_ahead`] chunks.
+/// While a group is being read, the following group is already fetched in the background,
+/// so sequential readers rarely wait on the network. Opening the file and fetching each group are
+/// reported as [`OperationKind::Download`] operations.
+///
+/// # Example
+///
+/// ```no_run
+/// use autonomi::Client;
+/// use tokio::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client = Client::init().await?;
+/// # let data_map = todo!();
+/// let mut file = client.file_open(&data_map).await?;
+/// file.seek(SeekFrom::End(-1024)).await?;
+/// let mut tail = vec![];
+/// file.read_to_end(&mut tail).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct NetworkFile {
+    client: Client,
+    datamap: DataMap,
+    /// Start offset of each chunk in index order, followed by the size of the data
+    offsets: Vec<u64>,
+    position: u64,
+    read_ahead: usize,
+    /// Decrypted group of chunks: (start offset, bytes)
+    buffer: Option<(u64, Bytes)>,
+    /// Group of chunks being fetched: (start offset, task)
+    pending: Option<(u64, JoinHandle<Result<Bytes, GetError>>)>,
+}
+
+impl NetworkFile {
+    pub(crate) fn new(client: Client, datamap: DataMap) -> Self {
+        let mut infos = datamap.infos();
+        infos.sort_by_key(|info| info.index);
+        let mut offsets = Vec::with_capacity(infos.len() + 1);
+        let mut offset = 0u64;
+        offsets.push(offset);
+        for info in infos {
+            offset += info.src_size as u64;
+            offsets.push(offset);
+        }
+
+        Self {
+            client,
+            datamap,
+            offsets,
+            position: 0,
+            read_ahead: DEFAULT_READ_AHEAD_CHUNKS,
+            buffer: None,
+            pending: None,
+        }
+    }
+
+    /// Set the number of chunks fetched together and prefetched ahead of the reader.
+    pub fn with_read_ahead(mut self, chunks: usize) -> Self {
+        self.read_ahead = std::cmp::max(1, chunks);
+        self
+    }
+
+    /// Returns the size of the file in bytes.
+    pub fn size(&self) -> u64 {
+        self.offsets.last().copied().unwrap_or(0)
+    }
+
+    /// Returns the current read position.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn spawn_fetch(&self, window: Range<u64>) -> JoinHandle<Result<Bytes, GetError>> {
+        let client = self.client.clone();
+        let datamap = self.datamap.clone();
+        tokio::spawn(async move {
+            client
+                .in_operation(OperationKind::Download, |client| async move {
+                    client.read_range_from_data_map(&datamap, window).await
+                })
+                .await
+        })
+    }
+
+    /// Start fetching the group of chunks following the current one, if not already done.
+    fn prefetch_after(&mut self, window_end: u64) {
+        if self.pending.is_some() {
+            return;
+        }
+        if let Some(next) = window_for(&self.offsets, self.read_ahead, window_end) {
+            debug!("Prefetching range {next:?} of network file");
+            self.pending = Some((next.start, self.spawn_fetch(next)));
+        }
+    }
+}
+
+impl Drop for NetworkFile {
+    fn drop(&mut self) {
+        if let Some((_, handle)) = self.pending.take() {
+            handle.abort();
+        }
+    }
+}
+
+impl AsyncRead for NetworkFile {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        loop {
+            // reading at or past the end of the file
+            let Some(window) = window_for(&this.offsets, this.read_ahead, this.position) else {
+                return Poll::Ready(Ok(()));
+            };
+
+            if let Some((start, bytes)) = &this.buffer
+                && *start == window.start
+            {
+                let offset = (this.position - start) as usize;
+                let available = bytes.len().saturating_sub(offset);
+                let n = std::cmp::min(available, buf.remaining());
+                buf.put_slice(&bytes[offset..offset + n]);
+                this.position += n as u64;
+                this.prefetch_after(window.end);
+                return Poll::Ready(Ok(()));
+            }
+
+            match this.pending.as_mut() {
+                Some((start, handle)) if *start == window.start => {
+                    let result = ready!(Pin::new(handle).poll(cx));
+                    this.pending = None;
+                    let bytes = result
+                        .map_err(io::Error::other)?
+                        .map_err(io::Error::other)?;
+                    this.buffer = Some((window.start, bytes));
+                }
+                Some(_) => {
+                    // the reader moved away from what was prefetched
+                    if let Some((_, handle)) = this.pending.take() {
+                        handle.abort();
+                    }
+                }
+                None => {
+                    this.pending = Some((window.start, this.spawn_fetch(window)));
+                }
+            }
+        }
+    }
+}
+
+impl AsyncSeek for NetworkFile {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        let this = self.get_mut();
+        let new_position = match position {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(delta) => this.size().checked_add_signed(delta),
+            SeekFrom::Current(delta) => this.position.checked_add_signed(delta),
+        };
+        match new_position {
+            Some(new_position) => {
+                this.position = new_position;
+                Ok(())
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Poll::Ready(Ok(self.position))
+    }
+}
+
+/// Returns the byte range of the group of chunks holding `position`, or `None` past the end of the data.
+fn window_for(offsets: &[u64], read_ahead: usize, position: u64) -> Option<Range<u64>> {
+    let total_chunks = offsets.len().saturating_sub(1);
+    if position >= offsets.last().copied().unwrap_or(0) {
+        return None;
+    }
+    let chunk = offsets.partition_point(|offset| *offset <= position) - 1;
+    let first = (chunk / read_ahead) * read_ahead;
+    let last = std::cmp::min(first + read_ahead, total_chunks);
+    Some(offsets[first]..offsets[last])
+}
+
+impl Client {
+    /// Open a private file on the network for reading with [`AsyncRead`] and [`AsyncSeek`].
+    pub async fn file_open(&self, data_map: &DataMapChunk) -> Result<NetworkFile, GetError> {
+        info!(
+            "Opening private file from datamap {:?}",
+            data_map.0.address()
+        );
+        let datamap = self
+            .in_operation(OperationKind::Download, |client| async move {
+                client.restore_data_map_from_chunk(data_map).await
+            })
+            .await?;
+        Ok(NetworkFile::new(self.clone(), datamap))
+    }
+
+    /// Open a public file on the network for reading with [`AsyncRead`] and [`AsyncSeek`].
+    pub async fn file_open_public(&self, addr: &DataAddress) -> Result<NetworkFile, GetError> {
+        info!("Opening public file from Data Address: {addr:?}");
+        let datamap = self
+            .in_operation(OperationKind::Download, |client| async move {
+                let data_map_chunk = DataMapChunk(
+                    client
+                        .chunk_get(&ChunkAddress::new(*addr.xorname()))
+                        .await?,
+                );
+                client.restore_data_map_from_chunk(&data_map_chunk).await
+            })
+            .await?;
+        Ok(NetworkFile::new(self.clone(), datamap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_for() {
+        // 5 chunks of 10 bytes, the last one being 5 bytes
+        let offsets = vec![0, 10, 20, 30, 40, 45];
+
+        assert_eq!(window_for(&offsets, 2, 0), Some(0..20));
+        assert_eq!(window_for(&offsets, 2, 19), Some(0..20));
+        assert_eq!(window_for(&offsets, 2, 20), Some(20..40));
+        assert_eq!(window_for(&offsets, 2, 44), Some(40..45));
+        assert_eq!(window_for(&offsets, 2, 45), None);
+        assert_eq!(window_for(&offsets, 1, 25), Some(20..30));
+        assert_eq!(window_for(&offsets, 10, 25), Some(0..45));
+        assert_eq!(window_for(&[0], 2, 0), None);
+    }
+}
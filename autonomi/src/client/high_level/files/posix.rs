@@ -0,0 +1,455 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::fs_public::metadata_from_entry;
+use super::{
+    Metadata, UploadError, get_relative_file_path_from_abs_file_and_folder_path, normalize_path,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+
+/// Mode of files created with the usual umask, not recorded in archives.
+pub(crate) const DEFAULT_FILE_MODE: u32 = 0o644;
+/// Mode of directories created with the usual umask, not recorded in archives.
+pub(crate) const DEFAULT_DIR_MODE: u32 = 0o755;
+/// Maximum number of symlinks followed when resolving a symlink target, as `SYMLOOP_MAX` on Linux.
+const MAX_SYMLINKS_FOLLOWED: usize = 40;
+
+/// POSIX attributes of an archive entry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PosixAttributes {
+    /// Mode bits of the entry: permissions, setuid, setgid and sticky bits
+    pub mode: u32,
+    /// Extended attributes of the entry, by name, if they were captured
+    pub xattrs: Option<BTreeMap<String, Vec<u8>>>,
+}
+
+/// An archive entry that is not a regular file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SpecialEntry {
+    /// A directory, recorded explicitly so that empty directories are kept
+    Directory,
+    /// A symbolic link, with the path it points to
+    Symlink(PathBuf),
+}
+
+/// The POSIX side of an archive: attributes of its entries, its directories and its symlinks.
+///
+/// Archives holding any of these are stored in the `V1` archive format, archives without stay in the `V0` format.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PosixEntries {
+    /// POSIX attributes of the files, directories and symlinks of the archive, by path
+    pub attributes: BTreeMap<PathBuf, PosixAttributes>,
+    /// Directories and symlinks of the archive, by path
+    pub special: BTreeMap<PathBuf, (SpecialEntry, Metadata)>,
+}
+
+impl PosixEntries {
+    /// Returns true if there are no POSIX attributes, directories nor symlinks.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.special.is_empty()
+    }
+
+    /// Add a directory.
+    pub fn add_directory(&mut self, path: PathBuf, meta: Metadata) {
+        self.special
+            .insert(normalize_path(path), (SpecialEntry::Directory, meta));
+    }
+
+    /// Add a symbolic link pointing to `target`.
+    pub fn add_symlink(&mut self, path: PathBuf, target: PathBuf, meta: Metadata) {
+        self.special
+            .insert(normalize_path(path), (SpecialEntry::Symlink(target), meta));
+    }
+
+    /// Set the POSIX attributes of an entry.
+    pub fn set_attributes(&mut self, path: PathBuf, attributes: PosixAttributes) {
+        self.attributes.insert(normalize_path(path), attributes);
+    }
+
+    /// Merge with other entries, the other entries being kept for duplicate paths.
+    pub fn merge(&mut self, other: &PosixEntries) {
+        self.attributes.extend(other.attributes.clone());
+        self.special.extend(other.special.clone());
+    }
+
+    /// Move the entries at `old_path` to `new_path`.
+    pub(crate) fn rename(&mut self, old_path: &Path, new_path: &Path) {
+        if let Some(attributes) = self.attributes.remove(old_path) {
+            self.attributes.insert(new_path.to_path_buf(), attributes);
+        }
+        if let Some(entry) = self.special.remove(old_path) {
+            self.special.insert(new_path.to_path_buf(), entry);
+        }
+    }
+
+    /// Capture the empty directories, symlinks and (on unix) the non-default modes of the entries
+    /// of a directory, keyed by the same relative paths as the files of an archive of that directory.
+    ///
+    /// Directories holding files are already recreated when downloading the files, and files or
+    /// directories with the usual [`DEFAULT_FILE_MODE`] or [`DEFAULT_DIR_MODE`] need no attributes,
+    /// so that directories without anything else to record are still archived in the `V0` format.
+    /// Extended attributes are not captured.
+    pub(crate) fn from_dir(dir_path: &Path) -> Result<Self, UploadError> {
+        let mut entries = PosixEntries::default();
+        for entry in walkdir::WalkDir::new(dir_path).min_depth(1) {
+            let entry = entry?;
+            let path = entry.path();
+            let relative_path =
+                get_relative_file_path_from_abs_file_and_folder_path(path, dir_path);
+
+            let file_type = entry.file_type();
+            if file_type.is_dir() {
+                if std::fs::read_dir(path)?.next().is_none() {
+                    entries.add_directory(relative_path.clone(), metadata_from_entry(&entry));
+                }
+            } else if file_type.is_symlink() {
+                let target = std::fs::read_link(path)?;
+                entries.add_symlink(relative_path, target, metadata_from_entry(&entry));
+                // the mode of a symlink itself is meaningless
+                continue;
+            }
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = entry.metadata()?.permissions().mode() & 0o7777;
+                let default_mode = if file_type.is_dir() {
+                    DEFAULT_DIR_MODE
+                } else {
+                    DEFAULT_FILE_MODE
+                };
+                if mode != default_mode {
+                    entries.set_attributes(relative_path, PosixAttributes { mode, xattrs: None });
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Restore the directories, symlinks and (on unix) the modes of the entries under `to_dest`.
+    /// Meant to be called once the files of the archive have been downloaded there.
+    ///
+    /// Extended attributes are not restored. Entries with a path leaving `to_dest`, and symlinks
+    /// pointing outside of it once resolved through the other symlinks of the archive, are rejected
+    /// before anything is restored. Modes are not restored through symlinks.
+    pub(crate) async fn restore(&self, to_dest: &Path) -> std::io::Result<()> {
+        for path in self.attributes.keys().chain(self.special.keys()) {
+            check_relative_path(path)?;
+        }
+        let symlinks: BTreeMap<&Path, &Path> = self
+            .special
+            .iter()
+            .filter_map(|(path, (entry, _meta))| match entry {
+                SpecialEntry::Symlink(target) => Some((path.as_path(), target.as_path())),
+                SpecialEntry::Directory => None,
+            })
+            .collect();
+        for (path, target) in symlinks.iter() {
+            check_symlink_target(path, target, &symlinks)?;
+        }
+
+        for (path, (entry, _meta)) in self.special.iter() {
+            let dest = to_dest.join(path);
+            match entry {
+                SpecialEntry::Directory => tokio::fs::create_dir_all(&dest).await?,
+                SpecialEntry::Symlink(target) => {
+                    if let Some(parent) = dest.parent() {
+                        tokio::fs::create_dir_all(parent).await?;
+                    }
+                    if tokio::fs::symlink_metadata(&dest).await.is_ok() {
+                        tokio::fs::remove_file(&dest).await?;
+                    }
+                    #[cfg(unix)]
+                    tokio::fs::symlink(target, &dest).await?;
+                    #[cfg(not(unix))]
+                    warn!(
+                        "Skipping symlink {dest:?} to {target:?}, symlinks are only restored on unix"
+                    );
+                }
+            }
+        }
+
+        // children first, so that a read-only directory doesn't prevent restoring its content
+        #[cfg(unix)]
+        for (path, attributes) in self.attributes.iter().rev() {
+            use std::os::unix::fs::PermissionsExt;
+            let dest = to_dest.join(path);
+            // setting the mode follows symlinks, which could lead anywhere
+            if passes_through_symlink(to_dest, path).await? {
+                warn!("Not restoring the mode of {dest:?}, its path goes through a symlink");
+                continue;
+            }
+            let permissions = std::fs::Permissions::from_mode(attributes.mode);
+            match tokio::fs::set_permissions(&dest, permissions).await {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    warn!("Cannot restore the mode of {dest:?}, it was not downloaded");
+                    continue;
+                }
+                Err(err) => return Err(err),
+            }
+            if attributes.xattrs.is_some() {
+                debug!("Extended attributes of {dest:?} are not restored");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Rejects archive paths that are not a plain relative path, such as absolute paths or paths with `..`.
+fn check_relative_path(path: &Path) -> std::io::Result<()> {
+    if path.as_os_str().is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Archive entry {path:?} is not a relative path"),
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects symlink targets that are absolute or lead outside of the directory the archive is restored to.
+///
+/// Both the directory holding the symlink and its target are resolved through the other `symlinks`
+/// of the archive, so that chained symlinks cannot escape either.
+fn check_symlink_target(
+    path: &Path,
+    target: &Path,
+    symlinks: &BTreeMap<&Path, &Path>,
+) -> std::io::Result<()> {
+    let escapes = || {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Symlink {path:?} points outside of the archive: {target:?}"),
+        )
+    };
+    // the target is relative to the directory holding the symlink
+    let mut pending: VecDeque<Component> = path
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .collect();
+    pending.extend(target.components());
+
+    let mut resolved: Vec<OsString> = vec![];
+    let mut followed = 0;
+    while let Some(component) = pending.pop_front() {
+        match component {
+            Component::Normal(name) => {
+                resolved.push(name.to_os_string());
+                let current: PathBuf = resolved.iter().collect();
+                if let Some(link_target) = symlinks.get(current.as_path()) {
+                    followed += 1;
+                    if followed > MAX_SYMLINKS_FOLLOWED {
+                        return Err(escapes());
+                    }
+                    // continue from the directory holding that symlink
+                    resolved.pop();
+                    for link_component in link_target.components().rev() {
+                        pending.push_front(link_component);
+                    }
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop().ok_or_else(escapes)?;
+            }
+            Component::RootDir | Component::Prefix(_) => return Err(escapes()),
+        }
+    }
+    Ok(())
+}
+
+/// Whether `path`, or one of its parents, is a symlink under `to_dest`, without following links.
+#[cfg(unix)]
+async fn passes_through_symlink(to_dest: &Path, path: &Path) -> std::io::Result<bool> {
+    let mut current = to_dest.to_path_buf();
+    for component in path.components() {
+        current.push(component);
+        match tokio::fs::symlink_metadata(&current).await {
+            Ok(metadata) if metadata.file_type().is_symlink() => return Ok(true),
+            Ok(_) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[tokio::test]
+    async fn test_capture_and_restore() {
+        let src = tempfile::tempdir().unwrap();
+        let root = src.path().join("tree");
+        std::fs::create_dir_all(root.join("empty")).unwrap();
+        std::fs::write(root.join("script.sh"), b"#!/bin/sh").unwrap();
+        std::fs::set_permissions(
+            root.join("script.sh"),
+            std::fs::Permissions::from_mode(0o750),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink("script.sh", root.join("link")).unwrap();
+
+        let entries = PosixEntries::from_dir(&root).unwrap();
+        assert_eq!(
+            entries.special.get(Path::new("tree/empty")).map(|e| &e.0),
+            Some(&SpecialEntry::Directory)
+        );
+        assert_eq!(
+            entries.special.get(Path::new("tree/link")).map(|e| &e.0),
+            Some(&SpecialEntry::Symlink(PathBuf::from("script.sh")))
+        );
+        assert_eq!(
+            entries
+                .attributes
+                .get(Path::new("tree/script.sh"))
+                .map(|a| a.mode),
+            Some(0o750)
+        );
+        assert!(!entries.attributes.contains_key(Path::new("tree/link")));
+
+        // restore next to the downloaded file
+        let dest = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dest.path().join("tree")).unwrap();
+        std::fs::write(dest.path().join("tree/script.sh"), b"#!/bin/sh").unwrap();
+        entries.restore(dest.path()).await.unwrap();
+
+        assert!(dest.path().join("tree/empty").is_dir());
+        assert_eq!(
+            std::fs::read_link(dest.path().join("tree/link")).unwrap(),
+            PathBuf::from("script.sh")
+        );
+        let mode = std::fs::metadata(dest.path().join("tree/script.sh"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o7777, 0o750);
+    }
+
+    #[test]
+    fn test_plain_dir_has_no_posix_entries() {
+        let src = tempfile::tempdir().unwrap();
+        let root = src.path().join("tree");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::set_permissions(&root, std::fs::Permissions::from_mode(DEFAULT_DIR_MODE)).unwrap();
+        std::fs::set_permissions(
+            root.join("sub"),
+            std::fs::Permissions::from_mode(DEFAULT_DIR_MODE),
+        )
+        .unwrap();
+        std::fs::write(root.join("sub/file"), b"data").unwrap();
+        std::fs::set_permissions(
+            root.join("sub/file"),
+            std::fs::Permissions::from_mode(DEFAULT_FILE_MODE),
+        )
+        .unwrap();
+
+        assert!(PosixEntries::from_dir(&root).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_paths_outside_dest() {
+        let dest = tempfile::tempdir().unwrap();
+        let meta = Metadata::empty();
+
+        let mut entries = PosixEntries::default();
+        entries.add_directory(PathBuf::from("../escaped"), meta.clone());
+        assert!(entries.restore(dest.path()).await.is_err());
+
+        let mut entries = PosixEntries::default();
+        entries.set_attributes(
+            PathBuf::from("/etc/passwd"),
+            PosixAttributes {
+                mode: 0o777,
+                xattrs: None,
+            },
+        );
+        assert!(entries.restore(dest.path()).await.is_err());
+
+        for target in ["/etc/passwd", "../../outside", "a/../../../outside"] {
+            let mut entries = PosixEntries::default();
+            entries.add_symlink(
+                PathBuf::from("tree/link"),
+                PathBuf::from(target),
+                meta.clone(),
+            );
+            assert!(entries.restore(dest.path()).await.is_err(), "{target}");
+        }
+        assert!(!dest.path().join("tree/link").exists());
+
+        // each symlink stays inside alone, but not once chained
+        let mut entries = PosixEntries::default();
+        entries.add_symlink(PathBuf::from("x/b"), PathBuf::from(".."), meta.clone());
+        entries.add_symlink(PathBuf::from("a"), PathBuf::from("x/b/.."), meta.clone());
+        entries.set_attributes(
+            PathBuf::from("a/victim"),
+            PosixAttributes {
+                mode: 0o777,
+                xattrs: None,
+            },
+        );
+        assert!(entries.restore(dest.path()).await.is_err());
+        assert!(!dest.path().join("a").exists());
+
+        // the directory holding a symlink is resolved too
+        let mut entries = PosixEntries::default();
+        entries.add_symlink(PathBuf::from("x/b"), PathBuf::from(".."), meta.clone());
+        entries.add_symlink(PathBuf::from("x/b/c"), PathBuf::from(".."), meta.clone());
+        assert!(entries.restore(dest.path()).await.is_err());
+
+        let mut entries = PosixEntries::default();
+        entries.add_symlink(PathBuf::from("loop"), PathBuf::from("loop/x"), meta.clone());
+        assert!(entries.restore(dest.path()).await.is_err());
+
+        // modes are not restored through a symlink staying inside
+        let mut entries = PosixEntries::default();
+        entries.add_symlink(PathBuf::from("x/b"), PathBuf::from(".."), meta.clone());
+        entries.set_attributes(
+            PathBuf::from("x/b/file"),
+            PosixAttributes {
+                mode: 0o777,
+                xattrs: None,
+            },
+        );
+        std::fs::write(dest.path().join("file"), b"data").unwrap();
+        std::fs::set_permissions(
+            dest.path().join("file"),
+            std::fs::Permissions::from_mode(DEFAULT_FILE_MODE),
+        )
+        .unwrap();
+        entries.restore(dest.path()).await.unwrap();
+        let mode = std::fs::metadata(dest.path().join("file"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o7777, DEFAULT_FILE_MODE);
+
+        let mut entries = PosixEntries::default();
+        entries.add_symlink(
+            PathBuf::from("tree/link"),
+            PathBuf::from("../tree/file"),
+            meta,
+        );
+        entries.restore(dest.path()).await.unwrap();
+        assert_eq!(
+            std::fs::read_link(dest.path().join("tree/link")).unwrap(),
+            PathBuf::from("../tree/file")
+        );
+    }
+}
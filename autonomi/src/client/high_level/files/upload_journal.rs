@@ -0,0 +1,773 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::archive_private::{PrivateArchive, PrivateArchiveDataMap};
+use super::fs_public::metadata_from_entry;
+use super::posix::PosixEntries;
+use super::{UploadError, get_relative_file_path_from_abs_file_and_folder_path};
+use crate::client::config::UPLOAD_FLOW_BATCH_SIZE;
+use crate::client::data_types::chunk::{ChunkAddress, DataMapChunk};
+use crate::client::payment::{PaymentOption, Receipt};
+use crate::client::put_error_state::interrupted_upload;
+use crate::client::{OperationKind, PutError};
+use crate::self_encryption::{EncryptionStream, encrypt_file};
+use crate::{AttoTokens, Client};
+use ant_protocol::storage::{Chunk, DataTypes};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use xor_name::XorName;
+
+/// Errors that can occur while reading or writing an [`UploadJournal`].
+#[derive(Debug, thiserror::Error)]
+pub enum UploadJournalError {
+    #[error("IO failure on upload journal: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to serialize upload journal: {0}")]
+    Serialization(#[from] rmp_serde::encode::Error),
+    #[error("Failed to deserialize upload journal: {0}")]
+    Deserialization(#[from] rmp_serde::decode::Error),
+}
+
+/// What kind of upload an [`UploadJournal`] tracks.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum UploadJournalKind {
+    /// A single private file, see [`Client::file_content_upload`].
+    File,
+    /// A private directory and its archive, see [`Client::dir_upload`].
+    Directory,
+}
+
+/// Progress of a single file within an [`UploadJournal`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct JournaledFile {
+    /// Size of the source file when its upload started
+    pub size: u64,
+    /// Last modification time of the source file when its upload started
+    pub modified: u64,
+    /// Number of chunks of the encryption stream stored so far, in the order they are produced
+    pub chunks_processed: usize,
+    /// The datamap of the file, as soon as the encryption produced it
+    pub data_map: Option<DataMapChunk>,
+    /// Whether all the chunks of the file are stored
+    pub completed: bool,
+}
+
+/// On-disk checkpoint of a private file or directory upload.
+///
+/// The journal records the encryption progress of each file, every payment made and every chunk
+/// confirmed to be stored, so an interrupted upload can be continued with [`Client::resume_upload`]
+/// without paying twice for the same chunk. As chunks are content addressed, a chunk recorded as stored
+/// or paid for stays valid even if the source file changes in between.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UploadJournal {
+    kind: UploadJournalKind,
+    source: PathBuf,
+    files: BTreeMap<PathBuf, JournaledFile>,
+    receipt: Receipt,
+    stored: HashSet<XorName>,
+    result: Option<DataMapChunk>,
+}
+
+/// This type wraps the journal in a version marker, the same way archives are.
+#[derive(Serialize, Deserialize)]
+#[non_exhaustive]
+enum UploadJournalVersioned {
+    V0(UploadJournal),
+}
+
+impl UploadJournal {
+    /// Create a new empty journal for the upload of `source`.
+    pub fn new(kind: UploadJournalKind, source: PathBuf) -> Self {
+        Self {
+            kind,
+            source,
+            files: BTreeMap::new(),
+            receipt: Receipt::new(),
+            stored: HashSet::new(),
+            result: None,
+        }
+    }
+
+    /// Load a journal from disk.
+    pub fn load(path: &Path) -> Result<Self, UploadJournalError> {
+        let bytes = std::fs::read(path)?;
+        let versioned: UploadJournalVersioned = rmp_serde::from_slice(&bytes)?;
+        let UploadJournalVersioned::V0(journal) = versioned;
+        Ok(journal)
+    }
+
+    /// Write the journal to disk.
+    ///
+    /// The journal is written to a temporary file first and then renamed over the previous one,
+    /// so a crash in the middle of a write never leaves a corrupted journal behind.
+    pub fn save(&self, path: &Path) -> Result<(), UploadJournalError> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let bytes = rmp_serde::to_vec_named(&UploadJournalVersioned::V0(self.clone()))?;
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, bytes)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// The kind of upload tracked by this journal.
+    pub fn kind(&self) -> UploadJournalKind {
+        self.kind
+    }
+
+    /// The file or directory being uploaded.
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// Progress of each file, keyed by its path relative to the uploaded directory.
+    pub fn files(&self) -> &BTreeMap<PathBuf, JournaledFile> {
+        &self.files
+    }
+
+    /// All the payments made so far.
+    pub fn receipt(&self) -> &Receipt {
+        &self.receipt
+    }
+
+    /// Number of chunks confirmed to be stored on the network.
+    pub fn stored_chunks(&self) -> usize {
+        self.stored.len()
+    }
+
+    /// Returns the resulting datamap if the upload completed.
+    pub fn result(&self) -> Option<&DataMapChunk> {
+        self.result.as_ref()
+    }
+
+    /// Returns the datamap of a file if it was fully uploaded and the source did not change since.
+    fn completed_file(
+        &self,
+        relative_path: &Path,
+        size: u64,
+        modified: u64,
+    ) -> Option<DataMapChunk> {
+        let file = self.files.get(relative_path)?;
+        if file.completed && file.size == size && file.modified == modified {
+            file.data_map.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the progress of a file to continue from, or a new one if the source changed since.
+    fn file_progress(&self, relative_path: &Path, size: u64, modified: u64) -> JournaledFile {
+        match self.files.get(relative_path) {
+            Some(file) if file.size == size && file.modified == modified => file.clone(),
+            _ => JournaledFile {
+                size,
+                modified,
+                chunks_processed: 0,
+                data_map: None,
+                completed: false,
+            },
+        }
+    }
+
+    /// Returns the chunks of a batch that still need to be paid for.
+    fn unpaid<'a>(&self, batch: &'a [Chunk]) -> Vec<&'a Chunk> {
+        batch
+            .iter()
+            .filter(|chunk| !self.receipt.contains_key(chunk.name()))
+            .collect()
+    }
+}
+
+impl Client {
+    /// Same as [`Client::file_content_upload`] but checkpoints the upload progress in a journal at `journal_path`.
+    ///
+    /// If a journal for the same file already exists at that path, the upload continues from it.
+    /// On failure the journal is left on disk and the upload can be continued with [`Client::resume_upload`].
+    pub async fn file_content_upload_with_journal(
+        &self,
+        path: PathBuf,
+        payment_option: PaymentOption,
+        journal_path: &Path,
+    ) -> Result<(AttoTokens, DataMapChunk), UploadError> {
+        let journal = self.open_journal(UploadJournalKind::File, path, journal_path)?;
+        self.journaled_upload(journal, journal_path, payment_option)
+            .await
+    }
+
+    /// Same as [`Client::dir_upload`] but checkpoints the upload progress in a journal at `journal_path`.
+    ///
+    /// If a journal for the same directory already exists at that path, the upload continues from it.
+    /// On failure the journal is left on disk and the upload can be continued with [`Client::resume_upload`].
+    pub async fn dir_upload_with_journal(
+        &self,
+        dir_path: PathBuf,
+        payment_option: PaymentOption,
+        journal_path: &Path,
+    ) -> Result<(AttoTokens, PrivateArchiveDataMap), UploadError> {
+        let journal = self.open_journal(UploadJournalKind::Directory, dir_path, journal_path)?;
+        self.journaled_upload(journal, journal_path, payment_option)
+            .await
+    }
+
+    /// Resume an upload from its journal.
+    ///
+    /// The files are encrypted again, but the chunks already stored are skipped and the chunks already paid
+    /// for are uploaded with their recorded payment, so only the remaining chunks are paid for with the given
+    /// `payment_option`.
+    /// Returns the [`DataMapChunk`] of the file, or the [`PrivateArchiveDataMap`] for a directory upload.
+    pub async fn resume_upload(
+        &self,
+        journal_path: &Path,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, DataMapChunk), UploadError> {
+        let journal = UploadJournal::load(journal_path)?;
+        info!(
+            "Resuming upload of {:?} from journal {journal_path:?}, {} chunks already stored",
+            journal.source,
+            journal.stored.len()
+        );
+        #[cfg(feature = "loud")]
+        println!(
+            "Resuming upload of {:?}, {} chunks already stored",
+            journal.source,
+            journal.stored.len()
+        );
+        self.journaled_upload(journal, journal_path, payment_option)
+            .await
+    }
+
+    fn open_journal(
+        &self,
+        kind: UploadJournalKind,
+        source: PathBuf,
+        journal_path: &Path,
+    ) -> Result<UploadJournal, UploadError> {
+        if journal_path.exists() {
+            let journal = UploadJournal::load(journal_path)?;
+            if journal.kind == kind && journal.source == source {
+                info!("Continuing upload of {source:?} from existing journal {journal_path:?}");
+                return Ok(journal);
+            }
+            warn!("Journal at {journal_path:?} belongs to another upload, starting a new one");
+        }
+        let journal = UploadJournal::new(kind, source);
+        journal.save(journal_path)?;
+        Ok(journal)
+    }
+
+    async fn journaled_upload(
+        &self,
+        mut journal: UploadJournal,
+        journal_path: &Path,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, DataMapChunk), UploadError> {
+        self.in_operation(OperationKind::Upload, |client| async move {
+            if let Some(result) = journal.result.clone() {
+                info!("Upload of {:?} already completed", journal.source);
+                return Ok((AttoTokens::zero(), result));
+            }
+
+            let mut receipts = vec![];
+            let mut total_chunks = 0;
+            let mut free_chunks = 0;
+
+            let source = journal.source.clone();
+            let entries: Vec<walkdir::DirEntry> = walkdir::WalkDir::new(&source)
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .filter(|entry| entry.file_type().is_file())
+                .collect();
+
+            let mut archive = PrivateArchive::new();
+            for entry in entries {
+                let file_path = entry.path().to_path_buf();
+                let relative_path =
+                    get_relative_file_path_from_abs_file_and_folder_path(&file_path, &source);
+                let metadata = metadata_from_entry(&entry);
+
+                if let Some(data_map) =
+                    journal.completed_file(&relative_path, metadata.size, metadata.modified)
+                {
+                    debug!("File {file_path:?} was already uploaded according to the journal");
+                    archive.add_file(relative_path, data_map, metadata);
+                    continue;
+                }
+
+                let mut progress =
+                    journal.file_progress(&relative_path, metadata.size, metadata.modified);
+
+                let mut stream = encrypt_file(
+                    relative_path.clone(),
+                    file_path.clone(),
+                    metadata.size as usize,
+                    metadata.clone(),
+                    false,
+                )
+                .await
+                .map_err(UploadError::Encryption)?;
+
+                // small files are encrypted in memory, their datamap is known straight away
+                if let Some(data_map) = stream.data_map_chunk() {
+                    if progress
+                        .data_map
+                        .as_ref()
+                        .is_some_and(|known| *known != data_map)
+                    {
+                        warn!("File {file_path:?} changed since its upload started, starting over");
+                        progress.chunks_processed = 0;
+                    }
+                    progress.data_map = Some(data_map);
+                }
+                journal.files.insert(relative_path.clone(), progress);
+                journal.save(journal_path)?;
+
+                let (processed, free, receipt) = client
+                    .journaled_upload_stream(
+                        &mut stream,
+                        &relative_path,
+                        &mut journal,
+                        journal_path,
+                        payment_option.clone(),
+                    )
+                    .await?;
+                total_chunks += processed;
+                free_chunks += free;
+                receipts.extend(receipt);
+
+                let data_map = stream.data_map_chunk().ok_or_else(|| {
+                    error!("Datamap chunk not found for file: {file_path:?}, this is a BUG");
+                    UploadError::Encryption(
+                        "Data map chunk not found after encryption, this is a BUG".to_string(),
+                    )
+                })?;
+                if let Some(file) = journal.files.get_mut(&relative_path) {
+                    file.data_map = Some(data_map.clone());
+                    file.completed = true;
+                }
+                journal.save(journal_path)?;
+                archive.add_file(relative_path, data_map, metadata);
+            }
+
+            if matches!(journal.kind, UploadJournalKind::Directory) {
+                *archive.posix_mut() = PosixEntries::from_dir(&source)?;
+            }
+
+            let (archive_cost, result) = match journal.kind {
+                UploadJournalKind::File => match archive.data_maps().into_iter().next() {
+                    Some(data_map) => (AttoTokens::zero(), data_map),
+                    None => {
+                        return Err(UploadError::IoError(std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            format!("No file to upload at {source:?}"),
+                        )));
+                    }
+                },
+                UploadJournalKind::Directory => {
+                    client.archive_put(&archive, payment_option).await?
+                }
+            };
+
+            journal.result = Some(result.clone());
+            journal.save(journal_path)?;
+
+            let files_cost = client
+                .calculate_total_cost(total_chunks, receipts, free_chunks)
+                .await;
+            let total_cost = files_cost.checked_add(archive_cost).unwrap_or_else(|| {
+                error!("Total cost overflowed: {files_cost:?} + {archive_cost:?}");
+                files_cost
+            });
+
+            debug!("Journaled upload of {source:?} completed");
+            Ok((total_cost, result))
+        })
+        .await
+    }
+
+    /// Upload the chunks of an encryption stream, checkpointing the encryption progress, payments and stored
+    /// chunks in the journal. The chunks the journal records as processed for this file are skipped.
+    /// Returns: (processed_chunks, free_chunks, receipts paid during this run)
+    async fn journaled_upload_stream(
+        &self,
+        stream: &mut EncryptionStream,
+        relative_path: &Path,
+        journal: &mut UploadJournal,
+        journal_path: &Path,
+        payment_option: PaymentOption,
+    ) -> Result<(usize, usize, Vec<Receipt>), UploadError> {
+        let est_total_todo = stream.total_chunks();
+        let mut processed_chunks = 0;
+        let mut free_chunks = 0;
+        let mut receipts = vec![];
+        let already_processed = journal
+            .files
+            .get(relative_path)
+            .map(|file| file.chunks_processed)
+            .unwrap_or_default();
+
+        // Allow up to `retry_failed` * est_total_chunks total uploads to be attempted
+        let mut attempted_uploads = 0;
+        let allowed_attempts =
+            est_total_todo + std::cmp::max(20, est_total_todo * self.retry_failed as usize);
+
+        while let Some(batch) = stream.next_batch(*UPLOAD_FLOW_BATCH_SIZE) {
+            let batch_start = processed_chunks;
+            processed_chunks += batch.len();
+
+            let mut todo: Vec<Chunk> = batch
+                .into_iter()
+                .enumerate()
+                .filter(|(i, chunk)| {
+                    batch_start + i >= already_processed && !journal.stored.contains(chunk.name())
+                })
+                .map(|(_, chunk)| chunk)
+                .collect();
+
+            loop {
+                if todo.is_empty() {
+                    break;
+                }
+                // stop before paying for more chunks, the journal allows to resume later on
+                if let Err(reason) = self.operation_options.check() {
+                    journal.save(journal_path)?;
+                    info!(
+                        "Upload of {relative_path:?} interrupted, progress saved to journal {journal_path:?}"
+                    );
+                    let stored = journal
+                        .stored
+                        .iter()
+                        .map(|name| ChunkAddress::new(*name))
+                        .collect();
+                    let pending = todo.iter().map(|chunk| *chunk.address());
+                    return Err(interrupted_upload(
+                        reason,
+                        stored,
+                        pending,
+                        vec![journal.receipt.clone()],
+                    )
+                    .into());
+                }
+                attempted_uploads += todo.len();
+
+                // pay for the chunks not paid for yet, and checkpoint the payment straight away
+                let unpaid: Vec<(XorName, usize)> = journal
+                    .unpaid(&todo)
+                    .iter()
+                    .map(|chunk| (*chunk.name(), chunk.size()))
+                    .collect();
+                if !unpaid.is_empty() {
+                    let (receipt, already_paid) = self
+                        .pay_for_content_addrs(
+                            DataTypes::Chunk,
+                            unpaid.into_iter(),
+                            payment_option.clone(),
+                        )
+                        .await
+                        .map_err(PutError::from)?;
+                    free_chunks += already_paid;
+                    journal.receipt.extend(receipt.clone());
+                    journal.save(journal_path)?;
+                    receipts.push(receipt);
+                }
+
+                // chunks without payment already exist on the network and are skipped by the batch upload
+                let result = self
+                    .chunk_batch_upload(todo.iter().collect(), &journal.receipt)
+                    .await;
+                match result {
+                    Ok(()) => {
+                        journal
+                            .stored
+                            .extend(todo.iter().map(|chunk| *chunk.name()));
+                        todo.clear();
+                    }
+                    Err(PutError::Batch(state)) => {
+                        let failed: HashSet<XorName> = state
+                            .failed
+                            .iter()
+                            .map(|(addr, _)| *addr.xorname())
+                            .collect();
+                        journal.stored.extend(
+                            todo.iter()
+                                .map(|chunk| *chunk.name())
+                                .filter(|name| !failed.contains(name)),
+                        );
+                        journal.save(journal_path)?;
+
+                        if attempted_uploads > allowed_attempts {
+                            error!(
+                                "Upload of {relative_path:?} failed, progress saved to journal {journal_path:?}"
+                            );
+                            return Err(PutError::Batch(state).into());
+                        }
+
+                        todo.retain(|chunk| failed.contains(chunk.name()));
+                        #[cfg(feature = "loud")]
+                        println!(
+                            "⚠️ Encountered upload failure, take 1 minute pause before continue..."
+                        );
+                        info!("Encountered upload failure, take 1 minute pause before continue...");
+                        self.operation_options.sleep(Duration::from_secs(60)).await;
+                    }
+                    Err(err) => {
+                        journal.save(journal_path)?;
+                        return Err(err.into());
+                    }
+                }
+            }
+
+            if let Some(file) = journal.files.get_mut(relative_path) {
+                file.chunks_processed = file.chunks_processed.max(processed_chunks);
+            }
+            journal.save(journal_path)?;
+        }
+
+        Ok((processed_chunks, free_chunks, receipts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::OperationOptions;
+    use crate::client::config::ClientOperatingStrategy;
+    use crate::client::files::Metadata;
+    use crate::test_utils::offline_client;
+    use ant_evm::ClientProofOfPayment;
+
+    /// Write a small file and encrypt it the way the journaled upload does.
+    /// Returns the file path, its path relative to itself, its datamap and its chunks.
+    async fn encrypted_file(dir: &Path) -> (PathBuf, PathBuf, DataMapChunk, Vec<Chunk>) {
+        let file_path = dir.join("file.bin");
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&file_path, data).unwrap();
+
+        let relative_path =
+            get_relative_file_path_from_abs_file_and_folder_path(&file_path, &file_path);
+        let mut stream = encrypted_stream(&file_path, &relative_path).await;
+        let data_map = stream.data_map_chunk().unwrap();
+        let chunks = stream.next_batch(usize::MAX).unwrap();
+        (file_path, relative_path, data_map, chunks)
+    }
+
+    async fn encrypted_stream(file_path: &Path, relative_path: &Path) -> EncryptionStream {
+        let metadata = file_metadata(file_path);
+        encrypt_file(
+            relative_path.to_path_buf(),
+            file_path.to_path_buf(),
+            metadata.size as usize,
+            metadata,
+            false,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn file_metadata(file_path: &Path) -> Metadata {
+        let entry = walkdir::WalkDir::new(file_path)
+            .into_iter()
+            .next()
+            .unwrap()
+            .unwrap();
+        metadata_from_entry(&entry)
+    }
+
+    /// A payment which is never expected to be used, as everything was already paid for.
+    fn unexpected_payment() -> (XorName, PaymentOption) {
+        let name = XorName([9; 32]);
+        let proof = ClientProofOfPayment {
+            peer_quotes: vec![],
+        };
+        let receipt = Receipt::from([(name, (proof, AttoTokens::from_u64(1)))]);
+        (name, PaymentOption::Receipt(receipt))
+    }
+
+    async fn test_client() -> Client {
+        offline_client(ClientOperatingStrategy {
+            chunk_cache_enabled: false,
+            ..Default::default()
+        })
+        .await
+    }
+
+    #[test]
+    fn test_journal_save_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("upload.journal");
+
+        let mut journal = UploadJournal::new(UploadJournalKind::Directory, PathBuf::from("data"));
+        journal.files.insert(
+            PathBuf::from("data/file.txt"),
+            JournaledFile {
+                size: 10,
+                modified: 42,
+                chunks_processed: 3,
+                data_map: Some(DataMapChunk::from_hex("1111").unwrap()),
+                completed: true,
+            },
+        );
+        journal.stored.insert(XorName([7; 32]));
+        journal.save(&journal_path).unwrap();
+
+        let loaded = UploadJournal::load(&journal_path).unwrap();
+        assert_eq!(loaded, journal);
+        assert_eq!(loaded.stored_chunks(), 1);
+        assert!(!journal_path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_journal_completed_file_detects_changes() {
+        let mut journal = UploadJournal::new(UploadJournalKind::File, PathBuf::from("file.txt"));
+        let meta = Metadata::new_with_size(10);
+        journal.files.insert(
+            PathBuf::from("file.txt"),
+            JournaledFile {
+                size: meta.size,
+                modified: meta.modified,
+                chunks_processed: 3,
+                data_map: Some(DataMapChunk::from_hex("1111").unwrap()),
+                completed: true,
+            },
+        );
+
+        let path = PathBuf::from("file.txt");
+        assert!(
+            journal
+                .completed_file(&path, meta.size, meta.modified)
+                .is_some()
+        );
+        assert!(
+            journal
+                .completed_file(&path, meta.size + 1, meta.modified)
+                .is_none()
+        );
+        assert!(
+            journal
+                .completed_file(&path, meta.size, meta.modified + 1)
+                .is_none()
+        );
+
+        if let Some(file) = journal.files.get_mut(&path) {
+            file.completed = false;
+        }
+        assert!(
+            journal
+                .completed_file(&path, meta.size, meta.modified)
+                .is_none()
+        );
+        assert_eq!(
+            journal
+                .file_progress(&path, meta.size, meta.modified)
+                .chunks_processed,
+            3
+        );
+        assert_eq!(
+            journal
+                .file_progress(&path, meta.size + 1, meta.modified)
+                .chunks_processed,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resume_upload_skips_the_stored_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("upload.journal");
+        let (file_path, relative_path, data_map, chunks) = encrypted_file(dir.path()).await;
+        assert!(chunks.len() > 1);
+
+        // the first chunk is recorded as processed, the others as stored
+        let metadata = file_metadata(&file_path);
+        let mut journal = UploadJournal::new(UploadJournalKind::File, file_path.clone());
+        journal.files.insert(
+            relative_path.clone(),
+            JournaledFile {
+                size: metadata.size,
+                modified: metadata.modified,
+                chunks_processed: 1,
+                data_map: Some(data_map.clone()),
+                completed: false,
+            },
+        );
+        journal
+            .stored
+            .extend(chunks.iter().skip(1).map(|chunk| *chunk.name()));
+        journal.save(&journal_path).unwrap();
+
+        // nothing is left to upload, so the offline client never reaches the network
+        let (unexpected, payment) = unexpected_payment();
+        let (cost, result) = test_client()
+            .await
+            .resume_upload(&journal_path, payment)
+            .await
+            .unwrap();
+        assert_eq!(cost, AttoTokens::zero());
+        assert_eq!(result, data_map);
+
+        let journal = UploadJournal::load(&journal_path).unwrap();
+        assert_eq!(journal.result(), Some(&data_map));
+        assert!(!journal.receipt().contains_key(&unexpected));
+        let file = &journal.files()[&relative_path];
+        assert!(file.completed);
+        assert_eq!(file.chunks_processed, chunks.len());
+    }
+
+    #[tokio::test]
+    async fn test_journaled_upload_stream_does_not_pay_twice() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal_path = dir.path().join("upload.journal");
+        let (file_path, relative_path, _data_map, chunks) = encrypted_file(dir.path()).await;
+
+        // the first chunk is stored, the others are paid for but not stored yet
+        let mut journal = UploadJournal::new(UploadJournalKind::File, file_path.clone());
+        journal.stored.insert(*chunks[0].name());
+        for chunk in &chunks[1..] {
+            let proof = ClientProofOfPayment {
+                peer_quotes: vec![],
+            };
+            journal
+                .receipt
+                .insert(*chunk.name(), (proof, AttoTokens::from_u64(1)));
+        }
+        let paid = journal.receipt.clone();
+
+        // the recorded payments have no payee, so their uploads fail until the deadline
+        let (unexpected, payment) = unexpected_payment();
+        let client = test_client().await.with_operation_options(
+            OperationOptions::new().with_timeout(Duration::from_millis(500)),
+        );
+        let mut stream = encrypted_stream(&file_path, &relative_path).await;
+        let result = client
+            .journaled_upload_stream(
+                &mut stream,
+                &relative_path,
+                &mut journal,
+                &journal_path,
+                payment,
+            )
+            .await;
+
+        let Err(UploadError::PutError(PutError::Interrupted { state, .. })) = result else {
+            panic!("Expected the upload to be interrupted, got {result:?}");
+        };
+        assert_eq!(state.successful, vec![*chunks[0].address()]);
+        let pending: HashSet<_> = state.failed.iter().map(|(addr, _)| *addr).collect();
+        let expected: HashSet<_> = chunks[1..].iter().map(|chunk| *chunk.address()).collect();
+        assert_eq!(pending, expected);
+
+        assert!(!journal.receipt.contains_key(&unexpected));
+        assert_eq!(journal.receipt.len(), paid.len());
+        assert_eq!(UploadJournal::load(&journal_path).unwrap(), journal);
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::archive_public::PublicArchive;
+use super::fs_public::metadata_from_entry;
+use super::{FileCostError, get_relative_file_path_from_abs_file_and_folder_path};
+use crate::client::high_level::data::DataAddress;
+use crate::client::quote::DataTypes;
+use crate::{AttoTokens, Client};
+use bytes::Bytes;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use xor_name::XorName;
+
+/// Chunks of a single file in an [`UploadPlan`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileUploadPlan {
+    /// Number of chunks of the file, including its datamap chunk
+    pub chunks: usize,
+    /// Number of those chunks already stored on the network, or appearing earlier in the upload
+    pub deduplicated: usize,
+    /// Size in bytes of the chunks of the file left to upload
+    pub bytes_to_upload: u64,
+}
+
+/// What an upload would store on the network, computed before paying for it.
+///
+/// Chunks are content addressed: a chunk already stored on the network, or appearing several times
+/// in the upload, is only paid for and uploaded once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadPlan {
+    /// Chunks of each file, by path relative to the parent of the uploaded path
+    pub files: BTreeMap<PathBuf, FileUploadPlan>,
+    /// Number of distinct chunks of the upload, including the archive chunks
+    pub total_chunks: usize,
+    /// Number of chunks appearing more than once in the upload, counted once in `total_chunks`
+    pub duplicate_chunks: usize,
+    /// Number of distinct chunks already stored on the network
+    pub already_stored_chunks: usize,
+    /// Size in bytes of the chunks left to upload
+    pub bytes_to_upload: u64,
+    /// Estimated cost of storing the chunks left to upload
+    pub estimated_cost: AttoTokens,
+}
+
+impl UploadPlan {
+    /// Number of distinct chunks left to upload.
+    pub fn chunks_to_upload(&self) -> usize {
+        self.total_chunks - self.already_stored_chunks
+    }
+
+    /// Build a plan from the content addresses of the files and of the archive,
+    /// given the addresses the network quoted for, i.e. the ones not stored yet.
+    fn new(
+        files: Vec<(PathBuf, Vec<(XorName, usize)>)>,
+        archive: Vec<(XorName, usize)>,
+        to_store: &HashSet<XorName>,
+        estimated_cost: AttoTokens,
+    ) -> Self {
+        let mut seen = HashSet::new();
+        let mut duplicate_chunks = 0;
+        let mut bytes_to_upload = 0;
+
+        let mut count = |addrs: &[(XorName, usize)]| {
+            let mut plan = FileUploadPlan {
+                chunks: addrs.len(),
+                ..Default::default()
+            };
+            for (addr, size) in addrs {
+                if !seen.insert(*addr) {
+                    duplicate_chunks += 1;
+                    plan.deduplicated += 1;
+                } else if to_store.contains(addr) {
+                    plan.bytes_to_upload += *size as u64;
+                } else {
+                    plan.deduplicated += 1;
+                }
+            }
+            bytes_to_upload += plan.bytes_to_upload;
+            plan
+        };
+
+        let files = files
+            .into_iter()
+            .map(|(path, addrs)| {
+                let plan = count(&addrs);
+                (path, plan)
+            })
+            .collect();
+        count(&archive);
+
+        let total_chunks = seen.len();
+        let already_stored_chunks = seen.difference(to_store).count();
+        Self {
+            files,
+            total_chunks,
+            duplicate_chunks,
+            already_stored_chunks,
+            bytes_to_upload,
+            estimated_cost,
+        }
+    }
+}
+
+impl Client {
+    /// Plan the upload of a file or directory without paying for anything.
+    ///
+    /// Files are self-encrypted locally and the network is asked for quotes for the resulting chunks:
+    /// nodes don't quote for chunks they already store, so these won't be paid for nor uploaded again.
+    /// Like [`Client::file_cost`], the plan is for a public upload, including the archive of the files.
+    pub async fn upload_plan(&self, path: &Path) -> Result<UploadPlan, FileCostError> {
+        info!("Planning the upload of: {path:?}");
+        let mut archive = PublicArchive::new();
+        let mut files = vec![];
+
+        for entry in walkdir::WalkDir::new(path) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let file_path = entry.path().to_path_buf();
+            let data = tokio::fs::read(&file_path).await?;
+            let addrs = self.get_content_addrs(Bytes::from(data))?;
+
+            // The first addr is always the chunk_map_name
+            let map_xor_name = addrs[0].0;
+            let relative_path =
+                get_relative_file_path_from_abs_file_and_folder_path(&file_path, path);
+            archive.add_file(
+                relative_path.clone(),
+                DataAddress::new(map_xor_name),
+                metadata_from_entry(&entry),
+            );
+            files.push((relative_path, addrs));
+        }
+
+        let archive_addrs = self.get_content_addrs(archive.to_bytes()?)?;
+
+        let unique_addrs: HashMap<XorName, usize> = files
+            .iter()
+            .flat_map(|(_, addrs)| addrs.iter())
+            .chain(archive_addrs.iter())
+            .copied()
+            .collect();
+        let store_quote = self
+            .get_store_quotes(DataTypes::Chunk, unique_addrs.into_iter())
+            .await?;
+        let to_store: HashSet<XorName> = store_quote.0.keys().copied().collect();
+        let estimated_cost = AttoTokens::from_atto(store_quote.price());
+
+        let plan = UploadPlan::new(files, archive_addrs, &to_store, estimated_cost);
+        debug!(
+            "Upload plan for {path:?}: {} chunks, {} already stored, {} duplicates, {} bytes to upload for {}",
+            plan.total_chunks,
+            plan.already_stored_chunks,
+            plan.duplicate_chunks,
+            plan.bytes_to_upload,
+            plan.estimated_cost
+        );
+        Ok(plan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_plan_deduplication() {
+        let [a, b, c, d, e] = [1u8, 2, 3, 4, 5].map(|n| XorName([n; 32]));
+        let files = vec![
+            (PathBuf::from("dir/one"), vec![(a, 10), (b, 100)]),
+            // shares chunk `b` with the first file
+            (PathBuf::from("dir/two"), vec![(c, 10), (b, 100), (d, 100)]),
+        ];
+        let archive = vec![(e, 20)];
+        // `d` is already stored on the network
+        let to_store = HashSet::from([a, b, c, e]);
+
+        let plan = UploadPlan::new(files, archive, &to_store, AttoTokens::zero());
+        assert_eq!(plan.total_chunks, 5);
+        assert_eq!(plan.duplicate_chunks, 1);
+        assert_eq!(plan.already_stored_chunks, 1);
+        assert_eq!(plan.chunks_to_upload(), 4);
+        assert_eq!(plan.bytes_to_upload, 10 + 100 + 10 + 20);
+
+        let two = &plan.files[Path::new("dir/two")];
+        assert_eq!(two.chunks, 3);
+        assert_eq!(two.deduplicated, 2);
+        assert_eq!(two.bytes_to_upload, 10);
+    }
+}
@@ -12,7 +12,7 @@ use crate::client::data_types::pointer::{PointerAddress, PointerError, PointerTa
 use crate::client::key_derivation::{DerivationIndex, MainPubkey, MainSecretKey};
 use crate::client::payment::PaymentOption;
 use crate::client::quote::CostError;
-use crate::client::{Client, GetError};
+use crate::client::{Client, GetError, OperationKind};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use xor_name::XorName;
@@ -178,6 +178,20 @@ impl Client {
         owner: &SecretKey,
         new_value: RegisterValue,
         payment_option: PaymentOption,
+    ) -> Result<AttoTokens, RegisterError> {
+        self.in_operation(OperationKind::RegisterUpdate, |client| async move {
+            client
+                .register_update_head(owner, new_value, payment_option)
+                .await
+        })
+        .await
+    }
+
+    async fn register_update_head(
+        &self,
+        owner: &SecretKey,
+        new_value: RegisterValue,
+        payment_option: PaymentOption,
     ) -> Result<AttoTokens, RegisterError> {
         // get the pointer of the register head
         let addr = RegisterAddress(owner.public_key());
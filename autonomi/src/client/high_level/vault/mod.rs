@@ -17,7 +17,7 @@ use crate::client::data_types::scratchpad::ScratchpadError;
 use crate::client::key_derivation::{DerivationIndex, MainSecretKey};
 use crate::client::payment::PaymentOption;
 use crate::client::quote::CostError;
-use crate::client::{Client, GetError};
+use crate::client::{Client, GetError, Interrupted, OperationKind};
 use crate::graph::GraphError;
 use crate::utils::process_tasks_with_max_concurrency;
 use ant_evm::{AttoTokens, U256};
@@ -77,6 +77,9 @@ pub enum VaultError {
     VaultNotEnoughGraphDescendants(String),
     #[error("Vault with empty content")]
     VaultWithZeroContentSize,
+    /// Writing the vault again reuses the capacity already paid for
+    #[error("Vault write interrupted: {0}")]
+    Interrupted(#[from] Interrupted),
 }
 
 impl Client {
@@ -175,6 +178,21 @@ impl Client {
         payment_option: PaymentOption,
         secret_key: &VaultSecretKey,
         content_type: VaultContentType,
+    ) -> Result<AttoTokens, VaultError> {
+        self.in_operation(OperationKind::VaultWrite, |client| async move {
+            client
+                .vault_put_contents(data, payment_option, secret_key, content_type)
+                .await
+        })
+        .await
+    }
+
+    async fn vault_put_contents(
+        &self,
+        data: Bytes,
+        payment_option: PaymentOption,
+        secret_key: &VaultSecretKey,
+        content_type: VaultContentType,
     ) -> Result<AttoTokens, VaultError> {
         if data.is_empty() {
             return Err(VaultError::VaultWithZeroContentSize);
@@ -204,6 +222,7 @@ impl Client {
         // Note: as the Scratchpad is `created on use`, hence during the `claim stage`,
         //       NUM_OF_SCRATCHPADS_PER_GRAPHENTRY to be claimed in one newly created GraphEntry.
         while scratchpad_derivations.len() < contents.len() {
+            self.operation_options.check()?;
             let (new_free_graphentry_derivation, new_scratchpad_derivations, graph_cost) = self
                 .vault_expand_capacity(
                     &main_secret_key,
@@ -227,6 +246,7 @@ impl Client {
                 let payment_option_clone = payment_option.clone();
 
                 async move {
+                    client.operation_options.check()?;
                     let target_addr = ScratchpadAddress::new(sp_secret_key.public_key().into());
                     let already_exists = self.scratchpad_check_existence(&target_addr).await?;
 
@@ -33,35 +33,54 @@ pub mod analyze;
 pub mod config;
 pub mod key_derivation;
 pub mod payment;
+pub mod pointer_cache;
 pub mod quote;
+pub mod quote_cache;
+pub mod receipt_store;
+pub mod watch;
 
 #[cfg(feature = "external-signer")]
 #[cfg_attr(docsrs, doc(cfg(feature = "external-signer")))]
 pub mod external_signer;
 
+pub mod chunk_cache;
+pub mod cost_budget;
+pub mod events;
+pub mod operation_options;
+mod payment_planner;
+mod shared_network;
+
 // private module with utility functions
-mod chunk_cache;
 mod data_map_restoration;
 mod network;
 mod put_error_state;
 
+use chunk_cache::{ChunkCache, DiskChunkCaches, chunk_cache_from_strategy};
+use cost_budget::OperationSpend;
+pub use events::{ClientEvent, OperationId, OperationKind, UploadSummary};
+pub use operation_options::{CancellationToken, Interrupted, OperationOptions};
 use payment::Receipt;
+use payment_planner::{PaymentPlanner, payment_planner_from_strategy};
+use pointer_cache::{PointerCache, pointer_cache_from_strategy};
 pub use put_error_state::ChunkBatchUploadState;
 use quote::PaymentMode;
+use quote_cache::{QuoteCache, quote_cache_from_strategy};
+pub use shared_network::SharedNetwork;
 
-use ant_bootstrap::{bootstrap::Bootstrap, contacts_fetcher::ALPHANET_CONTACTS};
+use ant_bootstrap::contacts_fetcher::ALPHANET_CONTACTS;
 pub use ant_evm::Amount;
 use ant_evm::EvmNetwork;
 use config::ClientConfig;
 use payment::PayError;
 use quote::CostError;
 use std::collections::HashSet;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
 /// Time before considering the connection timed out.
 pub const CONNECT_TIMEOUT_SECS: u64 = 10;
 
-const CLIENT_EVENT_CHANNEL_SIZE: usize = 100;
+const CLIENT_EVENT_CHANNEL_SIZE: usize = 1000;
 
 // Amount of peers to confirm into our routing table before we consider the client ready.
 use crate::client::config::ClientOperatingStrategy;
@@ -98,6 +117,22 @@ pub struct Client {
     retry_failed: u64,
     /// Payment mode to use for uploads
     payment_mode: PaymentMode,
+    /// Cache of downloaded chunks, built from the [`ClientOperatingStrategy`].
+    chunk_cache: Option<Arc<dyn ChunkCache>>,
+    /// The disk chunk caches shared with the other clients of the network, by directory.
+    disk_chunk_caches: DiskChunkCaches,
+    /// The operation this client is running, tagging the events it sends.
+    operation: Option<OperationId>,
+    /// Cancellation and deadline of the operations, see [`Client::with_operation_options`].
+    operation_options: OperationOptions,
+    /// What the operation this client is running paid so far, see [`cost_budget::CostBudget`].
+    operation_spend: Option<Arc<OperationSpend>>,
+    /// Quotes reused until they expire, built from the [`ClientOperatingStrategy`].
+    quote_cache: Option<Arc<QuoteCache>>,
+    /// Merges the payments of concurrent uploads, built from the [`ClientOperatingStrategy`].
+    payment_planner: Option<Arc<PaymentPlanner>>,
+    /// Targets of the pointers followed when resolving chains, built from the [`ClientOperatingStrategy`].
+    pointer_cache: Option<Arc<PointerCache>>,
 }
 
 /// Error returned by [`Client::init`].
@@ -148,6 +183,12 @@ pub enum PutError {
     },
     #[error("Batch upload: {0}")]
     Batch(ChunkBatchUploadState),
+    /// The upload was interrupted, `state` holding the chunks stored and the payments made so far
+    #[error("Upload interrupted: {reason}")]
+    Interrupted {
+        reason: Interrupted,
+        state: ChunkBatchUploadState,
+    },
 }
 
 /// Errors that can occur during the get operation.
@@ -178,6 +219,8 @@ pub enum GetError {
         "DataMap points to a file too large to be handled in memory, you can increase the MAX_IN_MEMORY_DOWNLOAD_SIZE env var or use streaming to avoid this error."
     )]
     TooLargeForMemory,
+    #[error("Download interrupted: {0}")]
+    Interrupted(#[from] Interrupted),
 }
 
 impl Client {
@@ -266,65 +309,17 @@ impl Client {
     /// # }
     /// ```
     pub async fn init_with_config(config: ClientConfig) -> Result<Self, ConnectError> {
-        if let Some(network_id) = config.network_id {
-            ant_protocol::version::set_network_id(network_id);
-        }
-
-        let bootstrap = Bootstrap::new(config.bootstrap_config.clone()).await?;
-        let network = Network::new(bootstrap)?;
-
-        // Wait for the network to be ready with enough peers
-        let connectivity_result = network.wait_for_connectivity().await;
-
-        // If the connection failed and we were using the bootstrap cache,
-        // retry once with the cache disabled to fall back to mainnet contacts
-        if connectivity_result.is_err() && !config.bootstrap_config.disable_cache_reading {
-            warn!(
-                "Initial connection failed with bootstrap cache enabled. Retrying with cache disabled to use mainnet contacts..."
-            );
-
-            // Create a new config with cache reading disabled
-            let retry_config = BootstrapConfig {
-                disable_cache_reading: true,
-                ..config.bootstrap_config.clone()
-            };
-
-            // Retry the bootstrap and connection with cache disabled
-            let bootstrap_retry = Bootstrap::new(retry_config).await?;
-            let network_retry = Network::new(bootstrap_retry)?;
-
-            // Wait for connectivity with the new bootstrap configuration
-            network_retry.wait_for_connectivity().await?;
-
-            info!(
-                "Successfully connected to the network using mainnet contacts after cache failure"
-            );
-
-            return Ok(Self {
-                network: network_retry,
-                client_event_sender: None,
-                evm_network: config.evm_network,
-                config: config.strategy,
-                retry_failed: 0,
-                payment_mode: PaymentMode::Standard,
-            });
-        }
-
-        // If the first attempt succeeded or cache was already disabled, return normally
-        connectivity_result?;
-
-        Ok(Self {
-            network,
-            client_event_sender: None,
-            evm_network: config.evm_network,
-            config: config.strategy,
-            retry_failed: 0,
-            payment_mode: PaymentMode::default(),
-        })
+        let shared = SharedNetwork::connect(config.bootstrap_config, config.network_id).await?;
+        Ok(shared.client(config.evm_network, config.strategy))
     }
 
     /// Set the `ClientOperatingStrategy` for the client.
     pub fn with_strategy(mut self, strategy: ClientOperatingStrategy) -> Self {
+        self.chunk_cache = chunk_cache_from_strategy(&strategy, &self.disk_chunk_caches);
+        self.quote_cache = quote_cache_from_strategy(&strategy);
+        self.payment_planner = payment_planner_from_strategy(&strategy);
+        self.pointer_cache = pointer_cache_from_strategy(&strategy);
+        self.network = strategy.apply_retry_policy(self.network);
         self.config = strategy;
         self
     }
@@ -357,26 +352,10 @@ impl Client {
     }
 }
 
-/// Events that can be sent by the client.
-#[derive(Debug, Clone)]
-pub enum ClientEvent {
-    UploadComplete(UploadSummary),
-}
-
-/// Summary of an upload operation.
-#[derive(Debug, Clone)]
-pub struct UploadSummary {
-    /// Records that were uploaded to the network
-    pub records_paid: usize,
-    /// Records that were already paid for so were not re-uploaded
-    pub records_already_paid: usize,
-    /// Total cost of the upload
-    pub tokens_spent: Amount,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ant_bootstrap::bootstrap::Bootstrap;
     use ant_logging::LogBuilder;
 
     #[tokio::test]
@@ -7,11 +7,12 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::Client;
-use crate::networking::NetworkError;
 use crate::networking::version::PackageVersion;
+use crate::networking::{NetworkError, PeerIssue, PeerReputation};
 use ant_protocol::NetworkAddress;
 use libp2p::PeerId;
 use libp2p::kad::{PeerInfo, Quorum, Record};
+use std::collections::HashMap;
 
 impl Client {
     /// Retrieve the closest peers to the given network address.
@@ -61,4 +62,21 @@ impl Client {
     pub async fn get_node_version(&self, peer: PeerInfo) -> Result<PackageVersion, String> {
         self.network.get_node_version(peer).await
     }
+
+    /// The reputation of the peers that misbehaved towards this client: invalid quotes, timeouts,
+    /// refused stores or invalid records. It persists across restarts in the bootstrap cache directory,
+    /// and peers with a poor reputation are avoided when requesting quotes and storing records.
+    pub fn peer_reputations(&self) -> HashMap<PeerId, PeerReputation> {
+        self.network.peer_reputations()
+    }
+
+    /// The reputation of a peer, if it ever misbehaved towards this client.
+    pub fn peer_reputation(&self, peer: &PeerId) -> Option<PeerReputation> {
+        self.network.peer_reputation(peer)
+    }
+
+    /// Lower the reputation of a peer, e.g. after it handed out a record failing application level checks.
+    pub fn report_peer(&self, peer: PeerId, issue: PeerIssue) {
+        self.network.report_peer(peer, issue)
+    }
 }
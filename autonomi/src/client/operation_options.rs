@@ -0,0 +1,157 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::Client;
+use std::time::Duration;
+use tokio::time::Instant;
+
+pub use tokio_util::sync::CancellationToken;
+
+/// Why an operation was interrupted, see [`OperationOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum Interrupted {
+    #[error("Operation was cancelled")]
+    Cancelled,
+    #[error("Operation deadline was exceeded")]
+    DeadlineExceeded,
+}
+
+/// Options bounding the operations of a client, set with [`Client::with_operation_options`].
+///
+/// Once cancelled or past the deadline, operations stop scheduling new chunks and return an
+/// [`Interrupted`] error. Payments already submitted are awaited, and the partial state returned
+/// with the error holds their receipts so nothing is paid twice:
+/// - uploads return [`crate::client::PutError::Interrupted`], journaled uploads can be continued
+///   with [`Client::resume_upload`]
+/// - downloads return [`crate::client::GetError::Interrupted`], the chunks fetched so far are kept
+///   in the chunk cache for the next attempt
+/// - vault writes return [`crate::client::vault::VaultError::Interrupted`], writing the vault again
+///   reuses the capacity already paid for
+#[derive(Debug, Clone, Default)]
+pub struct OperationOptions {
+    /// Token to cancel the operations with
+    pub cancellation: Option<CancellationToken>,
+    /// Point in time after which operations are interrupted
+    pub deadline: Option<Instant>,
+}
+
+impl OperationOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interrupt the operations when the token is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Interrupt the operations after the given point in time.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Interrupt the operations once the given duration elapsed, starting now.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Returns an error if the operations are to be interrupted.
+    pub fn check(&self) -> Result<(), Interrupted> {
+        if let Some(token) = &self.cancellation
+            && token.is_cancelled()
+        {
+            return Err(Interrupted::Cancelled);
+        }
+        if let Some(deadline) = self.deadline
+            && Instant::now() >= deadline
+        {
+            return Err(Interrupted::DeadlineExceeded);
+        }
+        Ok(())
+    }
+
+    /// Wait until the operations are to be interrupted.
+    async fn interrupted(&self) -> Interrupted {
+        let cancelled = async {
+            match &self.cancellation {
+                Some(token) => token.cancelled().await,
+                None => std::future::pending().await,
+            }
+        };
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            _ = cancelled => Interrupted::Cancelled,
+            _ = deadline => Interrupted::DeadlineExceeded,
+        }
+    }
+
+    /// Run `fut` unless interrupted first. Only for futures that are safe to drop, like reads.
+    pub(crate) async fn interruptible<F: Future>(&self, fut: F) -> Result<F::Output, Interrupted> {
+        self.check()?;
+        tokio::select! {
+            output = fut => Ok(output),
+            reason = self.interrupted() => Err(reason),
+        }
+    }
+
+    /// Sleep for the given duration, waking up early if interrupted.
+    pub(crate) async fn sleep(&self, duration: Duration) {
+        let _ = self.interruptible(tokio::time::sleep(duration)).await;
+    }
+}
+
+impl Client {
+    /// Returns a client whose operations are bounded by the given options.
+    ///
+    /// The client is a cheap clone sharing the network with this one, so it can be created per request:
+    /// `client.with_operation_options(options).dir_upload(path, payment).await`
+    pub fn with_operation_options(&self, options: OperationOptions) -> Self {
+        let mut client = self.clone();
+        client.operation_options = options;
+        client
+    }
+
+    /// The options bounding the operations of this client.
+    pub fn operation_options(&self) -> &OperationOptions {
+        &self.operation_options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_operation_options_interruption() {
+        assert_eq!(OperationOptions::new().check(), Ok(()));
+
+        let token = CancellationToken::new();
+        let options = OperationOptions::new().with_cancellation(token.clone());
+        assert_eq!(options.check(), Ok(()));
+        token.cancel();
+        assert_eq!(options.check(), Err(Interrupted::Cancelled));
+        assert_eq!(
+            options.interruptible(std::future::pending::<()>()).await,
+            Err(Interrupted::Cancelled)
+        );
+
+        let options = OperationOptions::new().with_timeout(Duration::from_millis(10));
+        assert_eq!(
+            options.interruptible(std::future::pending::<()>()).await,
+            Err(Interrupted::DeadlineExceeded)
+        );
+        assert_eq!(options.check(), Err(Interrupted::DeadlineExceeded));
+    }
+}
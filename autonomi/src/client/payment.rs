@@ -7,9 +7,13 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::Client;
+use crate::client::ClientEvent;
 use crate::client::quote::{DataTypes, StoreQuote};
-use ant_evm::{ClientProofOfPayment, EncodedPeerId, EvmWallet, EvmWalletError};
-use std::collections::HashMap;
+use ant_evm::{
+    ClientProofOfPayment, EncodedPeerId, EvmWallet, EvmWalletError, QuoteHash, QuotePayment, TxHash,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
 use xor_name::XorName;
 
 use super::quote::CostError;
@@ -21,6 +25,9 @@ pub type Receipt = HashMap<XorName, (ClientProofOfPayment, AttoTokens)>;
 
 pub type AlreadyPaidAddressesCount = usize;
 
+/// A failed payment, along with the transaction of each quote paid before the failure.
+pub(crate) type PartialPayment = (PayError, BTreeMap<QuoteHash, TxHash>);
+
 /// Errors that can occur during the pay operation.
 #[derive(Debug, thiserror::Error)]
 pub enum PayError {
@@ -34,6 +41,26 @@ pub enum PayError {
     SelfEncryption(#[from] crate::self_encryption::Error),
     #[error("Cost error: {0:?}")]
     Cost(#[from] CostError),
+    /// The payment would go above [`crate::client::cost_budget::CostBudget::max_total`]
+    #[error("Paying {cost} would exceed the budget of {budget}, {spent} being already spent")]
+    BudgetExceeded {
+        cost: AttoTokens,
+        spent: AttoTokens,
+        budget: AttoTokens,
+    },
+    /// Storing an address costs more than [`crate::client::cost_budget::CostBudget::max_per_address`]
+    #[error("Storing {address} costs {price}, above the price ceiling of {ceiling}")]
+    PriceCeilingExceeded {
+        address: XorName,
+        price: AttoTokens,
+        ceiling: AttoTokens,
+    },
+    /// The payment batched with those of other uploads failed, see
+    /// [`crate::ClientOperatingStrategy::payment_batch_window`]
+    #[error("Batched payment failed: {0:?}")]
+    BatchedPaymentFailed(Arc<EvmWalletError>),
+    #[error("The batched payment was interrupted before completing")]
+    BatchedPaymentInterrupted,
 }
 
 pub fn receipt_from_store_quotes(quotes: StoreQuote) -> Receipt {
@@ -99,13 +126,77 @@ impl Client {
     ) -> Result<(Receipt, AlreadyPaidAddressesCount), PayError> {
         match payment_option {
             PaymentOption::Wallet(wallet) => {
-                let (receipt, skipped) = self.pay(data_type, content_addrs, &wallet).await?;
+                let Some(store) = &self.config.receipt_store else {
+                    return self.pay(data_type, content_addrs, &wallet).await;
+                };
+
+                let stored = store.load(data_type, content_addrs.clone().map(|(addr, _)| addr));
+                if !stored.is_empty() {
+                    info!("Reusing {} stored proofs of payment", stored.len());
+                }
+                let (mut receipt, skipped) = self
+                    .pay(
+                        data_type,
+                        content_addrs.filter(|(addr, _)| !stored.contains_key(addr)),
+                        &wallet,
+                    )
+                    .await?;
+                if let Err(err) = store.save(data_type, &receipt) {
+                    warn!(
+                        "Failed to keep the proofs of payment in {:?}: {err}",
+                        store.dir()
+                    );
+                }
+                receipt.extend(stored);
                 Ok((receipt, skipped))
             }
             PaymentOption::Receipt(receipt) => Ok((receipt, 0)),
         }
     }
 
+    /// Forget the proofs of payment kept for addresses now stored on the network, as they won't be
+    /// paid for again, see [`crate::client::receipt_store::ReceiptStore`].
+    pub(crate) fn forget_stored_receipts(&self, addrs: impl IntoIterator<Item = XorName>) {
+        if let Some(store) = &self.config.receipt_store
+            && let Err(err) = store.remove(addrs)
+        {
+            warn!(
+                "Failed to remove stored proofs of payment from {:?}: {err}",
+                store.dir()
+            );
+        }
+    }
+
+    /// Pay the quotes with the wallet, through the payment planner if enabled.
+    ///
+    /// On error, the quotes paid before the error occurred are returned along with it.
+    async fn pay_quotes(
+        &self,
+        wallet: &EvmWallet,
+        quote_payments: Vec<QuotePayment>,
+    ) -> Result<BTreeMap<QuoteHash, TxHash>, PartialPayment> {
+        if let Some(planner) = &self.payment_planner {
+            return planner.pay(wallet, quote_payments).await;
+        }
+
+        // Make sure nobody else can use the wallet while we are paying
+        debug!("Waiting for wallet lock");
+        let lock_guard = wallet.lock().await;
+        debug!("Locked wallet");
+
+        // TODO: retry when it fails?
+        // Execute payments
+        let payments = wallet
+            .pay_for_quotes(quote_payments)
+            .await
+            .map_err(|err| (PayError::from(err.0), err.1));
+
+        // payment is done, unlock the wallet for other threads
+        drop(lock_guard);
+        debug!("Unlocked wallet");
+        payments
+    }
+
     /// Pay for the content addrs and get the proof of payment.
     pub(crate) async fn pay(
         &self,
@@ -119,29 +210,84 @@ impl Client {
         }
 
         let number_of_content_addrs = content_addrs.clone().count();
+        self.send_event(|op| ClientEvent::QuotesRequested {
+            op,
+            addresses: number_of_content_addrs,
+        });
+        let sizes: HashMap<XorName, usize> = content_addrs.clone().collect();
         let quotes = self.get_store_quotes(data_type, content_addrs).await?;
+        self.pay_store_quotes(data_type, quotes, &sizes, wallet)
+            .await
+    }
+
+    /// Pay the quotes and get the proof of payment, `sizes` holding the size of the data at each
+    /// quoted address along with the addresses needing no payment.
+    pub(crate) async fn pay_store_quotes(
+        &self,
+        data_type: DataTypes,
+        quotes: StoreQuote,
+        sizes: &HashMap<XorName, usize>,
+        wallet: &EvmWallet,
+    ) -> Result<(Receipt, AlreadyPaidAddressesCount), PayError> {
+        if wallet.network() != self.evm_network() {
+            return Err(PayError::EvmWalletNetworkMismatch);
+        }
+
+        let number_of_content_addrs = sizes.len();
+        let (quotes, reservation) = self.apply_cost_budget(data_type, quotes, sizes).await?;
+        self.send_event(|op| ClientEvent::QuotesReceived {
+            op,
+            to_pay: quotes.len(),
+            already_paid: number_of_content_addrs - quotes.len(),
+        });
 
         info!("Paying for {} addresses..", quotes.len());
         #[cfg(feature = "loud")]
         println!("Paying for {} addresses..", quotes.len());
 
         if !quotes.is_empty() {
-            // Make sure nobody else can use the wallet while we are paying
-            debug!("Waiting for wallet lock");
-            let lock_guard = wallet.lock().await;
-            debug!("Locked wallet");
-
-            // TODO: the error might contain some succeeded quote payments as well. These should be returned on err, so that they can be skipped when retrying.
-            // TODO: retry when it fails?
-            // Execute payments
-            let _payments = wallet
-                .pay_for_quotes(quotes.payments())
-                .await
-                .map_err(|err| PayError::from(err.0))?;
-
-            // payment is done, unlock the wallet for other threads
-            drop(lock_guard);
-            debug!("Unlocked wallet");
+            let quote_payments = quotes.payments();
+            self.send_event(|op| ClientEvent::PaymentSubmitted {
+                op,
+                quotes: quote_payments.len(),
+            });
+            let payments = match self.pay_quotes(wallet, quote_payments.clone()).await {
+                Ok(payments) => payments,
+                Err((err, paid)) => {
+                    warn!(
+                        "Payment failed after paying {} of {} quotes: {err}",
+                        paid.len(),
+                        quote_payments.len()
+                    );
+                    // the quotes paid before the error are spent, only the rest goes back to the budget
+                    if let Some(reservation) = reservation {
+                        let paid_amount = quote_payments
+                            .iter()
+                            .filter(|(quote_hash, _, _)| paid.contains_key(quote_hash))
+                            .fold(Amount::ZERO, |total, (_, _, amount)| {
+                                total.saturating_add(*amount)
+                            });
+                        reservation.commit_partial(paid_amount);
+                    }
+                    return Err(err);
+                }
+            };
+            self.send_event(|op| ClientEvent::PaymentConfirmed {
+                op,
+                transactions: payments
+                    .into_values()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect(),
+            });
+
+            // paid quotes must not be paid again
+            if let Some(cache) = &self.quote_cache {
+                cache.remove(quotes.0.keys());
+            }
+        }
+        if let Some(reservation) = reservation {
+            reservation.commit();
         }
 
         let skipped_chunks = number_of_content_addrs - quotes.len();
@@ -0,0 +1,343 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::config::ClientOperatingStrategy;
+use crate::client::payment::{PartialPayment, PayError};
+use ant_evm::{Amount, EvmWallet, EvmWalletError, QuoteHash, QuotePayment, RewardsAddress, TxHash};
+use evmlib::contract::payment_vault::MAX_TRANSFERS_PER_TRANSACTION;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tokio::sync::{Notify, oneshot};
+
+/// Outcome of paying a batch: the transaction of each paid quote, or the error along with the
+/// quotes paid before it occurred.
+type BatchOutcome =
+    Result<BTreeMap<QuoteHash, TxHash>, (Arc<EvmWalletError>, BTreeMap<QuoteHash, TxHash>)>;
+
+/// Quote payments waiting to be paid together with the same wallet.
+#[derive(Debug, Default)]
+struct PendingBatch {
+    payments: Vec<QuotePayment>,
+    quote_hashes: HashSet<QuoteHash>,
+    waiters: Vec<oneshot::Sender<BatchOutcome>>,
+    full: Arc<Notify>,
+}
+
+impl PendingBatch {
+    /// Add the payments not in the batch yet, a quote paid once being valid for every upload.
+    fn add(&mut self, payments: &[QuotePayment]) {
+        for payment in payments {
+            if self.quote_hashes.insert(payment.0) {
+                self.payments.push(*payment);
+            }
+        }
+    }
+}
+
+/// Merges the payments of concurrent uploads into fewer transactions, enabled with
+/// [`ClientOperatingStrategy::payment_batch_window`].
+///
+/// The first payment made with a wallet opens a batch, to which the payments made with the same
+/// wallet during the window are added. The batch is paid at the end of the window, or as soon as it
+/// holds [`MAX_TRANSFERS_PER_TRANSACTION`] payments.
+#[derive(Debug)]
+pub(crate) struct PaymentPlanner {
+    window: Duration,
+    batches: Mutex<HashMap<RewardsAddress, PendingBatch>>,
+}
+
+impl PaymentPlanner {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            batches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn batches(&self) -> MutexGuard<'_, HashMap<RewardsAddress, PendingBatch>> {
+        // the batches stay consistent even if a panic occurred while holding the lock
+        self.batches.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Pay for the quotes along with the other payments made with the wallet during the window.
+    ///
+    /// The batch is paid even if the caller stops waiting for it, as the other uploads depend on it.
+    pub async fn pay(
+        self: &Arc<Self>,
+        wallet: &EvmWallet,
+        payments: Vec<QuotePayment>,
+    ) -> Result<BTreeMap<QuoteHash, TxHash>, PartialPayment> {
+        let wallet = wallet.clone();
+        self.pay_with(wallet.address(), payments, move |payments| async move {
+            // Make sure nobody else can use the wallet while we are paying
+            let _lock_guard = wallet.lock().await;
+            wallet
+                .pay_for_quotes(payments)
+                .await
+                .map_err(|err| (Arc::new(err.0), err.1))
+        })
+        .await
+    }
+
+    /// Add the quotes to the batch of the `payer_address`, `pay_batch` paying the batch if this
+    /// opens it.
+    async fn pay_with<F, Fut>(
+        self: &Arc<Self>,
+        payer_address: RewardsAddress,
+        payments: Vec<QuotePayment>,
+        pay_batch: F,
+    ) -> Result<BTreeMap<QuoteHash, TxHash>, PartialPayment>
+    where
+        F: FnOnce(Vec<QuotePayment>) -> Fut + Send + 'static,
+        Fut: Future<Output = BatchOutcome> + Send + 'static,
+    {
+        // payments with 0 amount are not sent to the network
+        let quote_hashes: Vec<QuoteHash> = payments
+            .iter()
+            .filter(|(_, _, amount)| *amount > Amount::ZERO)
+            .map(|(quote_hash, _, _)| *quote_hash)
+            .collect();
+        if quote_hashes.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut batches = self.batches();
+            let batch = match batches.entry(payer_address) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let batch = entry.insert(PendingBatch::default());
+                    tokio::spawn(Arc::clone(self).pay_batch(
+                        payer_address,
+                        Arc::clone(&batch.full),
+                        pay_batch,
+                    ));
+                    batch
+                }
+            };
+            batch.add(&payments);
+            batch.waiters.push(sender);
+            if batch.payments.len() >= MAX_TRANSFERS_PER_TRANSACTION {
+                batch.full.notify_one();
+            }
+        }
+
+        let outcome = receiver
+            .await
+            .map_err(|_| (PayError::BatchedPaymentInterrupted, BTreeMap::new()))?;
+        let paid = match &outcome {
+            Ok(paid) => paid,
+            Err((_, paid)) => paid,
+        };
+        let own: BTreeMap<QuoteHash, TxHash> = quote_hashes
+            .iter()
+            .filter_map(|quote_hash| paid.get(quote_hash).map(|tx| (*quote_hash, *tx)))
+            .collect();
+        match outcome {
+            // our quotes might have been paid before the batch failed
+            Err((err, _)) if own.len() < quote_hashes.len() => {
+                Err((PayError::BatchedPaymentFailed(err), own))
+            }
+            _ => Ok(own),
+        }
+    }
+
+    /// Wait for the end of the window, then pay the batch of the payer.
+    async fn pay_batch<F, Fut>(
+        self: Arc<Self>,
+        payer_address: RewardsAddress,
+        full: Arc<Notify>,
+        pay_batch: F,
+    ) where
+        F: FnOnce(Vec<QuotePayment>) -> Fut,
+        Fut: Future<Output = BatchOutcome>,
+    {
+        tokio::select! {
+            _ = tokio::time::sleep(self.window) => {}
+            _ = full.notified() => {}
+        }
+
+        let Some(batch) = self.batches().remove(&payer_address) else {
+            return;
+        };
+        info!(
+            "Paying a batch of {} quotes for {} uploads",
+            batch.payments.len(),
+            batch.waiters.len()
+        );
+
+        let outcome = pay_batch(batch.payments).await;
+        for waiter in batch.waiters {
+            let _ = waiter.send(outcome.clone());
+        }
+    }
+}
+
+/// Build the payment planner of a client, if enabled in the strategy.
+pub(crate) fn payment_planner_from_strategy(
+    strategy: &ClientOperatingStrategy,
+) -> Option<Arc<PaymentPlanner>> {
+    strategy
+        .payment_batch_window
+        .map(|window| Arc::new(PaymentPlanner::new(window)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_pending_batch_merges_payments() {
+        let quote = |byte: u8| {
+            (
+                QuoteHash::repeat_byte(byte),
+                RewardsAddress::ZERO,
+                Amount::from(1),
+            )
+        };
+
+        let mut batch = PendingBatch::default();
+        batch.add(&[quote(1), quote(2)]);
+        // a quote shared by two uploads is paid once
+        batch.add(&[quote(2), quote(3)]);
+
+        let hashes: Vec<QuoteHash> = batch.payments.iter().map(|(hash, _, _)| *hash).collect();
+        assert_eq!(
+            hashes,
+            vec![
+                QuoteHash::repeat_byte(1),
+                QuoteHash::repeat_byte(2),
+                QuoteHash::repeat_byte(3)
+            ]
+        );
+    }
+
+    fn payment(index: u64) -> QuotePayment {
+        (
+            QuoteHash::left_padding_from(&index.to_be_bytes()),
+            RewardsAddress::ZERO,
+            Amount::from(1),
+        )
+    }
+
+    /// Pays every quote of the batch in the `tx` transaction, counting the batches paid.
+    fn pay_all(
+        batches_paid: &Arc<AtomicUsize>,
+        tx: TxHash,
+    ) -> impl FnOnce(Vec<QuotePayment>) -> futures::future::Ready<BatchOutcome> + use<> {
+        let batches_paid = Arc::clone(batches_paid);
+        move |payments| {
+            batches_paid.fetch_add(1, Ordering::SeqCst);
+            futures::future::ready(Ok(payments
+                .iter()
+                .map(|(quote_hash, _, _)| (*quote_hash, tx))
+                .collect()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_payments_share_a_batch() {
+        let planner = Arc::new(PaymentPlanner::new(Duration::from_millis(100)));
+        let batches_paid = Arc::new(AtomicUsize::new(0));
+        let tx = TxHash::repeat_byte(1);
+
+        let (first, second) = tokio::join!(
+            planner.pay_with(
+                RewardsAddress::ZERO,
+                vec![payment(1), payment(2)],
+                pay_all(&batches_paid, tx)
+            ),
+            planner.pay_with(
+                RewardsAddress::ZERO,
+                vec![payment(2), payment(3)],
+                pay_all(&batches_paid, tx)
+            ),
+        );
+        assert_eq!(batches_paid.load(Ordering::SeqCst), 1);
+
+        // each upload only gets its own quotes back
+        let first = first.expect("batch paid");
+        let second = second.expect("batch paid");
+        assert_eq!(
+            first.keys().copied().collect::<Vec<_>>(),
+            vec![payment(1).0, payment(2).0]
+        );
+        assert_eq!(
+            second.keys().copied().collect::<Vec<_>>(),
+            vec![payment(2).0, payment(3).0]
+        );
+
+        // free quotes are not batched
+        let mut free = payment(4);
+        free.2 = Amount::ZERO;
+        let paid = planner
+            .pay_with(RewardsAddress::ZERO, vec![free], pay_all(&batches_paid, tx))
+            .await
+            .expect("nothing to pay");
+        assert!(paid.is_empty());
+        assert_eq!(batches_paid.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_full_batch_is_paid_before_the_window_ends() {
+        let planner = Arc::new(PaymentPlanner::new(Duration::from_secs(3600)));
+        let batches_paid = Arc::new(AtomicUsize::new(0));
+        let payments = (0..MAX_TRANSFERS_PER_TRANSACTION as u64)
+            .map(payment)
+            .collect();
+
+        let paid = tokio::time::timeout(
+            Duration::from_secs(10),
+            planner.pay_with(
+                RewardsAddress::ZERO,
+                payments,
+                pay_all(&batches_paid, TxHash::repeat_byte(1)),
+            ),
+        )
+        .await
+        .expect("paid without waiting for the window")
+        .expect("batch paid");
+        assert_eq!(paid.len(), MAX_TRANSFERS_PER_TRANSACTION);
+        assert_eq!(batches_paid.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_reports_partial_payments() {
+        let planner = Arc::new(PaymentPlanner::new(Duration::from_millis(100)));
+        let tx = TxHash::repeat_byte(1);
+        // the batch fails after paying the first quote only
+        let fail_after_first = move |payments: Vec<QuotePayment>| {
+            let paid = BTreeMap::from([(payments[0].0, tx)]);
+            futures::future::ready(Err((Arc::new(EvmWalletError::PrivateKeyInvalid), paid)))
+        };
+
+        let (first, second) = tokio::join!(
+            planner.pay_with(RewardsAddress::ZERO, vec![payment(1)], fail_after_first),
+            planner.pay_with(
+                RewardsAddress::ZERO,
+                vec![payment(2), payment(3)],
+                fail_after_first
+            ),
+        );
+
+        // all the quotes of the first upload were paid before the failure
+        assert_eq!(
+            first.expect("own quotes paid"),
+            BTreeMap::from([(payment(1).0, tx)])
+        );
+        match second {
+            Err((PayError::BatchedPaymentFailed(_), paid)) => assert!(paid.is_empty()),
+            other => panic!("Expected a failed batch, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::config::ClientOperatingStrategy;
+use ant_protocol::storage::{PointerAddress, PointerTarget};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Suggested time during which the target of a pointer is reused when resolving pointer chains.
+pub const DEFAULT_POINTER_CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct CachedTarget {
+    target: PointerTarget,
+    expires_at: Instant,
+}
+
+/// Targets of the pointers followed by [`crate::Client::pointer_resolve`], reused until they expire.
+///
+/// As pointers can be updated at any time, an entry expires
+/// [`ClientOperatingStrategy::pointer_cache_ttl`] after it was fetched. Updates made by this
+/// client replace the cached target right away.
+#[derive(Debug)]
+pub(crate) struct PointerCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<PointerAddress, CachedTarget>>,
+}
+
+impl PointerCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<PointerAddress, CachedTarget>> {
+        // the entries stay consistent even if a panic occurred while holding the lock
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The target of the pointer, if fetched recently enough.
+    pub fn get(&self, addr: &PointerAddress) -> Option<PointerTarget> {
+        let mut entries = self.entries();
+        let entry = entries.get(addr)?;
+        if entry.expires_at <= Instant::now() {
+            entries.remove(addr);
+            return None;
+        }
+        Some(entry.target.clone())
+    }
+
+    pub fn insert(&self, addr: PointerAddress, target: PointerTarget) {
+        let now = Instant::now();
+        let mut entries = self.entries();
+        entries.retain(|_, entry| entry.expires_at > now);
+        entries.insert(
+            addr,
+            CachedTarget {
+                target,
+                expires_at: now + self.ttl,
+            },
+        );
+    }
+}
+
+/// Build the pointer cache of a client, if enabled in the strategy.
+pub(crate) fn pointer_cache_from_strategy(
+    strategy: &ClientOperatingStrategy,
+) -> Option<Arc<PointerCache>> {
+    strategy
+        .pointer_cache_ttl
+        .filter(|ttl| !ttl.is_zero())
+        .map(|ttl| Arc::new(PointerCache::new(ttl)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant_protocol::storage::ChunkAddress;
+    use bls::SecretKey;
+    use xor_name::XorName;
+
+    #[test]
+    fn test_pointer_cache_expiry() {
+        let addr = PointerAddress::new(SecretKey::random().public_key());
+        let target = PointerTarget::ChunkAddress(ChunkAddress::new(XorName::random(
+            &mut rand::thread_rng(),
+        )));
+
+        let cache = PointerCache::new(Duration::from_secs(60));
+        cache.insert(addr, target.clone());
+        assert_eq!(cache.get(&addr), Some(target.clone()));
+
+        let expired = PointerCache::new(Duration::ZERO);
+        expired.insert(addr, target);
+        assert_eq!(expired.get(&addr), None);
+    }
+}
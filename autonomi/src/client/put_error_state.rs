@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use super::PutError;
+use super::{Interrupted, PutError};
 use crate::client::NetworkAddress;
 use crate::client::data_types::chunk::ChunkAddress;
 use crate::client::payment::Receipt;
@@ -71,3 +71,24 @@ impl ChunkBatchUploadState {
         }
     }
 }
+
+/// Error of an interrupted upload, with the chunks stored and the payments made so far.
+pub(crate) fn interrupted_upload(
+    reason: Interrupted,
+    successful: Vec<ChunkAddress>,
+    pending: impl Iterator<Item = ChunkAddress>,
+    receipts: Vec<Receipt>,
+) -> PutError {
+    let mut payment = Receipt::default();
+    for receipt in receipts {
+        payment.extend(receipt);
+    }
+    PutError::Interrupted {
+        reason,
+        state: ChunkBatchUploadState {
+            successful,
+            failed: pending.map(|addr| (addr, reason.to_string())).collect(),
+            payment: Some(payment),
+        },
+    }
+}
@@ -8,6 +8,7 @@
 
 use super::Client;
 use crate::client::config::CHUNK_UPLOAD_BATCH_SIZE;
+use crate::client::quote_cache::PricedQuotes;
 use crate::networking::Network;
 use crate::networking::PeerInfo;
 use crate::networking::common::Addresses;
@@ -166,11 +167,90 @@ impl Client {
         }
     }
 
+    /// Get the quotes to pay for storing data at the content addresses.
+    ///
+    /// Quotes fetched earlier for the same addresses are reused until they expire, see
+    /// [`crate::ClientOperatingStrategy::quote_validity`].
     pub async fn get_store_quotes(
         &self,
         data_type: DataTypes,
         content_addrs: impl Iterator<Item = (XorName, usize)>,
     ) -> Result<StoreQuote, CostError> {
+        let mut quotes_per_addr: HashMap<XorName, PricedQuotes> = HashMap::new();
+        let mut sizes = HashMap::new();
+        let to_fetch: Vec<(XorName, usize)> = content_addrs
+            .filter(|(content_addr, data_size)| {
+                let cached = self
+                    .quote_cache
+                    .as_ref()
+                    .and_then(|cache| cache.get(content_addr, data_type.get_index(), *data_size));
+                match cached {
+                    Some(quotes) => {
+                        debug!("Reusing cached quotes for content_addr: {content_addr}");
+                        quotes_per_addr.insert(*content_addr, quotes);
+                        false
+                    }
+                    None => {
+                        sizes.insert(*content_addr, *data_size);
+                        true
+                    }
+                }
+            })
+            .collect();
+
+        let fetched = self
+            .fetch_priced_quotes(data_type, to_fetch.into_iter())
+            .await?;
+        if let Some(cache) = &self.quote_cache {
+            for (content_addr, quotes) in fetched.iter() {
+                let data_size = sizes.get(content_addr).copied().unwrap_or(0);
+                cache.insert(
+                    *content_addr,
+                    data_type.get_index(),
+                    data_size,
+                    quotes.clone(),
+                );
+            }
+        }
+        quotes_per_addr.extend(fetched);
+
+        let quotes_to_pay_per_addr = self.process_quotes_by_payment_mode(quotes_per_addr)?;
+
+        Ok(StoreQuote(quotes_to_pay_per_addr))
+    }
+
+    /// Get the quote to pay for storing data at the content address, from raw quotes gathered
+    /// from specific peers.
+    pub(crate) async fn store_quote_from_raw(
+        &self,
+        content_addr: XorName,
+        raw_quotes: Vec<(PeerId, Addresses, PaymentQuote)>,
+    ) -> Result<StoreQuote, CostError> {
+        let quoting_metrics: Vec<QuotingMetrics> = raw_quotes
+            .iter()
+            .map(|(_, _, quote)| quote.quoting_metrics.clone())
+            .collect();
+        let prices = get_market_price(&self.evm_network, quoting_metrics).await?;
+
+        let mut quotes: PricedQuotes = raw_quotes
+            .into_iter()
+            .zip(prices)
+            .map(|((peer_id, addrs, quote), price)| (peer_id, addrs, quote, price))
+            .collect();
+        quotes.sort_by_key(|(_, _, _, price)| *price);
+
+        let quotes_to_pay =
+            self.process_quotes_by_payment_mode(HashMap::from([(content_addr, quotes)]))?;
+        Ok(StoreQuote(quotes_to_pay))
+    }
+
+    /// Fetch the quotes of the close peers of the addresses and their market prices, sorted by
+    /// price. Addresses already stored are left out.
+    async fn fetch_priced_quotes(
+        &self,
+        data_type: DataTypes,
+        content_addrs: impl Iterator<Item = (XorName, usize)>,
+    ) -> Result<HashMap<XorName, PricedQuotes>, CostError> {
         let raw_quotes_per_addr = self.get_raw_quotes(data_type, content_addrs).await;
         let mut all_quotes = Vec::new();
 
@@ -222,14 +302,13 @@ impl Client {
         let quotes_with_prices: Vec<(XorName, PeerId, Addresses, PaymentQuote, Amount)> =
             all_quotes
                 .into_iter()
-                .zip(all_prices.into_iter())
+                .zip(all_prices)
                 .map(|((content_addr, peer_id, addrs, quote), price)| {
                     (content_addr, peer_id, addrs, quote, price)
                 })
                 .collect();
 
-        let mut quotes_per_addr: HashMap<XorName, Vec<(PeerId, Addresses, PaymentQuote, Amount)>> =
-            HashMap::new();
+        let mut quotes_per_addr: HashMap<XorName, PricedQuotes> = HashMap::new();
 
         for (content_addr, peer_id, addrs, quote, price) in quotes_with_prices {
             let entry = quotes_per_addr.entry(content_addr).or_default();
@@ -237,9 +316,7 @@ impl Client {
             entry.sort_by_key(|(_, _, _, price)| *price);
         }
 
-        let quotes_to_pay_per_addr = self.process_quotes_by_payment_mode(quotes_per_addr)?;
-
-        Ok(StoreQuote(quotes_to_pay_per_addr))
+        Ok(quotes_per_addr)
     }
 
     /// Process quotes according to the payment mode
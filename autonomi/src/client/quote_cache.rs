@@ -0,0 +1,142 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::config::ClientOperatingStrategy;
+use crate::networking::common::Addresses;
+use ant_evm::{Amount, PaymentQuote};
+use libp2p::PeerId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime};
+use xor_name::XorName;
+
+/// Suggested time during which a quote is reused, counted from the time the node issued it, see
+/// [`ClientOperatingStrategy::quote_validity`].
+pub const DEFAULT_QUOTE_VALIDITY: Duration = Duration::from_secs(5 * 60);
+
+/// The priced quotes of the close peers of an address, sorted by price.
+pub(crate) type PricedQuotes = Vec<(PeerId, Addresses, PaymentQuote, Amount)>;
+
+#[derive(Debug)]
+struct CachedQuotes {
+    data_type: u32,
+    data_size: usize,
+    quotes: PricedQuotes,
+    expires_at: SystemTime,
+}
+
+/// Quotes fetched for content addresses, reused until they expire or get paid.
+///
+/// An entry expires [`ClientOperatingStrategy::quote_validity`] after the oldest of its quotes was
+/// issued, according to the [`PaymentQuote::timestamp`] set by the nodes.
+#[derive(Debug)]
+pub(crate) struct QuoteCache {
+    validity: Duration,
+    entries: Mutex<HashMap<XorName, CachedQuotes>>,
+}
+
+impl QuoteCache {
+    pub fn new(validity: Duration) -> Self {
+        Self {
+            validity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<XorName, CachedQuotes>> {
+        // the entries stay consistent even if a panic occurred while holding the lock
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The quotes still valid for storing `data_size` bytes of `data_type` at `addr`.
+    pub fn get(&self, addr: &XorName, data_type: u32, data_size: usize) -> Option<PricedQuotes> {
+        let mut entries = self.entries();
+        let entry = entries.get(addr)?;
+        if entry.expires_at <= SystemTime::now() {
+            entries.remove(addr);
+            return None;
+        }
+        (entry.data_type == data_type && entry.data_size == data_size).then(|| entry.quotes.clone())
+    }
+
+    pub fn insert(&self, addr: XorName, data_type: u32, data_size: usize, quotes: PricedQuotes) {
+        let Some(oldest) = quotes.iter().map(|(_, _, quote, _)| quote.timestamp).min() else {
+            return;
+        };
+        let expires_at = oldest + self.validity;
+        let now = SystemTime::now();
+        if expires_at <= now {
+            return;
+        }
+
+        let mut entries = self.entries();
+        entries.retain(|_, entry| entry.expires_at > now);
+        entries.insert(
+            addr,
+            CachedQuotes {
+                data_type,
+                data_size,
+                quotes,
+                expires_at,
+            },
+        );
+    }
+
+    /// Forget the quotes of the addresses, once paid or when fresh quotes are needed.
+    pub fn remove<'a>(&self, addrs: impl IntoIterator<Item = &'a XorName>) {
+        let mut entries = self.entries();
+        for addr in addrs {
+            entries.remove(addr);
+        }
+    }
+}
+
+/// Build the quote cache of a client, if enabled in the strategy.
+pub(crate) fn quote_cache_from_strategy(
+    strategy: &ClientOperatingStrategy,
+) -> Option<Arc<QuoteCache>> {
+    strategy
+        .quote_validity
+        .filter(|validity| !validity.is_zero())
+        .map(|validity| Arc::new(QuoteCache::new(validity)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::quote_issued_at;
+
+    fn quotes_issued_at(addr: XorName, timestamp: SystemTime) -> PricedQuotes {
+        let quote = quote_issued_at(addr, timestamp);
+        vec![(PeerId::random(), Addresses(vec![]), quote, Amount::from(1))]
+    }
+
+    #[test]
+    fn test_quote_cache_expiry() {
+        let cache = QuoteCache::new(Duration::from_secs(60));
+        let fresh = XorName::random(&mut rand::thread_rng());
+        let stale = XorName::random(&mut rand::thread_rng());
+
+        cache.insert(fresh, 0, 10, quotes_issued_at(fresh, SystemTime::now()));
+        cache.insert(
+            stale,
+            0,
+            10,
+            quotes_issued_at(stale, SystemTime::now() - Duration::from_secs(61)),
+        );
+
+        assert!(cache.get(&fresh, 0, 10).is_some());
+        assert!(cache.get(&stale, 0, 10).is_none());
+        // quotes are only valid for the data type and size they were asked for
+        assert!(cache.get(&fresh, 1, 10).is_none());
+        assert!(cache.get(&fresh, 0, 11).is_none());
+
+        cache.remove([&fresh]);
+        assert!(cache.get(&fresh, 0, 10).is_none());
+    }
+}
@@ -0,0 +1,276 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::payment::Receipt;
+use crate::client::quote::DataTypes;
+use ant_evm::{AttoTokens, ClientProofOfPayment};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use xor_name::XorName;
+
+/// Default time during which a proof of payment is reused, counted from the time it was quoted.
+pub const DEFAULT_RECEIPT_EXPIRY: Duration = Duration::from_secs(3600 * 24 * 30);
+
+/// Extension of the files being written, renamed once complete.
+const TMP_EXTENSION: &str = "tmp";
+/// File touched each time the store is pruned.
+const LAST_PRUNE_FILE: &str = "last_prune";
+/// Minimum time between two prunings of the store when opening it.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600 * 24);
+
+/// Errors that can occur while reading or writing a [`ReceiptStore`].
+#[derive(Debug, thiserror::Error)]
+pub enum ReceiptStoreError {
+    #[error("IO failure on receipt store: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to serialize proof of payment: {0}")]
+    Serialization(#[from] rmp_serde::encode::Error),
+    #[error("Failed to deserialize proof of payment: {0}")]
+    Deserialization(#[from] rmp_serde::decode::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredProof {
+    data_type: u32,
+    proof: ClientProofOfPayment,
+}
+
+impl StoredProof {
+    /// Proofs expire with the oldest of their quotes.
+    fn quoted_at(&self) -> Option<SystemTime> {
+        self.proof
+            .peer_quotes
+            .iter()
+            .map(|(_, _, quote)| quote.timestamp)
+            .min()
+    }
+}
+
+/// This type wraps the proofs in a version marker, the same way archives are.
+#[derive(Serialize, Deserialize)]
+#[non_exhaustive]
+enum StoredProofVersioned {
+    V0(StoredProof),
+}
+
+/// Proofs of payment kept on disk by content address, so data paid for but not stored yet, e.g.
+/// after a failed upload, is not paid for again, even by another process.
+///
+/// Set in [`crate::ClientOperatingStrategy::receipt_store`], the store is consulted by every
+/// payment made with a wallet, such as those of [`crate::Client::chunk_put`],
+/// [`crate::Client::pointer_put`], [`crate::Client::scratchpad_put`] and
+/// [`crate::Client::graph_entry_put`], before asking for new quotes. Each proof is kept in its own
+/// file, until the data it paid for is stored or it expires.
+#[derive(Debug, Clone)]
+pub struct ReceiptStore {
+    dir: PathBuf,
+    expiry: Duration,
+}
+
+impl ReceiptStore {
+    /// Open the store kept in `dir`, creating the directory if needed.
+    ///
+    /// Expired proofs are removed when they are loaded, and the whole store is pruned at most once
+    /// a day when opening it, so that opening it stays cheap.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, ReceiptStoreError> {
+        let store = Self {
+            dir: dir.into(),
+            expiry: DEFAULT_RECEIPT_EXPIRY,
+        };
+        std::fs::create_dir_all(&store.dir)?;
+        if store.prune_is_due() {
+            let pruned = store.prune()?;
+            if pruned > 0 {
+                info!("Removed {pruned} expired proofs of payment");
+            }
+        }
+        Ok(store)
+    }
+
+    /// Whether the store was not pruned for [`PRUNE_INTERVAL`].
+    fn prune_is_due(&self) -> bool {
+        std::fs::metadata(self.dir.join(LAST_PRUNE_FILE))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|pruned_at| pruned_at.elapsed().ok())
+            .is_none_or(|elapsed| elapsed >= PRUNE_INTERVAL)
+    }
+
+    /// Reuse the proofs for `expiry` after they were quoted, instead of [`DEFAULT_RECEIPT_EXPIRY`].
+    pub fn with_expiry(mut self, expiry: Duration) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// The directory the proofs are kept in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, addr: &XorName) -> PathBuf {
+        self.dir.join(hex::encode(addr.0))
+    }
+
+    fn is_expired(&self, proof: &StoredProof) -> bool {
+        match proof.quoted_at() {
+            Some(quoted_at) => quoted_at + self.expiry <= SystemTime::now(),
+            None => true,
+        }
+    }
+
+    fn read(&self, path: &Path) -> Result<StoredProof, ReceiptStoreError> {
+        let bytes = std::fs::read(path)?;
+        let versioned: StoredProofVersioned = rmp_serde::from_slice(&bytes)?;
+        let StoredProofVersioned::V0(proof) = versioned;
+        Ok(proof)
+    }
+
+    /// Keep the proofs of the receipt, paid for storing data of `data_type`.
+    ///
+    /// Each proof is written to a temporary file first and then renamed, so a crash in the middle of
+    /// a write never leaves a corrupted proof behind.
+    pub fn save(&self, data_type: DataTypes, receipt: &Receipt) -> Result<(), ReceiptStoreError> {
+        for (addr, (proof, _price)) in receipt {
+            let stored = StoredProof {
+                data_type: data_type.get_index(),
+                proof: proof.clone(),
+            };
+            let bytes = rmp_serde::to_vec_named(&StoredProofVersioned::V0(stored))?;
+            let path = self.path(addr);
+            let tmp_path = path.with_extension(TMP_EXTENSION);
+            std::fs::write(&tmp_path, bytes)?;
+            std::fs::rename(&tmp_path, path)?;
+        }
+        Ok(())
+    }
+
+    /// The proofs kept for storing data of `data_type` at the addresses.
+    ///
+    /// The proofs are given a price of zero, as using them costs nothing more.
+    pub fn load(&self, data_type: DataTypes, addrs: impl IntoIterator<Item = XorName>) -> Receipt {
+        let mut receipt = Receipt::new();
+        for addr in addrs {
+            let path = self.path(&addr);
+            if !path.exists() {
+                continue;
+            }
+            match self.read(&path) {
+                Ok(proof) if self.is_expired(&proof) => {
+                    let _ = std::fs::remove_file(&path);
+                }
+                Ok(proof) if proof.data_type == data_type.get_index() => {
+                    receipt.insert(addr, (proof.proof, AttoTokens::zero()));
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    warn!("Ignoring unreadable proof of payment at {path:?}: {err}");
+                }
+            }
+        }
+        receipt
+    }
+
+    /// Forget the proofs of the addresses.
+    pub fn remove(
+        &self,
+        addrs: impl IntoIterator<Item = XorName>,
+    ) -> Result<(), ReceiptStoreError> {
+        for addr in addrs {
+            match std::fs::remove_file(self.path(&addr)) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the expired and unreadable proofs, returning how many were removed.
+    pub fn prune(&self) -> Result<usize, ReceiptStoreError> {
+        let last_prune = self.dir.join(LAST_PRUNE_FILE);
+        let mut pruned = 0;
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if !path.is_file() || path == last_prune {
+                continue;
+            }
+            let stale = path.extension().is_some_and(|ext| ext == TMP_EXTENSION)
+                || self
+                    .read(&path)
+                    .map_or(true, |proof| self.is_expired(&proof));
+            if stale {
+                std::fs::remove_file(&path)?;
+                pruned += 1;
+            }
+        }
+        std::fs::write(last_prune, [])?;
+        Ok(pruned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::quote_issued_at;
+    use ant_evm::EncodedPeerId;
+    use libp2p::PeerId;
+
+    fn proof_quoted_at(addr: XorName, timestamp: SystemTime) -> ClientProofOfPayment {
+        let quote = quote_issued_at(addr, timestamp);
+        ClientProofOfPayment {
+            peer_quotes: vec![(EncodedPeerId::from(PeerId::random()), vec![], quote)],
+        }
+    }
+
+    #[test]
+    fn test_receipt_store() -> Result<(), ReceiptStoreError> {
+        let dir = tempfile::tempdir()?;
+        let store = ReceiptStore::open(dir.path())?.with_expiry(Duration::from_secs(60));
+
+        let fresh = XorName::random(&mut rand::thread_rng());
+        let stale = XorName::random(&mut rand::thread_rng());
+        let mut receipt = Receipt::new();
+        receipt.insert(
+            fresh,
+            (
+                proof_quoted_at(fresh, SystemTime::now()),
+                AttoTokens::from_u64(10),
+            ),
+        );
+        receipt.insert(
+            stale,
+            (
+                proof_quoted_at(stale, SystemTime::now() - Duration::from_secs(61)),
+                AttoTokens::from_u64(10),
+            ),
+        );
+        store.save(DataTypes::Chunk, &receipt)?;
+
+        // proofs are reused across processes, at no cost
+        let reopened = ReceiptStore::open(dir.path())?.with_expiry(Duration::from_secs(60));
+        let loaded = reopened.load(DataTypes::Chunk, [fresh, stale]);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(
+            loaded[&fresh],
+            (receipt[&fresh].0.clone(), AttoTokens::zero())
+        );
+
+        // proofs are only valid for the data type they were paid for
+        assert!(reopened.load(DataTypes::Pointer, [fresh]).is_empty());
+
+        reopened.remove([fresh])?;
+        assert!(reopened.load(DataTypes::Chunk, [fresh]).is_empty());
+
+        // the store was pruned when first opened, it is not pruned again on each open
+        store.save(DataTypes::Chunk, &receipt)?;
+        ReceiptStore::open(dir.path())?;
+        assert!(dir.path().join(hex::encode(stale.0)).exists());
+        assert_eq!(store.prune()?, 1);
+        Ok(())
+    }
+}
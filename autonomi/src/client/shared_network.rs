@@ -0,0 +1,150 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::chunk_cache::{DiskChunkCaches, chunk_cache_from_strategy};
+use super::config::ClientOperatingStrategy;
+use super::operation_options::OperationOptions;
+use super::payment_planner::payment_planner_from_strategy;
+use super::pointer_cache::pointer_cache_from_strategy;
+use super::quote::PaymentMode;
+use super::quote_cache::quote_cache_from_strategy;
+use super::{Client, ConnectError};
+use crate::networking::{Network, RequestStats};
+use ant_bootstrap::{BootstrapConfig, bootstrap::Bootstrap};
+use ant_evm::EvmNetwork;
+
+/// A connection to the network that many [`Client`]s can share.
+///
+/// Bootstrapping and connecting to peers is done once, the clients attached with
+/// [`SharedNetwork::client`] then send their requests through the same network driver, each with
+/// its own EVM network, [`ClientOperatingStrategy`] and wallets. The requests of each client are
+/// counted separately, see [`Client::request_stats`]. The clients caching chunks on disk in the same
+/// [`ClientOperatingStrategy::chunk_cache_dir`] share one cache, clients in other processes must use
+/// their own directory.
+///
+/// ```no_run
+/// # use autonomi::client::{SharedNetwork, config::ClientOperatingStrategy};
+/// # use autonomi::{BootstrapConfig, Network};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let shared = SharedNetwork::connect(BootstrapConfig::new(false), None).await?;
+/// let alice = shared.client(Network::ArbitrumOne, ClientOperatingStrategy::default());
+/// let bob = shared.client(Network::ArbitrumOne, ClientOperatingStrategy::default());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedNetwork {
+    network: Network,
+    disk_chunk_caches: DiskChunkCaches,
+}
+
+impl SharedNetwork {
+    /// Bootstrap and connect to the network.
+    ///
+    /// This will block until [`crate::client::CLOSE_GROUP_SIZE`] have been added to the routing table.
+    /// If the connection fails with the bootstrap cache enabled, it is retried once without it.
+    pub async fn connect(
+        bootstrap_config: BootstrapConfig,
+        network_id: Option<u8>,
+    ) -> Result<Self, ConnectError> {
+        if let Some(network_id) = network_id {
+            ant_protocol::version::set_network_id(network_id);
+        }
+
+        let bootstrap = Bootstrap::new(bootstrap_config.clone()).await?;
+        let network = Network::new(bootstrap)?;
+
+        // Wait for the network to be ready with enough peers
+        let connectivity_result = network.wait_for_connectivity().await;
+
+        // If the connection failed and we were using the bootstrap cache,
+        // retry once with the cache disabled to fall back to mainnet contacts
+        if connectivity_result.is_err() && !bootstrap_config.disable_cache_reading {
+            warn!(
+                "Initial connection failed with bootstrap cache enabled. Retrying with cache disabled to use mainnet contacts..."
+            );
+
+            // Create a new config with cache reading disabled
+            let retry_config = BootstrapConfig {
+                disable_cache_reading: true,
+                ..bootstrap_config
+            };
+
+            // Retry the bootstrap and connection with cache disabled
+            let bootstrap_retry = Bootstrap::new(retry_config).await?;
+            let network_retry = Network::new(bootstrap_retry)?;
+
+            // Wait for connectivity with the new bootstrap configuration
+            network_retry.wait_for_connectivity().await?;
+
+            info!(
+                "Successfully connected to the network using mainnet contacts after cache failure"
+            );
+
+            return Ok(Self {
+                network: network_retry,
+                disk_chunk_caches: DiskChunkCaches::default(),
+            });
+        }
+
+        // If the first attempt succeeded or cache was already disabled, return normally
+        connectivity_result?;
+
+        Ok(Self {
+            network,
+            disk_chunk_caches: DiskChunkCaches::default(),
+        })
+    }
+
+    /// A network driver which is not connected to any peer.
+    #[cfg(test)]
+    pub(crate) fn offline(bootstrap: Bootstrap) -> Self {
+        let network = Network::new(bootstrap).expect("Failed to start the network driver");
+        Self {
+            network,
+            disk_chunk_caches: DiskChunkCaches::default(),
+        }
+    }
+
+    /// Attach a new client to this network, with its own EVM network, strategy and request stats.
+    pub fn client(&self, evm_network: EvmNetwork, strategy: ClientOperatingStrategy) -> Client {
+        Client {
+            network: strategy.apply_retry_policy(self.network.attach()),
+            client_event_sender: None,
+            evm_network,
+            chunk_cache: chunk_cache_from_strategy(&strategy, &self.disk_chunk_caches),
+            disk_chunk_caches: self.disk_chunk_caches.clone(),
+            quote_cache: quote_cache_from_strategy(&strategy),
+            payment_planner: payment_planner_from_strategy(&strategy),
+            pointer_cache: pointer_cache_from_strategy(&strategy),
+            config: strategy,
+            retry_failed: 0,
+            payment_mode: PaymentMode::default(),
+            operation: None,
+            operation_options: OperationOptions::default(),
+            operation_spend: None,
+        }
+    }
+}
+
+impl Client {
+    /// The network this client is connected to, to attach more clients to it.
+    pub fn shared_network(&self) -> SharedNetwork {
+        SharedNetwork {
+            network: self.network.clone(),
+            disk_chunk_caches: self.disk_chunk_caches.clone(),
+        }
+    }
+
+    /// The requests sent by this client and its clones, but not by the other clients attached to
+    /// the same [`SharedNetwork`].
+    pub fn request_stats(&self) -> RequestStats {
+        self.network.request_stats()
+    }
+}
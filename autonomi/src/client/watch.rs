@@ -0,0 +1,287 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::data_types::graph::GraphError;
+use crate::client::data_types::pointer::{Pointer, PointerAddress, PointerError, PointerTarget};
+use crate::client::data_types::scratchpad::{Scratchpad, ScratchpadAddress, ScratchpadError};
+use crate::client::high_level::register::{RegisterAddress, RegisterError, RegisterValue};
+use crate::client::{Client, GetError};
+use futures::Stream;
+use std::future::Future;
+use std::time::Duration;
+
+/// Bounds of the polling interval of the watch streams, see [`Client::watch_pointer`].
+///
+/// Polling starts at `min` and slows down up to `max` while nothing changes, going back to `min`
+/// as soon as a new version shows up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchInterval {
+    /// Interval right after a change
+    pub min: Duration,
+    /// Interval after a long time without changes
+    pub max: Duration,
+}
+
+impl Default for WatchInterval {
+    fn default() -> Self {
+        Self {
+            min: Duration::from_secs(1),
+            max: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A change seen by a watch stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent<T> {
+    /// A new version, with a counter above the previous one
+    Update(T),
+    /// Conflicting versions sharing the highest counter, sorted, to be resolved by their owner
+    Fork(Vec<T>),
+}
+
+/// A version fetched by a poll, along with its counter.
+struct Observed<T> {
+    counter: u64,
+    event: WatchEvent<T>,
+}
+
+impl<T: Ord> Observed<T> {
+    fn update(counter: u64, value: T) -> Self {
+        Self {
+            counter,
+            event: WatchEvent::Update(value),
+        }
+    }
+
+    fn fork(counter: u64, mut values: Vec<T>) -> Self {
+        values.sort();
+        Self {
+            counter,
+            event: WatchEvent::Fork(values),
+        }
+    }
+}
+
+struct Watcher<T, F> {
+    poll: F,
+    interval: WatchInterval,
+    delay: Option<Duration>,
+    last: Option<Observed<T>>,
+}
+
+impl<T: PartialEq + Clone, F> Watcher<T, F> {
+    /// The event to send for the version, if it was not sent already.
+    ///
+    /// Versions with a lower counter than the last one sent come from nodes lagging behind and are
+    /// ignored, while another version with the same counter is a fork.
+    fn observe(&mut self, observed: Observed<T>) -> Option<WatchEvent<T>> {
+        let is_new = match &self.last {
+            None => true,
+            Some(last) if observed.counter > last.counter => true,
+            Some(last) if observed.counter == last.counter => observed.event != last.event,
+            Some(_) => false,
+        };
+        if !is_new {
+            return None;
+        }
+        let event = observed.event.clone();
+        self.last = Some(observed);
+        Some(event)
+    }
+
+    fn slow_down(&mut self) {
+        let delay = self.delay.unwrap_or(self.interval.min);
+        self.delay = Some((delay * 2).min(self.interval.max));
+    }
+}
+
+/// Poll until a new version shows up, the poll being given the counter of the last version sent.
+///
+/// Errors are sent on the stream without ending it, as they are usually transient.
+fn watch<T, E, F, Fut>(
+    interval: WatchInterval,
+    poll: F,
+) -> impl Stream<Item = Result<WatchEvent<T>, E>>
+where
+    T: PartialEq + Clone,
+    F: Fn(Option<u64>) -> Fut,
+    Fut: Future<Output = Result<Option<Observed<T>>, E>>,
+{
+    let watcher = Watcher {
+        poll,
+        interval,
+        delay: None,
+        last: None,
+    };
+    futures::stream::unfold(watcher, |mut watcher| async move {
+        loop {
+            if let Some(delay) = watcher.delay {
+                tokio::time::sleep(delay).await;
+            }
+            let last_counter = watcher.last.as_ref().map(|last| last.counter);
+            match (watcher.poll)(last_counter).await {
+                Ok(Some(observed)) => {
+                    if let Some(event) = watcher.observe(observed) {
+                        watcher.delay = Some(watcher.interval.min);
+                        return Some((Ok(event), watcher));
+                    }
+                    watcher.slow_down();
+                }
+                Ok(None) => watcher.slow_down(),
+                Err(err) => {
+                    watcher.slow_down();
+                    return Some((Err(err), watcher));
+                }
+            }
+        }
+    })
+}
+
+impl Client {
+    /// Watch a pointer, getting its current version, then each new version as it shows up.
+    ///
+    /// The pointer is polled at an interval adapting to how often it changes, within
+    /// [`crate::ClientOperatingStrategy::watch_interval`]. Outdated and already seen versions are
+    /// skipped, and forks are sent as [`WatchEvent::Fork`]. Nothing is sent until the pointer
+    /// exists. The stream never ends, drop it to stop watching.
+    pub fn watch_pointer(
+        &self,
+        address: PointerAddress,
+    ) -> impl Stream<Item = Result<WatchEvent<Pointer>, PointerError>> + use<> {
+        let client = self.clone();
+        watch(self.config.watch_interval, move |_| {
+            let client = client.clone();
+            async move {
+                match client.pointer_get(&address).await {
+                    Ok(pointer) => Ok(Some(Observed::update(pointer.counter(), pointer))),
+                    Err(PointerError::Fork(pointers)) => {
+                        let counter = pointers.iter().map(Pointer::counter).max();
+                        Ok(counter.map(|counter| Observed::fork(counter, pointers)))
+                    }
+                    Err(PointerError::GetError(GetError::RecordNotFound)) => Ok(None),
+                    Err(err) => Err(err),
+                }
+            }
+        })
+    }
+
+    /// Watch a scratchpad, getting its current version, then each new version as it shows up.
+    ///
+    /// See [`Client::watch_pointer`] for how the scratchpad is polled.
+    pub fn watch_scratchpad(
+        &self,
+        address: ScratchpadAddress,
+    ) -> impl Stream<Item = Result<WatchEvent<Scratchpad>, ScratchpadError>> + use<> {
+        let client = self.clone();
+        watch(self.config.watch_interval, move |_| {
+            let client = client.clone();
+            async move {
+                match client.scratchpad_get(&address).await {
+                    Ok(scratchpad) => Ok(Some(Observed::update(scratchpad.counter(), scratchpad))),
+                    Err(ScratchpadError::Fork(scratchpads)) => {
+                        let counter = scratchpads.iter().map(Scratchpad::counter).max();
+                        Ok(counter.map(|counter| Observed::fork(counter, scratchpads)))
+                    }
+                    Err(ScratchpadError::NotFound(_)) => Ok(None),
+                    Err(err) => Err(err),
+                }
+            }
+        })
+    }
+
+    /// Watch a register, getting its current value, then each new value as it shows up.
+    ///
+    /// Only the head pointer of the register is polled while it does not change, see
+    /// [`Client::watch_pointer`]. Concurrent values are sent as [`WatchEvent::Fork`].
+    pub fn watch_register(
+        &self,
+        address: RegisterAddress,
+    ) -> impl Stream<Item = Result<WatchEvent<RegisterValue>, RegisterError>> + use<> {
+        let client = self.clone();
+        watch(self.config.watch_interval, move |last_counter| {
+            let client = client.clone();
+            async move {
+                let head = match client
+                    .pointer_get(&address.to_underlying_head_pointer())
+                    .await
+                {
+                    Ok(head) => head,
+                    Err(PointerError::GetError(GetError::RecordNotFound)) => return Ok(None),
+                    Err(err) => return Err(err.into()),
+                };
+                if Some(head.counter()) == last_counter {
+                    return Ok(None);
+                }
+
+                let entry_addr = match head.target() {
+                    PointerTarget::GraphEntryAddress(addr) => addr,
+                    other => return Err(RegisterError::InvalidHeadPointer(other.clone())),
+                };
+                match client.graph_entry_get(entry_addr).await {
+                    Ok(entry) => Ok(Some(Observed::update(head.counter(), entry.content))),
+                    Err(GraphError::Fork(entries)) => {
+                        let values = entries.iter().map(|entry| entry.content).collect();
+                        Ok(Some(Observed::fork(head.counter(), values)))
+                    }
+                    Err(err) => Err(err.into()),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watcher() -> Watcher<&'static str, ()> {
+        Watcher {
+            poll: (),
+            interval: WatchInterval::default(),
+            delay: None,
+            last: None,
+        }
+    }
+
+    #[test]
+    fn test_watch_dedup_and_forks() {
+        let mut watcher = watcher();
+        assert_eq!(
+            watcher.observe(Observed::update(1, "a")),
+            Some(WatchEvent::Update("a"))
+        );
+        // the same version, or an outdated one, is not sent again
+        assert_eq!(watcher.observe(Observed::update(1, "a")), None);
+        assert_eq!(watcher.observe(Observed::update(0, "z")), None);
+
+        // another version with the same counter reveals a fork
+        assert_eq!(
+            watcher.observe(Observed::fork(1, vec!["b", "a"])),
+            Some(WatchEvent::Fork(vec!["a", "b"]))
+        );
+        assert_eq!(watcher.observe(Observed::fork(1, vec!["a", "b"])), None);
+
+        assert_eq!(
+            watcher.observe(Observed::update(2, "c")),
+            Some(WatchEvent::Update("c"))
+        );
+    }
+
+    #[test]
+    fn test_watch_slows_down() {
+        let mut watcher = watcher();
+        let WatchInterval { min, max } = watcher.interval;
+        watcher.slow_down();
+        assert_eq!(watcher.delay, Some(min * 2));
+        for _ in 0..10 {
+            watcher.slow_down();
+        }
+        assert_eq!(watcher.delay, Some(max));
+    }
+}
@@ -101,12 +101,15 @@ pub use bytes::Bytes;
 pub use libp2p::Multiaddr;
 
 // private helper modules
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub(crate) mod utils;
 
 #[doc(inline)]
 pub use client::{
     // Client
     Client,
+    SharedNetwork,
     // Client Configs
     config::Bootstrap,
     config::BootstrapConfig,
@@ -0,0 +1,145 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::networking::interface::NetworkTask;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Number of requests of a kind sent through a [`crate::networking::Network`] handle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestCount {
+    /// Requests sent to the network, including those whose caller stopped waiting for them
+    pub sent: u64,
+    /// Requests that ended with an error
+    pub failed: u64,
+}
+
+impl RequestCount {
+    fn add(self, other: Self) -> Self {
+        Self {
+            sent: self.sent + other.sent,
+            failed: self.failed + other.failed,
+        }
+    }
+}
+
+/// Requests sent through a [`crate::networking::Network`] handle, see [`crate::networking::Network::request_stats`].
+///
+/// Each client attached to a [`crate::client::SharedNetwork`] has its own stats, although they all
+/// send their requests through the same network driver.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestStats {
+    pub get_closest_peers: RequestCount,
+    pub get_record: RequestCount,
+    /// Each peer a record is sent to counts as one request
+    pub put_record: RequestCount,
+    pub get_quote: RequestCount,
+    pub get_record_from_peer: RequestCount,
+    pub get_version: RequestCount,
+}
+
+impl RequestStats {
+    /// All the requests, regardless of their kind.
+    pub fn total(&self) -> RequestCount {
+        [
+            self.get_closest_peers,
+            self.get_record,
+            self.put_record,
+            self.get_quote,
+            self.get_record_from_peer,
+            self.get_version,
+        ]
+        .into_iter()
+        .fold(RequestCount::default(), RequestCount::add)
+    }
+
+    fn count_mut(&mut self, kind: RequestKind) -> &mut RequestCount {
+        match kind {
+            RequestKind::GetClosestPeers => &mut self.get_closest_peers,
+            RequestKind::GetRecord => &mut self.get_record,
+            RequestKind::PutRecord => &mut self.put_record,
+            RequestKind::GetQuote => &mut self.get_quote,
+            RequestKind::GetRecordFromPeer => &mut self.get_record_from_peer,
+            RequestKind::GetVersion => &mut self.get_version,
+        }
+    }
+}
+
+/// Kinds of requests counted in [`RequestStats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RequestKind {
+    GetClosestPeers,
+    GetRecord,
+    PutRecord,
+    GetQuote,
+    GetRecordFromPeer,
+    GetVersion,
+}
+
+impl RequestKind {
+    /// The kind of request sent with the task, if it sends any to the network.
+    pub fn of(task: &NetworkTask) -> Option<Self> {
+        match task {
+            NetworkTask::GetClosestPeers { .. } => Some(Self::GetClosestPeers),
+            NetworkTask::GetRecord { .. } => Some(Self::GetRecord),
+            NetworkTask::PutRecordKad { .. } | NetworkTask::PutRecordReq { .. } => {
+                Some(Self::PutRecord)
+            }
+            NetworkTask::GetQuote { .. } => Some(Self::GetQuote),
+            NetworkTask::GetRecordFromPeer { .. } => Some(Self::GetRecordFromPeer),
+            NetworkTask::GetVersion { .. } => Some(Self::GetVersion),
+            NetworkTask::ConnectionsMade { .. } => None,
+        }
+    }
+}
+
+/// Counts the requests sent through a [`crate::networking::Network`] handle and the clones sharing its accounting.
+#[derive(Debug, Default)]
+pub(crate) struct RequestAccounting {
+    stats: Mutex<RequestStats>,
+}
+
+impl RequestAccounting {
+    fn stats(&self) -> MutexGuard<'_, RequestStats> {
+        // the stats stay consistent even if a panic occurred while holding the lock
+        self.stats.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn snapshot(&self) -> RequestStats {
+        self.stats().clone()
+    }
+
+    /// Count a request as soon as it is sent, before its outcome is known.
+    pub fn record_sent(&self, kind: RequestKind) {
+        self.stats().count_mut(kind).sent += 1;
+    }
+
+    /// Count a request sent earlier as failed.
+    pub fn record_failed(&self, kind: RequestKind) {
+        self.stats().count_mut(kind).failed += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_accounting() {
+        let accounting = RequestAccounting::default();
+        accounting.record_sent(RequestKind::GetQuote);
+        accounting.record_sent(RequestKind::GetQuote);
+        accounting.record_failed(RequestKind::GetQuote);
+        accounting.record_sent(RequestKind::PutRecord);
+
+        let stats = accounting.snapshot();
+        assert_eq!(stats.get_quote, RequestCount { sent: 2, failed: 1 });
+        assert_eq!(stats.put_record, RequestCount { sent: 1, failed: 0 });
+        assert_eq!(stats.get_record, RequestCount::default());
+        assert_eq!(stats.total(), RequestCount { sent: 3, failed: 1 });
+    }
+}
@@ -7,10 +7,15 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use exponential_backoff::Backoff;
+use libp2p::PeerId;
+use libp2p::kad::PeerInfo;
+use rand::Rng;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
-use std::time::Duration;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
 
-use super::Quorum;
+use super::{NetworkError, Quorum};
 
 /// A strategy that translates into a configuration for exponential backoff.
 /// The first retry is done after 2 seconds, after which the backoff is roughly doubled each time.
@@ -71,3 +76,331 @@ pub struct Strategy {
     /// The retry strategy to use if the get operation fails
     pub get_retry: RetryStrategy,
 }
+
+/// How a [`NetworkError`] is handled by a [`RetryPolicy`], see [`NetworkError::class`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Retrying cannot help, e.g. the network driver is offline or the record is outdated
+    Fatal,
+    /// Peers did not answer in time, the network or our connection is congested
+    Timeout,
+    /// Not enough peers answered successfully, other peers may do better
+    NotEnoughResponses,
+    /// Peers refused the request, retrying is unlikely to help
+    Rejected,
+    /// The record was not found, it might still be replicating
+    NotFound,
+    /// Any other error
+    Other,
+}
+
+/// Decides when to retry failed network requests, and which peers to send them to.
+///
+/// The number of attempts is bound by the [`RetryStrategy`] of the [`Strategy`] in use,
+/// the policy may give up earlier.
+pub trait RetryPolicy: Send + Sync + Debug {
+    /// Delay before the next attempt after `attempt` failed attempts out of `max_attempts`,
+    /// or `None` to give up. `error` is `None` when the record was not found.
+    fn retry_delay(
+        &self,
+        attempt: usize,
+        max_attempts: usize,
+        error: Option<&NetworkError>,
+    ) -> Option<Duration>;
+
+    /// Record how long a peer took to answer a request, and whether it succeeded.
+    fn record_response(&self, _peer: PeerId, _elapsed: Duration, _success: bool) {}
+
+    /// Select the peers to ask for quotes, keeping at least `needed` of them.
+    ///
+    /// Records are always sent to all the peers paid to store them, whatever the policy.
+    fn select_peers(&self, peers: Vec<PeerInfo>, _needed: usize) -> Vec<PeerInfo> {
+        peers
+    }
+}
+
+/// The exponential backoff of [`RetryStrategy`] applied to all errors, sending requests to all peers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedRetryPolicy;
+
+impl RetryPolicy for FixedRetryPolicy {
+    fn retry_delay(
+        &self,
+        attempt: usize,
+        max_attempts: usize,
+        error: Option<&NetworkError>,
+    ) -> Option<Duration> {
+        if error.is_some_and(NetworkError::cannot_retry) {
+            return None;
+        }
+        RetryStrategy::N(max_attempts)
+            .backoff()
+            .into_iter()
+            .nth(attempt.checked_sub(1)?)
+            .flatten()
+    }
+}
+
+/// Number of response times kept per peer.
+const LATENCY_SAMPLES: usize = 32;
+/// Number of response times needed before judging a peer slow.
+const MIN_LATENCY_SAMPLES: usize = 5;
+/// Peers whose 90th percentile response time is this many times the median response time are slow.
+const SLOW_PEER_FACTOR: u32 = 3;
+/// Peers answering within this time are never considered slow.
+const MIN_SLOW_PEER_LATENCY: Duration = Duration::from_secs(2);
+/// Peers failing this many requests in a row are excluded.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+/// How long failing peers are excluded before getting another chance.
+const PEER_EXCLUSION_PERIOD: Duration = Duration::from_secs(300);
+/// Maximum delay between two attempts, timeouts backing off twice as far.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(8);
+
+/// Response times observed for a peer, see [`AdaptiveRetryPolicy::peer_stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerLatencyStats {
+    /// Number of response times recorded
+    pub samples: usize,
+    /// Median response time
+    pub p50: Duration,
+    /// 90th percentile response time
+    pub p90: Duration,
+    /// Number of requests failed in a row
+    pub consecutive_failures: u32,
+    /// Whether the peer is excluded from the requests for failing too often
+    pub excluded: bool,
+}
+
+#[derive(Debug, Default)]
+struct PeerRecord {
+    latencies: VecDeque<Duration>,
+    consecutive_failures: u32,
+    excluded_until: Option<Instant>,
+}
+
+impl PeerRecord {
+    fn percentile(&self, percentile: f64) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<_> = self.latencies.iter().copied().collect();
+        sorted.sort();
+        let index = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+        sorted.get(index).copied()
+    }
+
+    fn is_excluded(&self, now: Instant) -> bool {
+        self.excluded_until.is_some_and(|until| until > now)
+    }
+}
+
+/// The default [`RetryPolicy`], adapting to the errors and to the peers' response times.
+///
+/// - Fatal errors are not retried and rejections only once. Timeouts back off further than
+///   missing responses, which are quickly retried with other peers.
+/// - Delays are jittered so that concurrent requests failing together don't retry together.
+/// - Peers failing several requests in a row are excluded for a while, and peers much slower than
+///   the others are not asked for quotes, as long as enough peers remain for the request.
+#[derive(Debug, Default)]
+pub struct AdaptiveRetryPolicy {
+    peers: Mutex<HashMap<PeerId, PeerRecord>>,
+}
+
+impl AdaptiveRetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Response times observed for a peer, if it answered any request.
+    pub fn peer_stats(&self, peer: &PeerId) -> Option<PeerLatencyStats> {
+        let peers = self.peers();
+        let record = peers.get(peer)?;
+        Some(PeerLatencyStats {
+            samples: record.latencies.len(),
+            p50: record.percentile(0.5).unwrap_or_default(),
+            p90: record.percentile(0.9).unwrap_or_default(),
+            consecutive_failures: record.consecutive_failures,
+            excluded: record.is_excluded(Instant::now()),
+        })
+    }
+
+    fn peers(&self) -> MutexGuard<'_, HashMap<PeerId, PeerRecord>> {
+        // the records stay consistent even if a panic occurred while holding the lock
+        self.peers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Median response time across peers, to compare each peer with.
+    fn typical_latency(peers: &HashMap<PeerId, PeerRecord>) -> Option<Duration> {
+        let mut medians: Vec<Duration> = peers
+            .values()
+            .filter(|record| record.latencies.len() >= MIN_LATENCY_SAMPLES)
+            .filter_map(|record| record.percentile(0.5))
+            .collect();
+        medians.sort();
+        medians.get(medians.len() / 2).copied()
+    }
+}
+
+impl RetryPolicy for AdaptiveRetryPolicy {
+    fn retry_delay(
+        &self,
+        attempt: usize,
+        max_attempts: usize,
+        error: Option<&NetworkError>,
+    ) -> Option<Duration> {
+        if attempt == 0 || attempt >= max_attempts {
+            return None;
+        }
+        let class = error
+            .map(NetworkError::class)
+            .unwrap_or(ErrorClass::NotFound);
+        let (base, max) = match class {
+            ErrorClass::Fatal => return None,
+            ErrorClass::Rejected if attempt > 1 => return None,
+            ErrorClass::Timeout => (Duration::from_secs(4), MAX_RETRY_DELAY * 2),
+            ErrorClass::NotEnoughResponses => (Duration::from_secs(1), MAX_RETRY_DELAY),
+            ErrorClass::Rejected | ErrorClass::NotFound | ErrorClass::Other => {
+                (Duration::from_secs(2), MAX_RETRY_DELAY)
+            }
+        };
+        let exponent = (attempt - 1).min(16) as u32;
+        let delay = base.saturating_mul(1 << exponent).min(max);
+
+        // wait between half and all of the delay
+        let half = delay / 2;
+        let jitter = rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+        Some(half + Duration::from_millis(jitter))
+    }
+
+    fn record_response(&self, peer: PeerId, elapsed: Duration, success: bool) {
+        let mut peers = self.peers();
+        let record = peers.entry(peer).or_default();
+        if success {
+            // failures can be quick rejections or timeouts, only successes tell how fast a peer is
+            if record.latencies.len() == LATENCY_SAMPLES {
+                record.latencies.pop_front();
+            }
+            record.latencies.push_back(elapsed);
+            record.consecutive_failures = 0;
+            record.excluded_until = None;
+        } else {
+            record.consecutive_failures += 1;
+            if record.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                debug!(
+                    "Excluding peer {peer:?} after {MAX_CONSECUTIVE_FAILURES} failures in a row"
+                );
+                record.excluded_until = Some(Instant::now() + PEER_EXCLUSION_PERIOD);
+            }
+        }
+    }
+
+    fn select_peers(&self, peers: Vec<PeerInfo>, needed: usize) -> Vec<PeerInfo> {
+        let removable = peers.len().saturating_sub(needed);
+        if removable == 0 {
+            return peers;
+        }
+
+        let mut records = self.peers();
+        let now = Instant::now();
+        let slow_latency = Self::typical_latency(&records)
+            .map(|typical| (typical * SLOW_PEER_FACTOR).max(MIN_SLOW_PEER_LATENCY));
+
+        // excluded peers first, then the slowest ones
+        let mut unwanted: Vec<(bool, Duration, PeerId)> = vec![];
+        for peer in peers.iter() {
+            let Some(record) = records.get_mut(&peer.peer_id) else {
+                continue;
+            };
+            if record.excluded_until.is_some_and(|until| until <= now) {
+                // give the peer another chance
+                record.consecutive_failures = 0;
+                record.excluded_until = None;
+            }
+            let p90 = record.percentile(0.9).unwrap_or_default();
+            let slow = record.latencies.len() >= MIN_LATENCY_SAMPLES
+                && slow_latency.is_some_and(|slow_latency| p90 > slow_latency);
+            if record.is_excluded(now) || slow {
+                unwanted.push((record.is_excluded(now), p90, peer.peer_id));
+            }
+        }
+        unwanted.sort_by(|a, b| b.cmp(a));
+        unwanted.truncate(removable);
+
+        if !unwanted.is_empty() {
+            debug!(
+                "Leaving out {} failing or slow peers: {:?}",
+                unwanted.len(),
+                unwanted.iter().map(|(_, _, peer)| peer).collect::<Vec<_>>()
+            );
+        }
+        peers
+            .into_iter()
+            .filter(|peer| !unwanted.iter().any(|(_, _, id)| *id == peer.peer_id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer() -> PeerInfo {
+        PeerInfo {
+            peer_id: PeerId::random(),
+            addrs: vec![],
+        }
+    }
+
+    #[test]
+    fn test_adaptive_retry_delay() {
+        let policy = AdaptiveRetryPolicy::new();
+        let timeout = NetworkError::GetRecordTimeout(vec![]);
+        let offline = NetworkError::NetworkDriverOffline;
+        let rejected = NetworkError::PutRecordRejected("no".to_string());
+
+        let delay = policy.retry_delay(1, 4, Some(&timeout)).expect("retried");
+        assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(4));
+        let delay = policy.retry_delay(3, 4, Some(&timeout)).expect("retried");
+        assert!(delay >= Duration::from_secs(8) && delay <= Duration::from_secs(16));
+        assert_eq!(policy.retry_delay(4, 4, Some(&timeout)), None);
+
+        assert_eq!(policy.retry_delay(1, 4, Some(&offline)), None);
+        assert!(policy.retry_delay(1, 4, Some(&rejected)).is_some());
+        assert_eq!(policy.retry_delay(2, 4, Some(&rejected)), None);
+    }
+
+    #[test]
+    fn test_adaptive_peer_selection() {
+        let policy = AdaptiveRetryPolicy::new();
+        let peers: Vec<PeerInfo> = (0..7).map(|_| peer()).collect();
+        let [fast @ .., slow, failing] = &peers[..] else {
+            panic!("7 peers");
+        };
+
+        for _ in 0..MIN_LATENCY_SAMPLES {
+            for peer in fast {
+                policy.record_response(peer.peer_id, Duration::from_millis(500), true);
+            }
+            policy.record_response(slow.peer_id, Duration::from_secs(10), true);
+        }
+        for _ in 0..MAX_CONSECUTIVE_FAILURES {
+            policy.record_response(failing.peer_id, Duration::from_secs(1), false);
+        }
+        let stats = policy.peer_stats(&failing.peer_id).expect("recorded");
+        assert!(stats.excluded);
+        assert_eq!(stats.consecutive_failures, MAX_CONSECUTIVE_FAILURES);
+        // failed requests don't count as response times
+        assert_eq!(stats.samples, 0);
+
+        let selected = policy.select_peers(peers.clone(), 5);
+        assert_eq!(selected, fast.to_vec());
+
+        // the failing peer is left out first, keeping the needed peers
+        let selected = policy.select_peers(peers.clone(), 6);
+        assert_eq!(selected.len(), 6);
+        assert!(selected.contains(slow));
+        assert!(!selected.contains(failing));
+
+        assert_eq!(policy.select_peers(peers.clone(), 7), peers);
+    }
+}
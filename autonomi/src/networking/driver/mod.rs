@@ -11,10 +11,12 @@ mod swarm_events;
 mod task_handler;
 
 use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::{num::NonZeroUsize, time::Duration};
 
 use crate::networking::NetworkError;
 use crate::networking::interface::NetworkTask;
+use crate::networking::reputation::{PERSIST_INTERVAL, PeerReputations};
 use ant_bootstrap::bootstrap::Bootstrap;
 use ant_protocol::NetworkAddress;
 use ant_protocol::version::IDENTIFY_PROTOCOL_STR;
@@ -82,6 +84,8 @@ pub(crate) struct NetworkDriver {
     pending_tasks: TaskHandler,
     /// Count of connections established to peers. Can be used to determine if we are a 'connected' client.
     connections_made: usize,
+    /// Reputation of the peers, updated with the outcome of our requests and shared with the [`crate::Network`]
+    reputation: Arc<PeerReputations>,
 }
 
 #[derive(NetworkBehaviour)]
@@ -95,7 +99,11 @@ pub(crate) struct AutonomiClientBehaviour {
 
 impl NetworkDriver {
     /// Create a new network runner
-    pub fn new(bootstrap: Bootstrap, task_receiver: mpsc::Receiver<NetworkTask>) -> Self {
+    pub fn new(
+        bootstrap: Bootstrap,
+        task_receiver: mpsc::Receiver<NetworkTask>,
+        reputation: Arc<PeerReputations>,
+    ) -> Self {
         // random new client id
         let keypair = Keypair::generate_ed25519();
         let peer_id = PeerId::from(keypair.public());
@@ -201,6 +209,7 @@ impl NetworkDriver {
             task_receiver,
             pending_tasks: task_handler,
             connections_made: 0,
+            reputation,
         };
 
         driver.bootstrap_network();
@@ -211,6 +220,7 @@ impl NetworkDriver {
     /// Run the network runner, loops forever waiting for tasks and processing them
     pub async fn run(mut self) {
         let mut bootstrap_interval = Some(tokio::time::interval(BOOTSTRAP_CHECK_INTERVAL));
+        let mut persist_interval = tokio::time::interval(PERSIST_INTERVAL);
         loop {
             tokio::select! {
                 // tasks sent by client
@@ -219,6 +229,9 @@ impl NetworkDriver {
                         Some(task) => self.process_task(task),
                         None => {
                             info!("Task receiver closed, exiting");
+                            if let Err(e) = self.reputation.persist() {
+                                warn!("Failed to persist peer reputations: {e}");
+                            }
                             break;
                         }
                     }
@@ -241,6 +254,16 @@ impl NetworkDriver {
                         bootstrap_interval = None;
                     }
                 }
+                // write the peer reputations to disk off the driver loop
+                _ = persist_interval.tick() => {
+                    let reputation = Arc::clone(&self.reputation);
+                    #[allow(clippy::let_underscore_future)]
+                    let _ = tokio::task::spawn_blocking(move || {
+                        if let Err(e) = reputation.persist() {
+                            warn!("Failed to persist peer reputations: {e}");
+                        }
+                    });
+                }
             }
         }
     }
@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::networking::reputation::PeerIssue;
 use ant_protocol::messages::{QueryResponse, Response};
 use libp2p::autonat::OutboundFailure;
 use libp2p::kad::{Event as KadEvent, ProgressStep, QueryId, QueryResult, QueryStats};
@@ -40,10 +41,10 @@ impl NetworkDriver {
                             request_id,
                             response,
                         },
-                    peer: _,
+                    peer,
                     connection_id: _,
                 },
-            )) => self.handle_request_resp_event(request_id, response),
+            )) => self.handle_request_resp_event(peer, request_id, response),
             SwarmEvent::Behaviour(AutonomiClientBehaviourEvent::RequestResponse(
                 ReqEvent::OutboundFailure {
                     peer,
@@ -158,6 +159,7 @@ impl NetworkDriver {
 
     fn handle_request_resp_event(
         &mut self,
+        peer: PeerId,
         request_id: OutboundRequestId,
         response: Response,
     ) -> Result<(), NetworkDriverError> {
@@ -175,18 +177,32 @@ impl NetworkDriver {
                 peer_address,
                 storage_proofs: _,
             }) => {
-                self.pending_tasks
+                let valid = self
+                    .pending_tasks
                     .update_get_quote(request_id, quote, peer_address)?;
+                if valid {
+                    self.reputation.record_success(peer);
+                } else {
+                    self.reputation.record_issue(peer, PeerIssue::InvalidQuote);
+                }
             }
             Response::Query(QueryResponse::PutRecord {
                 result,
                 peer_address: _,
                 record_addr: _,
             }) => {
+                match &result {
+                    Ok(()) => self.reputation.record_success(peer),
+                    // the peer holds a newer version, it is not to blame for that
+                    Err(ant_protocol::error::Error::OutdatedRecordCounter { .. })
+                    | Err(ant_protocol::error::Error::UnexpectedRecordCounter { .. }) => {}
+                    Err(_) => self.reputation.record_issue(peer, PeerIssue::FailedToStore),
+                }
                 self.pending_tasks
                     .update_put_record_req(request_id, result)?;
             }
             Response::Query(QueryResponse::GetVersion { peer: _, version }) => {
+                self.reputation.record_success(peer);
                 self.pending_tasks.update_get_version(request_id, version)?;
             }
             Response::Query(QueryResponse::GetReplicatedRecord(result)) => {
@@ -274,6 +290,16 @@ impl NetworkDriver {
     ) -> Result<(), NetworkDriverError> {
         trace!("Request response outbound failure: {:?}", error);
 
+        // peers that could not be reached, as opposed to peers running another protocol version
+        if matches!(
+            error,
+            OutboundFailure::Timeout
+                | OutboundFailure::DialFailure
+                | OutboundFailure::ConnectionClosed
+        ) {
+            self.reputation.record_issue(peer, PeerIssue::Unresponsive);
+        }
+
         // skip unknown or completed queries
         if !self.pending_tasks.contains_query(&request_id) {
             trace!("Ignore result for unknown query (possibly already completed): {request_id:?}");
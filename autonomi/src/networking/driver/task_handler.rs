@@ -352,12 +352,13 @@ impl TaskHandler {
         Ok(())
     }
 
+    /// Returns whether the quote was valid, for the reputation of the peer.
     pub fn update_get_quote(
         &mut self,
         id: OutboundRequestId,
         quote_res: Result<PaymentQuote, ant_protocol::error::Error>,
         peer_address: NetworkAddress,
-    ) -> Result<(), TaskHandlerError> {
+    ) -> Result<bool, TaskHandlerError> {
         let (resp, data_type, peer) =
             self.get_cost
                 .remove(&id)
@@ -365,31 +366,29 @@ impl TaskHandler {
                     "OutboundRequestId {id:?}"
                 )))?;
 
-        match verify_quote(quote_res, peer_address.clone(), data_type) {
+        let result = match verify_quote(quote_res, peer_address.clone(), data_type) {
             Ok(Some(quote)) => {
                 trace!("OutboundRequestId({id}): got quote from peer {peer_address:?}");
-                // Send can fail here if we already accumulated enough quotes.
-                resp.send(Ok(Some((peer, quote))))
-                    .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
-                Ok(())
+                Ok(Some((peer, quote)))
             }
             Ok(None) => {
                 trace!(
                     "OutboundRequestId({id}): no quote needed as record already exists at peer {peer_address:?}"
                 );
-                // Send can fail here if we already accumulated enough quotes.
-                resp.send(Ok(None))
-                    .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
-                Ok(())
+                Ok(None)
             }
             Err(e) => {
                 warn!("OutboundRequestId({id}): got invalid quote from peer {peer_address:?}: {e}");
-                // Send can fail here if we already accumulated enough quotes.
-                resp.send(Err(e))
-                    .map_err(|_| TaskHandlerError::NetworkClientDropped(format!("{id:?}")))?;
-                Ok(())
+                Err(e)
             }
+        };
+        let valid = result.is_ok();
+
+        // Send can fail here if we already accumulated enough quotes.
+        if resp.send(result).is_err() {
+            trace!("OutboundRequestId({id}): quote arrived after enough quotes were gathered");
         }
+        Ok(valid)
     }
 
     pub fn update_get_version(
@@ -7,10 +7,12 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 // all modules are private to this networking module
+mod accounting;
 pub(crate) mod common;
 mod config;
 mod driver;
 mod interface;
+mod reputation;
 mod retries;
 mod utils;
 pub mod version;
@@ -21,26 +23,33 @@ use ant_bootstrap::bootstrap::Bootstrap;
 pub(crate) use utils::multiaddr_is_global;
 
 // re-export the types our API exposes to avoid dependency version conflicts
+pub use accounting::{RequestCount, RequestStats};
 pub use ant_evm::PaymentQuote;
 pub use ant_protocol::NetworkAddress;
-pub use config::{RetryStrategy, Strategy};
+pub use config::{
+    AdaptiveRetryPolicy, ErrorClass, FixedRetryPolicy, PeerLatencyStats, RetryPolicy,
+    RetryStrategy, Strategy,
+};
 pub use libp2p::kad::PeerInfo;
 pub use libp2p::{
     Multiaddr, PeerId,
     kad::{Quorum, Record},
 };
+pub use reputation::{PeerIssue, PeerReputation};
 
 // internal needs
 use crate::networking::version::PackageVersion;
+use accounting::{RequestAccounting, RequestKind};
 use ant_protocol::{CLOSE_GROUP_SIZE, PrettyPrintRecordKey};
 use driver::NetworkDriver;
 use futures::stream::{FuturesUnordered, StreamExt};
 use interface::NetworkTask;
 use libp2p::kad::NoKnownPeers;
+use reputation::PeerReputations;
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::sync::{mpsc, oneshot};
 use tokio::time::{sleep, timeout};
@@ -149,12 +158,38 @@ impl NetworkError {
     pub fn cannot_retry(&self) -> bool {
         matches!(self, NetworkError::OutdatedRecordRejected { .. }) || self.is_fatal()
     }
+
+    /// How the error is handled by a [`RetryPolicy`]
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            _ if self.cannot_retry() => ErrorClass::Fatal,
+            NetworkError::InvalidNonZeroUsize(_)
+            | NetworkError::PutRecordMissingTargets
+            | NetworkError::SplitRecord(_)
+            | NetworkError::InvalidRetryStrategy => ErrorClass::Fatal,
+            NetworkError::GetClosestPeersTimeout
+            | NetworkError::PutRecordTimeout(_)
+            | NetworkError::GetRecordTimeout(_) => ErrorClass::Timeout,
+            NetworkError::InsufficientPeers { .. }
+            | NetworkError::PutRecordQuorumFailed(..)
+            | NetworkError::PutRecordTooManyPeerFailed(..)
+            | NetworkError::InsufficientQuotes { .. }
+            | NetworkError::GetRecordQuorumFailed { .. } => ErrorClass::NotEnoughResponses,
+            NetworkError::PutRecordRejected(_)
+            | NetworkError::PutRecordVerification(_)
+            | NetworkError::InvalidQuote(_) => ErrorClass::Rejected,
+            _ => ErrorClass::Other,
+        }
+    }
 }
 
 /// The Client interface to the Autonomi Network
 #[derive(Debug, Clone)]
 pub struct Network {
     task_sender: Arc<tokio::sync::mpsc::Sender<NetworkTask>>,
+    retry_policy: Arc<dyn RetryPolicy>,
+    reputation: Arc<PeerReputations>,
+    accounting: Arc<RequestAccounting>,
 }
 
 impl Network {
@@ -163,7 +198,10 @@ impl Network {
     /// The [`Network`] is cheaply cloneable, prefer cloning over creating new instances to avoid creating multiple network drivers
     pub fn new(bootstrap: Bootstrap) -> Result<Self, NoKnownPeers> {
         let (task_sender, task_receiver) = mpsc::channel(100);
-        let driver = NetworkDriver::new(bootstrap, task_receiver);
+        let reputation = Arc::new(PeerReputations::from_bootstrap_config(
+            bootstrap.cache_store().config(),
+        ));
+        let driver = NetworkDriver::new(bootstrap, task_receiver, Arc::clone(&reputation));
 
         // run the network driver in a background task
         tokio::spawn(async move {
@@ -172,11 +210,59 @@ impl Network {
 
         let network = Self {
             task_sender: Arc::new(task_sender),
+            retry_policy: Arc::new(AdaptiveRetryPolicy::new()),
+            reputation,
+            accounting: Default::default(),
         };
 
         Ok(network)
     }
 
+    /// Use the given [`RetryPolicy`] for the requests of this network, instead of the [`AdaptiveRetryPolicy`]
+    pub fn with_retry_policy(mut self, retry_policy: Arc<dyn RetryPolicy>) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// The [`RetryPolicy`] used for the requests of this network
+    pub fn retry_policy(&self) -> &Arc<dyn RetryPolicy> {
+        &self.retry_policy
+    }
+
+    /// A handle to the same network driver as this one, counting its requests separately.
+    ///
+    /// The handles share the connections, the routing table and the peer reputations, but not the
+    /// [`RetryPolicy`] which is reset to the default one.
+    pub fn attach(&self) -> Self {
+        Self {
+            task_sender: Arc::clone(&self.task_sender),
+            retry_policy: Arc::new(AdaptiveRetryPolicy::new()),
+            reputation: Arc::clone(&self.reputation),
+            accounting: Default::default(),
+        }
+    }
+
+    /// The requests sent through this handle and its clones, see [`Network::attach`].
+    pub fn request_stats(&self) -> RequestStats {
+        self.accounting.snapshot()
+    }
+
+    /// The reputation of the peers that misbehaved, persisted across restarts in the bootstrap cache directory.
+    /// Peers with a poor reputation are left out of quote and put requests when enough others are available.
+    pub fn peer_reputations(&self) -> HashMap<PeerId, PeerReputation> {
+        self.reputation.all()
+    }
+
+    /// The reputation of a peer, if it ever misbehaved.
+    pub fn peer_reputation(&self, peer: &PeerId) -> Option<PeerReputation> {
+        self.reputation.get(peer)
+    }
+
+    /// Lower the reputation of a peer, for misbehaviour detected outside the network layer.
+    pub fn report_peer(&self, peer: PeerId, issue: PeerIssue) {
+        self.reputation.record_issue(peer, issue);
+    }
+
     /// Wait until we made [`CLOSE_GROUP_SIZE`] connections to the network.
     pub async fn wait_for_connectivity(&self) -> Result<(), crate::client::ConnectError> {
         let timeout_duration = Duration::from_secs(CONNECT_TIMEOUT_SECS); // Total timeout
@@ -239,11 +325,42 @@ impl Network {
             quorum,
             resp: tx,
         };
-        self.task_sender
-            .send(task)
-            .await
-            .map_err(|_| NetworkError::NetworkDriverOffline)?;
-        rx.await?
+        let result = self.send_task(task, rx).await;
+        match &result {
+            Ok((Some(record), holders)) => {
+                self.check_records_from_peers(holders.iter().map(|peer| (*peer, record)))
+            }
+            Err(NetworkError::SplitRecord(records)) => {
+                self.check_records_from_peers(records.iter().map(|(peer, record)| (*peer, record)))
+            }
+            _ => {}
+        }
+        result
+    }
+
+    /// Validate the records served by peers, lowering the reputation of the peers serving invalid ones.
+    /// Each distinct record is decoded and validated once, however many peers served it.
+    fn check_records_from_peers<'a>(&self, served: impl Iterator<Item = (PeerId, &'a Record)>) {
+        let mut checked: Vec<(&Record, bool)> = vec![];
+        for (peer, record) in served {
+            let valid = match checked.iter().find(|(known, _)| *known == record) {
+                Some((_, valid)) => *valid,
+                None => {
+                    let valid = utils::record_is_valid(record);
+                    checked.push((record, valid));
+                    valid
+                }
+            };
+            if valid {
+                self.reputation.record_success(peer);
+            } else {
+                warn!(
+                    "Peer {peer:?} served an invalid record at {:?}",
+                    PrettyPrintRecordKey::from(&record.key)
+                );
+                self.reputation.record_issue(peer, PeerIssue::InvalidRecord);
+            }
+        }
     }
 
     /// Put a record to the network
@@ -271,7 +388,9 @@ impl Network {
         for peer in to {
             let record_clone = record.clone();
             tasks.push(async move {
+                let start = Instant::now();
                 let res = self.put_record_req(record_clone, peer.clone()).await;
+                self.record_response(&peer, start, &res);
                 (res, peer)
             });
         }
@@ -346,12 +465,7 @@ impl Network {
             to,
             resp: tx,
         };
-        self.task_sender
-            .send(task)
-            .await
-            .map_err(|_| NetworkError::NetworkDriverOffline)?;
-
-        rx.await?
+        self.send_task(task, rx).await
     }
 
     async fn put_record_kad(
@@ -368,12 +482,7 @@ impl Network {
             quorum,
             resp: tx,
         };
-        self.task_sender
-            .send(task)
-            .await
-            .map_err(|_| NetworkError::NetworkDriverOffline)?;
-
-        let res = rx.await?;
+        let res = self.send_task(task, rx).await;
 
         // In poor network conditions PutRecordQuorumFailed is unreliable.
         // To eliminate false positives, we do a manual record existence check after the put.
@@ -410,12 +519,7 @@ impl Network {
     ) -> Result<Vec<PeerInfo>, NetworkError> {
         let (tx, rx) = oneshot::channel();
         let task = NetworkTask::GetClosestPeers { addr, resp: tx, n };
-        self.task_sender
-            .send(task)
-            .await
-            .map_err(|_| NetworkError::NetworkDriverOffline)?;
-
-        match rx.await? {
+        match self.send_task(task, rx).await {
             Ok(mut peers) => {
                 if peers.len() < n.get() {
                     return Err(NetworkError::InsufficientPeers {
@@ -442,16 +546,17 @@ impl Network {
         peer: PeerInfo,
     ) -> Result<Option<Record>, NetworkError> {
         let (tx, rx) = oneshot::channel();
+        let peer_id = peer.peer_id;
         let task = NetworkTask::GetRecordFromPeer {
             addr,
             peer,
             resp: tx,
         };
-        self.task_sender
-            .send(task)
-            .await
-            .map_err(|_| NetworkError::NetworkDriverOffline)?;
-        rx.await?
+        let result = self.send_task(task, rx).await;
+        if let Ok(Some(record)) = &result {
+            self.check_records_from_peers(std::iter::once((peer_id, record)));
+        }
+        result
     }
 
     /// Get a quote for a record from a Peer on the Network
@@ -473,11 +578,7 @@ impl Network {
             data_size,
             resp: tx,
         };
-        self.task_sender
-            .send(task)
-            .await
-            .map_err(|_| NetworkError::NetworkDriverOffline)?;
-        rx.await?
+        self.send_task(task, rx).await
     }
 
     /// Get the quotes for a Record from the closest Peers to that address on the Network
@@ -495,6 +596,10 @@ impl Network {
         let closest_peers = self
             .get_closest_peers_with_retries(addr.clone(), None)
             .await?;
+        let closest_peers = self.reputation.select_peers(closest_peers, minimum_quotes);
+        let closest_peers = self
+            .retry_policy
+            .select_peers(closest_peers, minimum_quotes);
         let closest_peers_id = closest_peers.iter().map(|p| p.peer_id).collect::<Vec<_>>();
         debug!("Get quotes for {addr}: got closest peers: {closest_peers_id:?}");
 
@@ -503,9 +608,11 @@ impl Network {
         for peer in closest_peers {
             let addr_clone = addr.clone();
             tasks.push(async move {
+                let start = Instant::now();
                 let res = self
                     .get_quote(addr_clone, peer.clone(), data_type, data_size)
                     .await;
+                self.record_response(&peer, start, &res);
                 (res, peer)
             });
         }
@@ -547,36 +654,62 @@ impl Network {
         })
     }
 
+    /// Report the response time of a peer to the [`RetryPolicy`].
+    /// Peers running the previous protocol are not to blame for it.
+    fn record_response<T>(&self, peer: &PeerInfo, start: Instant, res: &Result<T, NetworkError>) {
+        if !matches!(res, Err(NetworkError::IncompatibleNetworkProtocol)) {
+            self.retry_policy
+                .record_response(peer.peer_id, start.elapsed(), res.is_ok());
+        }
+    }
+
     /// Request the node version of a peer on the network.
     /// Requires the node address(es) to be passed if the node is not in the local routing table.
     pub async fn get_node_version(&self, peer: PeerInfo) -> Result<PackageVersion, String> {
         let (tx, rx) = oneshot::channel();
         let task = NetworkTask::GetVersion { peer, resp: tx };
-        self.task_sender
-            .send(task)
+        let version_str = self
+            .send_task(task, rx)
             .await
-            .map_err(|_| "Network driver offline".to_string())?;
-
-        let version_string = rx
-            .await
-            .map_err(|e| format!("Failed to receive version: {e}"))?;
-
-        match version_string {
-            Ok(version_str) => PackageVersion::try_from(version_str),
-            Err(e) => Err(format!("Network error: {e}")),
-        }
+            .map_err(|e| format!("Network error: {e}"))?;
+        PackageVersion::try_from(version_str)
     }
 
     /// Get information about the routing table
     pub async fn get_connections_made(&self) -> Result<usize, NetworkError> {
         let (tx, rx) = oneshot::channel();
         let task = NetworkTask::ConnectionsMade { resp: tx };
-        self.task_sender
-            .send(task)
-            .await
-            .map_err(|_| NetworkError::NetworkDriverOffline)?;
         tracing::trace!("Waiting for connections made response");
-        rx.await?
+        self.send_task(task, rx).await
+    }
+
+    /// Send a task to the network driver and wait for its response, counting the request it sends.
+    ///
+    /// The request is counted as sent before waiting, so that it is counted even if this future is
+    /// dropped before the response comes back.
+    async fn send_task<T>(
+        &self,
+        task: NetworkTask,
+        rx: oneshot::Receiver<Result<T, NetworkError>>,
+    ) -> Result<T, NetworkError> {
+        let kind = RequestKind::of(&task);
+        if let Some(kind) = kind {
+            self.accounting.record_sent(kind);
+        }
+        let res = async {
+            self.task_sender
+                .send(task)
+                .await
+                .map_err(|_| NetworkError::NetworkDriverOffline)?;
+            rx.await?
+        }
+        .await;
+        if let Some(kind) = kind
+            && res.is_err()
+        {
+            self.accounting.record_failed(kind);
+        }
+        res
     }
 }
 
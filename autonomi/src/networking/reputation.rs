@@ -0,0 +1,390 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use ant_bootstrap::BootstrapConfig;
+use libp2p::PeerId;
+use libp2p::kad::PeerInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Name of the reputation file, stored next to the bootstrap cache.
+const REPUTATION_FILE_NAME: &str = "client_peer_reputation";
+/// Penalties halve over this period, so misbehaving peers eventually get another chance.
+const PENALTY_HALF_LIFE: Duration = Duration::from_secs(24 * 3600);
+/// Penalties of unreachable peers halve much faster, as transport failures are often transient.
+const UNRESPONSIVE_HALF_LIFE: Duration = Duration::from_secs(10 * 60);
+/// Peers with at least this penalty are left out of the requests, as long as enough peers remain.
+const DEPRIORITISED_PENALTY: f64 = 4.0;
+/// A successful response makes up for part of the penalty of a peer.
+const SUCCESS_REWARD: f64 = 0.25;
+/// Number of peers remembered, the ones with the lowest penalty are forgotten first.
+const MAX_PEERS: usize = 10_000;
+/// How often the driver writes the reputations to disk when they changed.
+pub(crate) const PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Misbehaviour of a peer lowering its reputation, see [`PeerReputation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PeerIssue {
+    /// The peer answered with a quote that failed verification
+    InvalidQuote,
+    /// The peer timed out or could not be reached, forgiven faster than the other issues
+    Unresponsive,
+    /// The peer refused to store a record it was sent
+    FailedToStore,
+    /// The peer served a record that failed validation
+    InvalidRecord,
+}
+
+impl PeerIssue {
+    fn penalty(self) -> f64 {
+        match self {
+            PeerIssue::InvalidQuote => 4.0,
+            PeerIssue::Unresponsive => 1.0,
+            PeerIssue::FailedToStore => 2.0,
+            PeerIssue::InvalidRecord => 8.0,
+        }
+    }
+}
+
+/// What the client learnt about a peer, see [`crate::networking::Network::peer_reputations`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerReputation {
+    /// Number of requests the peer answered successfully
+    pub successes: u64,
+    /// Number of invalid quotes the peer answered with
+    pub invalid_quotes: u64,
+    /// Number of requests the peer did not answer in time or at all
+    pub unresponsive: u64,
+    /// Number of records the peer refused to store
+    pub failed_stores: u64,
+    /// Number of invalid records the peer served
+    pub invalid_records: u64,
+    /// Penalty as of `updated_at`, use [`PeerReputation::penalty`] for the current one
+    penalty: f64,
+    /// Penalty of the transport failures as of `updated_at`, decaying faster than `penalty`
+    #[serde(default)]
+    unresponsive_penalty: f64,
+    /// Last update, in seconds since the unix epoch
+    pub updated_at: u64,
+}
+
+impl PeerReputation {
+    /// Current penalty of the peer, 0 for a well behaved one.
+    pub fn penalty(&self) -> f64 {
+        self.penalty_at(now_secs())
+    }
+
+    /// Whether the peer is left out of the requests when enough other peers are available.
+    pub fn is_deprioritised(&self) -> bool {
+        self.penalty() >= DEPRIORITISED_PENALTY
+    }
+
+    fn penalty_at(&self, now: u64) -> f64 {
+        let (penalty, unresponsive_penalty) = self.penalties_at(now);
+        penalty + unresponsive_penalty
+    }
+
+    /// The penalty and the penalty of the transport failures, decayed until `now`.
+    fn penalties_at(&self, now: u64) -> (f64, f64) {
+        let elapsed = now.saturating_sub(self.updated_at) as f64;
+        (
+            self.penalty * 0.5f64.powf(elapsed / PENALTY_HALF_LIFE.as_secs_f64()),
+            self.unresponsive_penalty * 0.5f64.powf(elapsed / UNRESPONSIVE_HALF_LIFE.as_secs_f64()),
+        )
+    }
+
+    fn decay(&mut self, now: u64) {
+        (self.penalty, self.unresponsive_penalty) = self.penalties_at(now);
+        self.updated_at = now;
+    }
+
+    fn record_issue(&mut self, issue: PeerIssue, now: u64) {
+        self.decay(now);
+        match issue {
+            PeerIssue::InvalidQuote => self.invalid_quotes += 1,
+            PeerIssue::Unresponsive => self.unresponsive += 1,
+            PeerIssue::FailedToStore => self.failed_stores += 1,
+            PeerIssue::InvalidRecord => self.invalid_records += 1,
+        }
+        match issue {
+            PeerIssue::Unresponsive => self.unresponsive_penalty += issue.penalty(),
+            _ => self.penalty += issue.penalty(),
+        }
+    }
+
+    fn record_success(&mut self, now: u64) {
+        self.decay(now);
+        self.successes += 1;
+        // a peer answering is reachable again
+        let reward = SUCCESS_REWARD.min(self.unresponsive_penalty);
+        self.unresponsive_penalty -= reward;
+        self.penalty = (self.penalty - (SUCCESS_REWARD - reward)).max(0.0);
+    }
+}
+
+/// Versioned on-disk format of the reputations, keyed by peer id.
+#[derive(Serialize, Deserialize)]
+enum PeerReputationsVersioned {
+    V0(BTreeMap<String, PeerReputation>),
+}
+
+/// Client side reputation of the peers, shared by the [`crate::Network`] and its driver.
+///
+/// The driver records the outcome of the requests it sends, the [`crate::Network`] leaves the
+/// deprioritised peers out of its quote and put requests. The reputations are persisted next to
+/// the bootstrap cache so they survive restarts.
+#[derive(Debug, Default)]
+pub(crate) struct PeerReputations {
+    peers: Mutex<HashMap<PeerId, PeerReputation>>,
+    /// Where the reputations are persisted, if at all
+    path: Option<PathBuf>,
+    /// Whether the reputations changed since they were last persisted
+    changed: AtomicBool,
+}
+
+impl PeerReputations {
+    /// Load the reputations persisted in the bootstrap cache directory.
+    pub fn from_bootstrap_config(config: &BootstrapConfig) -> Self {
+        let suffix = if config.local { "_local" } else { "" };
+        let path = config
+            .cache_dir
+            .join(format!("{REPUTATION_FILE_NAME}{suffix}.rmp"));
+        let mut reputations = Self::load(&path);
+        reputations.path = (!config.disable_cache_writing).then_some(path);
+        reputations
+    }
+
+    /// Load the reputations from the given file, starting afresh if it is missing or corrupted.
+    pub fn load(path: &Path) -> Self {
+        let peers = match std::fs::read(path) {
+            Ok(bytes) => match rmp_serde::from_slice(&bytes) {
+                Ok(PeerReputationsVersioned::V0(peers)) => peers
+                    .into_iter()
+                    .filter_map(|(peer, reputation)| Some((peer.parse().ok()?, reputation)))
+                    .collect(),
+                Err(err) => {
+                    warn!("Ignoring corrupted peer reputations at {path:?}: {err}");
+                    HashMap::new()
+                }
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => {
+                warn!("Failed to read peer reputations at {path:?}: {err}");
+                HashMap::new()
+            }
+        };
+        debug!("Loaded the reputation of {} peers", peers.len());
+        Self {
+            peers: Mutex::new(peers),
+            path: Some(path.to_path_buf()),
+            changed: AtomicBool::new(false),
+        }
+    }
+
+    fn peers(&self) -> MutexGuard<'_, HashMap<PeerId, PeerReputation>> {
+        // the reputations stay consistent even if a panic occurred while holding the lock
+        self.peers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn record_issue(&self, peer: PeerId, issue: PeerIssue) {
+        debug!("Lowering the reputation of {peer:?}: {issue:?}");
+        self.peers()
+            .entry(peer)
+            .or_default()
+            .record_issue(issue, now_secs());
+        self.changed.store(true, Ordering::Relaxed);
+    }
+
+    pub fn record_success(&self, peer: PeerId) {
+        let mut peers = self.peers();
+        // only track the well behaved peers once they misbehaved, to keep the table small
+        if let Some(reputation) = peers.get_mut(&peer) {
+            reputation.record_success(now_secs());
+            self.changed.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn get(&self, peer: &PeerId) -> Option<PeerReputation> {
+        self.peers().get(peer).cloned()
+    }
+
+    pub fn all(&self) -> HashMap<PeerId, PeerReputation> {
+        self.peers().clone()
+    }
+
+    /// Leave out the deprioritised peers, worst first, keeping at least `needed` of them.
+    ///
+    /// Only used to choose the peers to ask for quotes, the peers paid to store a record are never
+    /// left out.
+    pub fn select_peers(&self, peers: Vec<PeerInfo>, needed: usize) -> Vec<PeerInfo> {
+        let removable = peers.len().saturating_sub(needed);
+        if removable == 0 {
+            return peers;
+        }
+
+        let now = now_secs();
+        let reputations = self.peers();
+        let mut unwanted: Vec<(f64, PeerId)> = peers
+            .iter()
+            .filter_map(|peer| {
+                let penalty = reputations.get(&peer.peer_id)?.penalty_at(now);
+                (penalty >= DEPRIORITISED_PENALTY).then_some((penalty, peer.peer_id))
+            })
+            .collect();
+        unwanted.sort_by(|a, b| b.0.total_cmp(&a.0));
+        unwanted.truncate(removable);
+
+        if !unwanted.is_empty() {
+            debug!("Leaving out peers with a poor reputation: {unwanted:?}");
+        }
+        peers
+            .into_iter()
+            .filter(|peer| !unwanted.iter().any(|(_, id)| *id == peer.peer_id))
+            .collect()
+    }
+
+    /// Write the reputations to disk if they changed since the last time.
+    ///
+    /// The file is written to a temporary file first and then renamed over the previous one,
+    /// so a crash in the middle of a write never leaves a corrupted file behind.
+    pub fn persist(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.changed.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let now = now_secs();
+        let mut peers: Vec<(PeerId, PeerReputation)> = self.all().into_iter().collect();
+        if peers.len() > MAX_PEERS {
+            peers.sort_by(|a, b| b.1.penalty_at(now).total_cmp(&a.1.penalty_at(now)));
+            peers.truncate(MAX_PEERS);
+        }
+        let peers = peers
+            .into_iter()
+            .map(|(peer, reputation)| (peer.to_string(), reputation))
+            .collect();
+
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let bytes = rmp_serde::to_vec_named(&PeerReputationsVersioned::V0(peers))
+            .map_err(std::io::Error::other)?;
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, bytes)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_info(peer_id: PeerId) -> PeerInfo {
+        PeerInfo {
+            peer_id,
+            addrs: vec![],
+        }
+    }
+
+    #[test]
+    fn test_peer_reputation_penalty_decays() {
+        let mut reputation = PeerReputation::default();
+        reputation.record_issue(PeerIssue::InvalidQuote, 1_000);
+        assert_eq!(reputation.invalid_quotes, 1);
+        assert_eq!(reputation.penalty_at(1_000), 4.0);
+
+        let half_life = PENALTY_HALF_LIFE.as_secs();
+        assert_eq!(reputation.penalty_at(1_000 + half_life), 2.0);
+
+        reputation.record_success(1_000 + half_life);
+        assert_eq!(reputation.successes, 1);
+        assert_eq!(reputation.penalty_at(1_000 + half_life), 1.75);
+    }
+
+    #[test]
+    fn test_peer_reputation_transport_failures_decay_fast() {
+        let mut reputation = PeerReputation::default();
+        reputation.record_issue(PeerIssue::FailedToStore, 1_000);
+        reputation.record_issue(PeerIssue::Unresponsive, 1_000);
+        reputation.record_issue(PeerIssue::Unresponsive, 1_000);
+        assert_eq!(reputation.unresponsive, 2);
+        assert_eq!(reputation.penalty_at(1_000), 4.0);
+
+        // the transport failures are mostly forgotten within the hour
+        let half_life = UNRESPONSIVE_HALF_LIFE.as_secs();
+        let penalty = reputation.penalty_at(1_000 + half_life);
+        assert!((penalty - 3.0).abs() < 0.01, "{penalty}");
+        let penalty = reputation.penalty_at(1_000 + 6 * half_life);
+        assert!(penalty < 2.05, "{penalty}");
+
+        // answering makes up for the transport failures first
+        reputation.record_success(1_000);
+        assert_eq!(reputation.penalty_at(1_000), 3.75);
+        assert_eq!(reputation.penalties_at(1_000), (2.0, 1.75));
+    }
+
+    #[test]
+    fn test_select_peers_leaves_out_worst_peers() {
+        let reputations = PeerReputations::default();
+        let peers: Vec<PeerInfo> = (0..7).map(|_| peer_info(PeerId::random())).collect();
+        reputations.record_issue(peers[1].peer_id, PeerIssue::InvalidRecord);
+        reputations.record_issue(peers[3].peer_id, PeerIssue::InvalidQuote);
+        reputations.record_issue(peers[5].peer_id, PeerIssue::Unresponsive);
+
+        // enough peers left
+        let selected = reputations.select_peers(peers.clone(), 5);
+        assert_eq!(selected.len(), 5);
+        assert!(!selected.contains(&peers[1]));
+        assert!(!selected.contains(&peers[3]));
+
+        // only the worst one can go
+        let selected = reputations.select_peers(peers.clone(), 6);
+        assert_eq!(selected.len(), 6);
+        assert!(!selected.contains(&peers[1]));
+        assert!(selected.contains(&peers[3]));
+
+        // successes alone don't add peers to the table
+        let good_peer = PeerId::random();
+        reputations.record_success(good_peer);
+        assert_eq!(reputations.get(&good_peer), None);
+    }
+
+    #[test]
+    fn test_peer_reputations_persist() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let path = dir.path().join("reputations.rmp");
+        let peer = PeerId::random();
+
+        let reputations = PeerReputations::load(&path);
+        assert!(reputations.all().is_empty());
+        reputations.record_issue(peer, PeerIssue::FailedToStore);
+        reputations
+            .persist()
+            .expect("Failed to persist reputations");
+
+        let reloaded = PeerReputations::load(&path);
+        assert_eq!(reloaded.get(&peer), reputations.get(&peer));
+        assert_eq!(reloaded.get(&peer).map(|r| r.failed_stores), Some(1));
+    }
+}
@@ -11,12 +11,15 @@ use ant_protocol::{NetworkAddress, PrettyPrintRecordKey};
 
 use super::{Network, RetryStrategy};
 use super::{NetworkError, PeerInfo, Record, Strategy};
+use std::time::Duration;
 use tokio::time::sleep;
 
 impl Network {
     /// Put a record to the network with retries
     ///
     /// Will carry out network get after put success, to verify the existence of the record.
+    /// Each attempt goes to all the peers of `to`, whatever their reputation or the
+    /// [`super::RetryPolicy`], as they are the payees of the record.
     pub async fn put_record_with_retries(
         &self,
         record: Record,
@@ -24,8 +27,8 @@ impl Network {
         strategy: &Strategy,
     ) -> Result<(), NetworkError> {
         let addr = PrettyPrintRecordKey::from(&record.key).into_owned();
-        let mut errors = vec![];
-        for duration in strategy.put_retry.backoff() {
+        let max_attempts = strategy.put_retry.attempts();
+        for attempt in 1..=max_attempts {
             match self
                 .put_record(record.clone(), to.clone(), strategy.put_quorum)
                 .await
@@ -33,19 +36,14 @@ impl Network {
                 // Exitence verification is no longer mandatory as req/rsp upload allows client
                 // collect storage result from nodes directly.
                 Ok(()) => return Ok(()),
-                // return fatal errors
-                Err(err) if err.cannot_retry() => {
-                    return Err(err);
-                }
-                // retry on other errors
-                Err(err) => {
-                    warn!("Put record failed at {addr}: {err:?}, retrying in {duration:?}");
-                    errors.push(err.clone());
-                    match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
-                        None => return Err(err),
+                // retry unless the policy gives up on the error
+                Err(err) => match self.retry_delay(attempt, max_attempts, Some(&err)) {
+                    Some(retry_delay) => {
+                        warn!("Put record failed at {addr}: {err:?}, retrying in {retry_delay:?}");
+                        sleep(retry_delay).await;
                     }
-                }
+                    None => return Err(err),
+                },
             }
         }
         Err(NetworkError::InvalidRetryStrategy)
@@ -57,9 +55,9 @@ impl Network {
         addr: NetworkAddress,
         strategy: &Strategy,
     ) -> Result<Option<Record>, NetworkError> {
-        let mut errors = vec![];
         let quorum = strategy.get_quorum;
-        for duration in strategy.get_retry.backoff() {
+        let max_attempts = strategy.get_retry.attempts();
+        for attempt in 1..=max_attempts {
             match self.get_record(addr.clone(), quorum).await {
                 // return success
                 Ok(Some(record)) => return Ok(Some(record)),
@@ -67,27 +65,22 @@ impl Network {
                 Err(err) if matches!(err, NetworkError::SplitRecord(_)) => {
                     return Err(err);
                 }
-                // return fatal errors
-                Err(err) if err.cannot_retry() => {
-                    return Err(err);
-                }
                 // retry on no record
-                Ok(None) => {
-                    warn!("Record not found at {addr}, retrying in {duration:?}");
-                    match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
-                        None => return Ok(None),
+                Ok(None) => match self.retry_delay(attempt, max_attempts, None) {
+                    Some(retry_delay) => {
+                        warn!("Record not found at {addr}, retrying in {retry_delay:?}");
+                        sleep(retry_delay).await;
                     }
-                }
-                // retry on other errors
-                Err(err) => {
-                    warn!("Get record failed at {addr}: {err:?}, retrying in {duration:?}");
-                    errors.push(err.clone());
-                    match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
-                        None => return Err(err),
+                    None => return Ok(None),
+                },
+                // retry unless the policy gives up on the error
+                Err(err) => match self.retry_delay(attempt, max_attempts, Some(&err)) {
+                    Some(retry_delay) => {
+                        warn!("Get record failed at {addr}: {err:?}, retrying in {retry_delay:?}");
+                        sleep(retry_delay).await;
                     }
-                }
+                    None => return Err(err),
+                },
             }
         }
         Err(NetworkError::InvalidRetryStrategy)
@@ -100,24 +93,19 @@ impl Network {
         data_type: u32,
         data_size: usize,
     ) -> Result<Option<Vec<(PeerInfo, PaymentQuote)>>, NetworkError> {
-        let mut errors = vec![];
-        for duration in RetryStrategy::Once.backoff() {
+        let max_attempts = RetryStrategy::Once.attempts();
+        for attempt in 1..=max_attempts {
             match self.get_quotes(addr.clone(), data_type, data_size).await {
                 // return success
                 Ok(quotes) => return Ok(quotes),
-                // return fatal errors
-                Err(err) if err.cannot_retry() => {
-                    return Err(err);
-                }
-                // retry on other errors
-                Err(err) => {
-                    warn!("Get quotes failed at {addr}: {err:?}, retrying in {duration:?}");
-                    errors.push(err.clone());
-                    match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
-                        None => return Err(err),
+                // retry unless the policy gives up on the error
+                Err(err) => match self.retry_delay(attempt, max_attempts, Some(&err)) {
+                    Some(retry_delay) => {
+                        warn!("Get quotes failed at {addr}: {err:?}, retrying in {retry_delay:?}");
+                        sleep(retry_delay).await;
                     }
-                }
+                    None => return Err(err),
+                },
             }
         }
         Err(NetworkError::InvalidRetryStrategy)
@@ -129,26 +117,36 @@ impl Network {
         addr: NetworkAddress,
         count: Option<usize>,
     ) -> Result<Vec<PeerInfo>, NetworkError> {
-        let mut errors = vec![];
-        for duration in RetryStrategy::Once.backoff() {
+        let max_attempts = RetryStrategy::Once.attempts();
+        for attempt in 1..=max_attempts {
             match self.get_closest_peers(addr.clone(), count).await {
                 // return success
                 Ok(peers) => return Ok(peers),
-                // return fatal errors
-                Err(err) if err.cannot_retry() => {
-                    return Err(err);
-                }
-                // retry on other errors
-                Err(err) => {
-                    warn!("Get closest peers failed at {addr}: {err:?}, retrying in {duration:?}");
-                    errors.push(err.clone());
-                    match duration {
-                        Some(retry_delay) => sleep(retry_delay).await,
-                        None => return Err(err),
+                // retry unless the policy gives up on the error
+                Err(err) => match self.retry_delay(attempt, max_attempts, Some(&err)) {
+                    Some(retry_delay) => {
+                        warn!(
+                            "Get closest peers failed at {addr}: {err:?}, retrying in {retry_delay:?}"
+                        );
+                        sleep(retry_delay).await;
                     }
-                }
+                    None => return Err(err),
+                },
             }
         }
         Err(NetworkError::InvalidRetryStrategy)
     }
+
+    /// Delay before the next attempt according to the [`super::RetryPolicy`], never retrying fatal errors
+    fn retry_delay(
+        &self,
+        attempt: usize,
+        max_attempts: usize,
+        error: Option<&NetworkError>,
+    ) -> Option<Duration> {
+        if attempt >= max_attempts || error.is_some_and(NetworkError::cannot_retry) {
+            return None;
+        }
+        self.retry_policy.retry_delay(attempt, max_attempts, error)
+    }
 }
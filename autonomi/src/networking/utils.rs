@@ -1,6 +1,10 @@
 use crate::Multiaddr;
-use ant_protocol::CLOSE_GROUP_SIZE;
-use libp2p::kad::Quorum;
+use ant_protocol::storage::{
+    Chunk, DataTypes, GraphEntry, Pointer, RecordHeader, RecordKind, Scratchpad,
+    try_deserialize_record,
+};
+use ant_protocol::{CLOSE_GROUP_SIZE, NetworkAddress};
+use libp2p::kad::{Quorum, Record};
 use libp2p::multiaddr::Protocol;
 // @anselme: this is a duplicate function from ant_networking, wasn't sure where to place it
 
@@ -38,3 +42,167 @@ pub fn get_quorum_amount(quorum: &Quorum) -> usize {
         Quorum::One => 1,
     }
 }
+
+/// Whether a record served by a peer is well formed: it decodes to the data type its header
+/// claims, its content matches its address and its signature is valid for signed data types.
+pub(crate) fn record_is_valid(record: &Record) -> bool {
+    let data_type = match RecordHeader::from_record(record) {
+        Ok(RecordHeader {
+            kind: RecordKind::DataOnly(data_type),
+        }) => data_type,
+        // nodes never store the payments along with the data
+        _ => return false,
+    };
+    let address = match data_type {
+        DataTypes::Chunk => try_deserialize_record::<Chunk>(record)
+            .ok()
+            .filter(|chunk| *chunk.name() == xor_name::XorName::from_content(chunk.value()))
+            .map(|chunk| chunk.network_address()),
+        DataTypes::Pointer => try_deserialize_record::<Pointer>(record)
+            .ok()
+            .filter(Pointer::verify_signature)
+            .map(|pointer| NetworkAddress::from(pointer.address())),
+        DataTypes::Scratchpad => try_deserialize_record::<Scratchpad>(record)
+            .ok()
+            .filter(Scratchpad::verify_signature)
+            .map(|scratchpad| scratchpad.network_address()),
+        // nodes store all the entries at an address, which are forks of each other
+        DataTypes::GraphEntry => {
+            let Ok(entries) = try_deserialize_record::<Vec<GraphEntry>>(record) else {
+                return false;
+            };
+            return !entries.is_empty()
+                && entries.iter().all(|entry| {
+                    entry.verify_signature()
+                        && NetworkAddress::from(entry.address()).to_record_key() == record.key
+                });
+        }
+    };
+    address.is_some_and(|address| address.to_record_key() == record.key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant_protocol::storage::{PointerTarget, try_serialize_record};
+    use bls::SecretKey;
+    use bytes::Bytes;
+    use libp2p::kad::RecordKey;
+
+    fn record<T: serde::Serialize>(key: RecordKey, value: &T, data_type: DataTypes) -> Record {
+        Record {
+            key,
+            value: try_serialize_record(value, RecordKind::DataOnly(data_type))
+                .expect("Failed to serialize record")
+                .to_vec(),
+            publisher: None,
+            expires: None,
+        }
+    }
+
+    #[test]
+    fn test_record_is_valid_chunk() {
+        let chunk = Chunk::new(Bytes::from_static(b"chunk content"));
+        let key = chunk.network_address().to_record_key();
+        assert!(record_is_valid(&record(
+            key.clone(),
+            &chunk,
+            DataTypes::Chunk
+        )));
+
+        let other = Chunk::new(Bytes::from_static(b"other content"));
+        assert!(!record_is_valid(&record(
+            key.clone(),
+            &other,
+            DataTypes::Chunk
+        )));
+        // a valid chunk announced as another data type
+        assert!(!record_is_valid(&record(key, &chunk, DataTypes::Pointer)));
+    }
+
+    #[test]
+    fn test_record_is_valid_pointer() {
+        let owner = SecretKey::random();
+        let target = PointerTarget::ChunkAddress(ant_protocol::storage::ChunkAddress::new(
+            xor_name::XorName::random(&mut rand::thread_rng()),
+        ));
+        let pointer = Pointer::new(&owner, 0, target.clone());
+        let key = NetworkAddress::from(pointer.address()).to_record_key();
+        assert!(record_is_valid(&record(
+            key.clone(),
+            &pointer,
+            DataTypes::Pointer
+        )));
+
+        let other = Pointer::new(&SecretKey::random(), 0, target);
+        assert!(!record_is_valid(&record(key, &other, DataTypes::Pointer)));
+    }
+
+    #[test]
+    fn test_record_is_valid_scratchpad() {
+        let owner = SecretKey::random();
+        let scratchpad = Scratchpad::new(&owner, 0, &Bytes::from_static(b"content"), 0);
+        let key = scratchpad.network_address().to_record_key();
+        assert!(record_is_valid(&record(
+            key.clone(),
+            &scratchpad,
+            DataTypes::Scratchpad
+        )));
+
+        let other = Scratchpad::new(&SecretKey::random(), 0, &Bytes::from_static(b"content"), 0);
+        assert!(!record_is_valid(&record(
+            key,
+            &other,
+            DataTypes::Scratchpad
+        )));
+    }
+
+    #[test]
+    fn test_record_is_valid_graph_entry() {
+        let owner = SecretKey::random();
+        let entry = GraphEntry::new(&owner, vec![], [1; 32], vec![]);
+        let fork = GraphEntry::new(&owner, vec![], [2; 32], vec![]);
+        let key = NetworkAddress::from(entry.address()).to_record_key();
+        assert!(record_is_valid(&record(
+            key.clone(),
+            &vec![entry.clone()],
+            DataTypes::GraphEntry
+        )));
+        assert!(record_is_valid(&record(
+            key.clone(),
+            &vec![entry.clone(), fork],
+            DataTypes::GraphEntry
+        )));
+
+        let empty: Vec<GraphEntry> = vec![];
+        assert!(!record_is_valid(&record(
+            key.clone(),
+            &empty,
+            DataTypes::GraphEntry
+        )));
+        let other = GraphEntry::new(&SecretKey::random(), vec![], [1; 32], vec![]);
+        assert!(!record_is_valid(&record(
+            key.clone(),
+            &vec![entry.clone(), other],
+            DataTypes::GraphEntry
+        )));
+        let forged = GraphEntry::new_with_signature(
+            owner.public_key(),
+            vec![],
+            [3; 32],
+            vec![],
+            entry.signature.clone(),
+        );
+        assert!(!record_is_valid(&record(
+            key.clone(),
+            &vec![entry.clone(), forged],
+            DataTypes::GraphEntry
+        )));
+        // entries are never stored alone
+        assert!(!record_is_valid(&record(
+            key,
+            &entry,
+            DataTypes::GraphEntry
+        )));
+    }
+}
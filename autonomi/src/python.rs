@@ -1560,15 +1560,32 @@ impl PyClientEvent {
     fn event_type(&self) -> &'static str {
         match self.inner {
             ClientEvent::UploadComplete(_) => "UploadComplete",
+            ClientEvent::OperationStarted { .. } => "OperationStarted",
+            ClientEvent::OperationFinished { .. } => "OperationFinished",
+            ClientEvent::QuotesRequested { .. } => "QuotesRequested",
+            ClientEvent::QuotesReceived { .. } => "QuotesReceived",
+            ClientEvent::PaymentSubmitted { .. } => "PaymentSubmitted",
+            ClientEvent::PaymentConfirmed { .. } => "PaymentConfirmed",
+            ClientEvent::ChunkStored { .. } => "ChunkStored",
+            ClientEvent::ChunkRetried { .. } => "ChunkRetried",
+            ClientEvent::ChunkDownloaded { .. } => "ChunkDownloaded",
+            ClientEvent::DecryptionProgress { .. } => "DecryptionProgress",
         }
     }
 
+    /// The id of the operation which emitted the event, if any.
+    #[getter]
+    fn operation_id(&self) -> Option<u64> {
+        self.inner.operation().map(|op| op.0)
+    }
+
     #[getter]
     fn upload_summary(&self) -> Option<PyUploadSummary> {
         match &self.inner {
             ClientEvent::UploadComplete(summary) => Some(PyUploadSummary {
                 inner: summary.clone(),
             }),
+            _ => None,
         }
     }
 
@@ -19,6 +19,10 @@ pub enum Error {
     Encoding(#[from] rmp_serde::encode::Error),
     #[error(transparent)]
     SelfEncryption(#[from] self_encryption::Error),
+    #[error("Failed to read the data to encrypt: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Streaming encryption failed: {0}")]
+    Stream(String),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -14,6 +14,8 @@ use bytes::Bytes;
 use self_encryption::MAX_CHUNK_SIZE;
 use std::path::PathBuf;
 use std::time::Instant;
+use tempfile::TempPath;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::{sync::oneshot, task::spawn_blocking};
 
 use crate::client::config::{FILE_ENCRYPT_BATCH_SIZE, IN_MEMORY_ENCRYPTION_MAX_SIZE};
@@ -21,6 +23,7 @@ use crate::client::data::DataAddress;
 use crate::utils::process_tasks_with_max_concurrency;
 
 const STREAM_CHUNK_CHANNEL_CAPACITY: usize = 100;
+const STREAM_READ_CHANNEL_CAPACITY: usize = 4;
 
 pub enum EncryptionState {
     InMemory(Vec<Chunk>, DataMapChunk),
@@ -189,6 +192,25 @@ impl EncryptionStream {
         metadata: Metadata,
         is_public: bool,
         file_size: usize,
+    ) -> Result<Self, String> {
+        Self::stream_from_file(
+            file_path,
+            relative_path,
+            metadata,
+            is_public,
+            file_size,
+            None,
+        )
+    }
+
+    /// Streaming encryption of a file, removing it once encrypted if it is a temporary file.
+    fn stream_from_file(
+        file_path: String,
+        relative_path: PathBuf,
+        metadata: Metadata,
+        is_public: bool,
+        file_size: usize,
+        temp_file: Option<TempPath>,
     ) -> Result<Self, String> {
         let start = Instant::now();
         let (chunk_sender, chunk_receiver) =
@@ -212,27 +234,11 @@ impl EncryptionStream {
             });
 
             // once we're done, send the datamap to the datamap channel
-            match result {
-                Ok(datamap) => {
-                    // Send the DataMap result
-                    // Convert DataMap to bytes and create a chunk
-                    let datamap_bytes = rmp_serde::to_vec(&datamap)
-                        .map_err(|e| error!("Streaming encryption error serializing datamap for {file_path_clone}: {e}"))
-                        .unwrap_or_else(|_| vec![]);
-                    let datamap_chunk = DataMapChunk(Chunk::new(Bytes::from(datamap_bytes)));
-                    if let Err(err) = datamap_sender.send(datamap_chunk) {
-                        error!(
-                            "Streaming encryption error sending datamap for {file_path_clone}: {err}"
-                        );
-                    };
-                }
-                Err(err) => {
-                    error!("Streaming encryption failed for {file_path_clone}: {err}");
-                }
-            }
+            send_datamap(result, datamap_sender, &file_path_clone);
 
             // then close the chunk sender to signal completion and datamap availability
             drop(chunk_sender);
+            drop(temp_file);
         });
 
         #[cfg(feature = "loud")]
@@ -259,6 +265,220 @@ impl EncryptionStream {
         );
         Ok(stream)
     }
+
+    /// Encrypts all the data read from `reader`.
+    ///
+    /// When `size` is known and larger than [`IN_MEMORY_ENCRYPTION_MAX_SIZE`], the data is encrypted
+    /// as it is read, see [`EncryptionStream::new_stream_from_reader`]. Otherwise the reader is
+    /// drained first, as self-encryption needs the total size before producing any chunk: up to
+    /// [`IN_MEMORY_ENCRYPTION_MAX_SIZE`] bytes are kept in memory, and as a fallback for larger data
+    /// of unknown size, the data is staged in a temporary file and encrypted from there.
+    /// When the data is drained first and the reader yields a different number of bytes than `size`,
+    /// this fails before any chunk is produced.
+    pub async fn new_from_reader<R>(
+        mut reader: R,
+        size: Option<usize>,
+        is_public: bool,
+    ) -> Result<Self, crate::self_encryption::Error>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        let max_in_memory = *IN_MEMORY_ENCRYPTION_MAX_SIZE;
+        if let Some(size) = size.filter(|size| *size > max_in_memory) {
+            return Ok(Self::new_stream_from_reader(reader, size, is_public));
+        }
+
+        let mut data = Vec::with_capacity(size.unwrap_or_default().min(max_in_memory));
+        (&mut reader)
+            .take(max_in_memory as u64 + 1)
+            .read_to_end(&mut data)
+            .await?;
+
+        if data.len() <= max_in_memory {
+            check_reader_size(data.len(), size)?;
+            let (stream, _data_map_chunk) = Self::new_in_memory(Bytes::from(data), is_public)?;
+            return Ok(stream);
+        }
+
+        let temp_file = tempfile::NamedTempFile::new()?;
+        let (file, temp_path) = temp_file.into_parts();
+        let mut file = tokio::fs::File::from_std(file);
+        file.write_all(&data).await?;
+        let buffered = data.len();
+        drop(data);
+        let data_size = buffered + tokio::io::copy(&mut reader, &mut file).await? as usize;
+        file.flush().await?;
+        drop(file);
+        check_reader_size(data_size, size)?;
+
+        info!("Staged {data_size} bytes from reader to {temp_path:?} for encryption");
+        let file_path = temp_path.to_string_lossy().to_string();
+        Self::stream_from_file(
+            file_path,
+            Default::default(),
+            Metadata::new_with_size(data_size as u64),
+            is_public,
+            data_size,
+            Some(temp_path),
+        )
+        .map_err(crate::self_encryption::Error::Stream)
+    }
+
+    /// Streaming encryption of exactly `data_size` bytes read from `reader`, without staging them
+    /// to disk or memory.
+    ///
+    /// If the reader yields less or more than `data_size` bytes, the encryption fails and the stream
+    /// ends without a datamap, after the chunks produced so far were handed out.
+    pub fn new_stream_from_reader<R>(reader: R, data_size: usize, is_public: bool) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        let start = Instant::now();
+        let (chunk_sender, chunk_receiver) =
+            std::sync::mpsc::sync_channel(STREAM_CHUNK_CHANNEL_CAPACITY);
+        let (datamap_sender, datamap_receiver) = oneshot::channel();
+        let (bytes_sender, mut bytes_receiver) =
+            tokio::sync::mpsc::channel(STREAM_READ_CHANNEL_CAPACITY);
+
+        // Read the source in an async task, handing the bytes over to the encryption
+        tokio::spawn(forward_reader(reader, data_size, bytes_sender));
+
+        // Spawn a task to handle streaming encryption
+        spawn_blocking(move || {
+            let data_iter = std::iter::from_fn(move || bytes_receiver.blocking_recv());
+            let result =
+                self_encryption::stream_encrypt(data_size, data_iter).and_then(|mut stream| {
+                    for chunk in stream.chunks() {
+                        let (_xorname, bytes) = chunk?;
+                        chunk_sender.send(Chunk::new(bytes)).map_err(|err| {
+                            error!("Error sending chunk: {err:?}");
+                            self_encryption::Error::Io(std::io::Error::other(format!(
+                                "Channel send error in encryption stream from reader: {err}"
+                            )))
+                        })?;
+                    }
+                    Ok(stream.into_datamap())
+                });
+
+            // once we're done, send the datamap to the datamap channel
+            send_datamap(result, datamap_sender, "reader");
+
+            // then close the chunk sender to signal completion and datamap availability
+            drop(chunk_sender);
+        });
+
+        info!("Streaming encryption of {data_size} bytes from reader ...");
+
+        let stream = EncryptionStream {
+            file_path: "".to_string(),
+            relative_path: Default::default(),
+            metadata: Metadata::new_with_size(data_size as u64),
+            is_public,
+            state: EncryptionState::StreamInProgress(StreamProgressState {
+                chunk_receiver,
+                datamap_receiver,
+                chunk_count: 0,
+                total_estimated_chunks: std::cmp::max(3, data_size / MAX_CHUNK_SIZE),
+            }),
+        };
+
+        debug!(
+            "Started streaming encryption from reader (size: {data_size} bytes) in: {:.2?}",
+            start.elapsed()
+        );
+        stream
+    }
+}
+
+/// Checks that a reader yielded the number of bytes it was expected to.
+fn check_reader_size(
+    read: usize,
+    expected: Option<usize>,
+) -> Result<(), crate::self_encryption::Error> {
+    match expected {
+        Some(expected) if expected != read => Err(crate::self_encryption::Error::Stream(format!(
+            "Reader yielded {read} bytes, {expected} bytes expected"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Serializes the datamap resulting from a streaming encryption and sends it to the datamap channel.
+fn send_datamap(
+    result: self_encryption::Result<self_encryption::DataMap>,
+    datamap_sender: oneshot::Sender<DataMapChunk>,
+    source: &str,
+) {
+    match result {
+        Ok(datamap) => {
+            // Convert DataMap to bytes and create a chunk
+            let datamap_bytes = rmp_serde::to_vec(&datamap)
+                .map_err(|e| {
+                    error!("Streaming encryption error serializing datamap for {source}: {e}")
+                })
+                .unwrap_or_else(|_| vec![]);
+            let datamap_chunk = DataMapChunk(Chunk::new(Bytes::from(datamap_bytes)));
+            if let Err(err) = datamap_sender.send(datamap_chunk) {
+                error!("Streaming encryption error sending datamap for {source}: {err}");
+            };
+        }
+        Err(err) => {
+            error!("Streaming encryption failed for {source}: {err}");
+        }
+    }
+}
+
+/// Reads exactly `data_size` bytes from `reader` and forwards them in pieces of up to [`MAX_CHUNK_SIZE`].
+///
+/// On read errors, or if the reader yields less or more than `data_size` bytes, the sender is dropped
+/// before all bytes are forwarded, which makes the encryption fail rather than silently truncate the data.
+async fn forward_reader<R>(
+    mut reader: R,
+    data_size: usize,
+    sender: tokio::sync::mpsc::Sender<Bytes>,
+) where
+    R: AsyncRead + Unpin,
+{
+    let mut remaining = data_size;
+    while remaining > 0 {
+        let mut buf = vec![0u8; std::cmp::min(MAX_CHUNK_SIZE, remaining)];
+        let mut filled = 0;
+        while filled < buf.len() {
+            match reader.read(&mut buf[filled..]).await {
+                Ok(0) => {
+                    let read = data_size - remaining + filled;
+                    error!("Reader ended after {read} bytes, {data_size} bytes expected");
+                    return;
+                }
+                Ok(n) => filled += n,
+                Err(err) => {
+                    error!("Error reading data to encrypt: {err}");
+                    return;
+                }
+            }
+        }
+        remaining -= filled;
+
+        // hold back the last piece until the reader is confirmed to be exhausted
+        if remaining == 0 {
+            match reader.read(&mut [0u8; 1]).await {
+                Ok(0) => {}
+                Ok(_) => {
+                    error!("Reader yielded more than the {data_size} bytes expected");
+                    return;
+                }
+                Err(err) => {
+                    error!("Error reading data to encrypt: {err}");
+                    return;
+                }
+            }
+        }
+
+        if sender.send(Bytes::from(buf)).await.is_err() {
+            error!("Encryption stopped before reading all the data");
+            return;
+        }
+    }
 }
 
 /// Encrypts all files in a directory and returns the encryption results (common logic)
@@ -429,4 +649,63 @@ mod tests {
         let data_map_chunk = stream.data_map_chunk();
         assert!(data_map_chunk.is_some());
     }
+
+    async fn forwarded_len(data: &[u8], data_size: usize) -> usize {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        forward_reader(data, data_size, sender).await;
+        let mut forwarded = 0;
+        while let Some(bytes) = receiver.recv().await {
+            forwarded += bytes.len();
+        }
+        forwarded
+    }
+
+    #[tokio::test]
+    async fn test_forward_reader_requires_exact_size() {
+        let data = vec![7u8; MAX_CHUNK_SIZE + 10];
+
+        // all bytes are forwarded when the size matches
+        assert_eq!(forwarded_len(&data, data.len()).await, data.len());
+
+        // the last piece is held back when the reader yields more or less than expected
+        assert!(forwarded_len(&data, data.len() - 1).await < data.len() - 1);
+        assert!(forwarded_len(&data, data.len() + 1).await < data.len() + 1);
+    }
+
+    #[tokio::test]
+    async fn test_new_from_reader_checks_size_before_encrypting() {
+        let data = vec![7u8; 1024];
+
+        let stream =
+            EncryptionStream::new_from_reader(std::io::Cursor::new(data.clone()), None, false)
+                .await
+                .unwrap();
+        assert!(stream.data_map_chunk().is_some());
+
+        let res = EncryptionStream::new_from_reader(
+            std::io::Cursor::new(data.clone()),
+            Some(1025),
+            false,
+        )
+        .await;
+        assert!(matches!(res, Err(crate::self_encryption::Error::Stream(_))));
+    }
+
+    #[tokio::test]
+    async fn test_new_from_reader_streams_large_data_of_known_size() {
+        // nothing is ever written, so the reader would block if it was drained first
+        let (writer, reader) = tokio::io::duplex(1024);
+        let size = *IN_MEMORY_ENCRYPTION_MAX_SIZE + 1;
+
+        let stream = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            EncryptionStream::new_from_reader(reader, Some(size), false),
+        )
+        .await
+        .expect("reader of known size was drained before encrypting")
+        .unwrap();
+        assert!(matches!(stream.state, EncryptionState::StreamInProgress(_)));
+        assert!(stream.data_map_chunk().is_none());
+        drop(writer);
+    }
 }
@@ -0,0 +1,75 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Fixtures shared by the unit tests.
+
+use crate::Client;
+use crate::client::SharedNetwork;
+use crate::client::config::ClientOperatingStrategy;
+use ant_bootstrap::BootstrapConfig;
+use ant_bootstrap::bootstrap::Bootstrap;
+use ant_evm::{EvmNetwork, PaymentQuote, QuotingMetrics, RewardsAddress};
+use bytes::Bytes;
+use self_encryption::DataMap;
+use std::collections::HashMap;
+use std::time::SystemTime;
+use xor_name::XorName;
+
+/// An unsigned quote for `addr`, issued at `timestamp`.
+pub(crate) fn quote_issued_at(addr: XorName, timestamp: SystemTime) -> PaymentQuote {
+    PaymentQuote {
+        content: addr,
+        timestamp,
+        quoting_metrics: QuotingMetrics {
+            data_size: 0,
+            data_type: 0,
+            close_records_stored: 0,
+            records_per_type: vec![],
+            max_records: 0,
+            received_payment_count: 0,
+            live_time: 0,
+            network_density: None,
+            network_size: None,
+        },
+        rewards_address: RewardsAddress::ZERO,
+        pub_key: vec![],
+        signature: vec![],
+    }
+}
+
+/// Encrypt data and resolve its root datamap, as done on download by `restore_data_map_from_chunk`.
+/// Returns the root datamap along with all the chunks, by name.
+pub(crate) fn encrypt_locally(data: &Bytes) -> (DataMap, HashMap<XorName, Bytes>) {
+    let (data_map, chunks) =
+        self_encryption::encrypt(data.clone()).expect("Failed to encrypt test data");
+    let chunks: HashMap<XorName, Bytes> = chunks
+        .into_iter()
+        .map(|chunk| (XorName::from_content(&chunk.content), chunk.content))
+        .collect();
+    let mut chunk_fetcher = |name: XorName| -> self_encryption::Result<Bytes> {
+        chunks
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| self_encryption::Error::Generic("Chunk not found".to_string()))
+    };
+    let data_map = self_encryption::get_root_data_map(data_map, &mut chunk_fetcher)
+        .expect("Failed to resolve root datamap");
+    (data_map, chunks)
+}
+
+/// A client which is not connected to any peer, for the logic which does not reach the network.
+pub(crate) async fn offline_client(strategy: ClientOperatingStrategy) -> Client {
+    let config = BootstrapConfig::new(true)
+        .with_first(true)
+        .with_disable_cache_reading(true)
+        .with_disable_cache_writing(true);
+    let bootstrap = Bootstrap::new(config)
+        .await
+        .expect("Failed to create bootstrap");
+    SharedNetwork::offline(bootstrap).client(EvmNetwork::ArbitrumOne, strategy)
+}
@@ -47,7 +47,7 @@ async fn pay_for_content_addresses(
     // Form quotes payment transaction data
     let pay_for_quotes_calldata = autonomi::client::external_signer::pay_for_quotes_calldata(
         wallet.network(),
-        quote_payments.into_iter(),
+        quote_payments,
     )?;
 
     // Init an external wallet provider. In the webapp, this would be MetaMask for example
@@ -7,10 +7,14 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::Network;
-use crate::common::{Address, Amount, Calldata, QuoteHash, QuotePayment, U256};
+use crate::common::{Address, Amount, Calldata, QuoteHash, QuotePayment, TxHash, U256};
 use crate::contract::network_token::{self, NetworkToken};
 use crate::contract::payment_vault::MAX_TRANSFERS_PER_TRANSACTION;
+use crate::contract::payment_vault::interface::IPaymentVault;
 use crate::utils::http_provider;
+use alloy::network::ReceiptResponse;
+use alloy::providers::Provider;
+use alloy::transports::{RpcError, TransportErrorKind};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -20,6 +24,14 @@ pub enum Error {
     NetworkTokenContract(#[from] network_token::Error),
     #[error("Data payments contract error: {0}")]
     DataPaymentsContract(#[from] crate::contract::payment_vault::error::Error),
+    #[error("RPC error: {0}")]
+    Rpc(#[from] RpcError<TransportErrorKind>),
+    #[error("Transaction {0} was not found, it may not be mined yet")]
+    TransactionNotFound(TxHash),
+    #[error("Transaction {0} failed")]
+    TransactionFailed(TxHash),
+    #[error("Transaction {0} was not sent to the payment vault")]
+    NotAPaymentTransaction(TxHash),
 }
 
 /// Approve an address / smart contract to spend this wallet's payment tokens.
@@ -96,3 +108,34 @@ pub fn pay_for_quotes_calldata<T: IntoIterator<Item = QuotePayment>>(
         approve_amount,
     })
 }
+
+/// The quote hashes paid by a transaction sent to the payment vault, as recorded in its events.
+///
+/// Fails if the transaction is not mined yet, reverted or was sent to another contract.
+pub async fn paid_quote_hashes(
+    network: &Network,
+    tx_hash: TxHash,
+) -> Result<Vec<QuoteHash>, Error> {
+    let provider = http_provider(network.rpc_url().clone());
+    let receipt = provider
+        .get_transaction_receipt(tx_hash)
+        .await?
+        .ok_or(Error::TransactionNotFound(tx_hash))?;
+    if !receipt.status() {
+        return Err(Error::TransactionFailed(tx_hash));
+    }
+    let payment_vault = *network.data_payments_address();
+    if receipt.to() != Some(payment_vault) {
+        return Err(Error::NotAPaymentTransaction(tx_hash));
+    }
+
+    let quote_hashes = receipt
+        .inner
+        .logs()
+        .iter()
+        .filter(|log| log.address() == payment_vault)
+        .filter_map(|log| log.log_decode::<IPaymentVault::DataPaymentMade>().ok())
+        .map(|log| log.inner.data.quoteHash)
+        .collect();
+    Ok(quote_hashes)
+}